@@ -0,0 +1,318 @@
+//! Node clustering for level-of-detail rendering
+//!
+//! Groups nodes by domain, by depth band, or by community (label
+//! propagation over the undirected graph). Clusterings can be intersected with
+//! [`Clustering::refine`] to build a nested hierarchy, coarse to fine:
+//!
+//! ```text
+//! domain  ⊇  domain × depth band  ⊇  domain × depth band × community
+//! ```
+
+use crate::graph::GraphStore;
+use std::collections::HashMap;
+
+/// Maximum label propagation rounds before giving up on convergence
+const MAX_PROPAGATION_ROUNDS: usize = 20;
+
+/// Assignment of every node to a cluster
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clustering {
+    /// Cluster index of each node, in `GraphStore` node order
+    pub assignment: Vec<usize>,
+    /// Human-readable label of each cluster
+    pub labels: Vec<String>,
+}
+
+impl Clustering {
+    /// Builds a clustering from one key per node
+    ///
+    /// Clusters are numbered in order of first appearance.
+    fn from_keys(keys: Vec<String>) -> Self {
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let mut labels = Vec::new();
+
+        let assignment = keys
+            .into_iter()
+            .map(|key| {
+                *index.entry(key.clone()).or_insert_with(|| {
+                    labels.push(key);
+                    labels.len() - 1
+                })
+            })
+            .collect();
+
+        Self { assignment, labels }
+    }
+
+    /// Returns the number of clusters
+    #[must_use]
+    pub const fn cluster_count(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Returns the node indices belonging to a cluster
+    #[must_use]
+    pub fn members(&self, cluster: usize) -> Vec<usize> {
+        self.assignment
+            .iter()
+            .enumerate()
+            .filter(|(_, &c)| c == cluster)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Intersects this clustering with a finer one
+    ///
+    /// Every resulting cluster lies entirely within one cluster of `self`, so
+    /// repeated refinement yields a nested hierarchy.
+    #[must_use]
+    pub fn refine(&self, other: &Self) -> Self {
+        let keys = self
+            .assignment
+            .iter()
+            .zip(&other.assignment)
+            .map(|(&a, &b)| format!("{} / {}", self.labels[a], other.labels[b]))
+            .collect();
+
+        Self::from_keys(keys)
+    }
+}
+
+/// Clusters nodes by their primary domain
+#[must_use]
+pub fn cluster_by_domain(graph: &GraphStore) -> Clustering {
+    Clustering::from_keys(graph.nodes.iter().map(|node| node.domain.clone()).collect())
+}
+
+/// Clusters nodes into bands of `band_size` consecutive depths
+///
+/// # Arguments
+///
+/// * `graph` - Graph store
+/// * `depths` - Node depths from `compute_depths`
+/// * `band_size` - Number of depth levels per band (0 is treated as 1)
+#[must_use]
+#[allow(clippy::implicit_hasher)]
+pub fn cluster_by_depth_band(
+    graph: &GraphStore,
+    depths: &HashMap<String, u32>,
+    band_size: u32,
+) -> Clustering {
+    let band_size = band_size.max(1);
+
+    Clustering::from_keys(
+        graph
+            .nodes
+            .iter()
+            .map(|node| {
+                let band = depths.get(&node.id).copied().unwrap_or(0) / band_size;
+                let first = band * band_size;
+                format!("depth {first}-{}", first + band_size - 1)
+            })
+            .collect(),
+    )
+}
+
+/// Clusters nodes into communities by label propagation
+///
+/// Edges are treated as undirected. Nodes are visited in index order and
+/// adopt the most frequent label among their neighbours (ties go to the
+/// current label, then the largest), which makes the result deterministic.
+#[must_use]
+pub fn cluster_by_community(graph: &GraphStore) -> Clustering {
+    let node_count = graph.nodes.len();
+    let mut labels: Vec<usize> = (0..node_count).collect();
+
+    for _ in 0..MAX_PROPAGATION_ROUNDS {
+        let mut changed = false;
+
+        for idx in 0..node_count {
+            let mut counts: HashMap<usize, usize> = HashMap::new();
+            for &neighbor in graph.out_edges[idx].iter().chain(&graph.in_edges[idx]) {
+                if neighbor != idx {
+                    *counts.entry(labels[neighbor]).or_default() += 1;
+                }
+            }
+
+            let Some(&best_count) = counts.values().max() else {
+                continue;
+            };
+            if counts.get(&labels[idx]) == Some(&best_count) {
+                continue;
+            }
+
+            let best = counts
+                .iter()
+                .filter(|(_, &count)| count == best_count)
+                .map(|(&label, _)| label)
+                .max()
+                .unwrap_or(labels[idx]);
+            labels[idx] = best;
+            changed = true;
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    // Renumber communities densely in order of first appearance
+    let mut dense: HashMap<usize, usize> = HashMap::new();
+    let keys = labels
+        .iter()
+        .map(|label| {
+            let next = dense.len();
+            format!("community {}", dense.entry(*label).or_insert(next))
+        })
+        .collect();
+
+    Clustering::from_keys(keys)
+}
+
+/// Builds the standard nested LOD hierarchy, coarsest first
+///
+/// Levels: domain, domain × depth band, domain × depth band × community.
+///
+/// # Arguments
+///
+/// * `graph` - Graph store with adjacency built
+/// * `depths` - Node depths from `compute_depths`
+/// * `band_size` - Number of depth levels per band
+#[must_use]
+#[allow(clippy::implicit_hasher)]
+pub fn build_cluster_hierarchy(
+    graph: &GraphStore,
+    depths: &HashMap<String, u32>,
+    band_size: u32,
+) -> Vec<Clustering> {
+    let domain = cluster_by_domain(graph);
+    let banded = domain.refine(&cluster_by_depth_band(graph, depths, band_size));
+    let community = banded.refine(&cluster_by_community(graph));
+
+    vec![domain, banded, community]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Edge, Node};
+    use crate::layout::depth::compute_depths;
+
+    fn create_test_node(id: &str, domain: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            r#type: "proposition".to_string(),
+            domain: domain.to_string(),
+            title: format!("Node {id}"),
+            content: None,
+            formal: None,
+            tags: vec![],
+            metadata: serde_json::Value::Null,
+            sources: vec![],
+            created: None,
+            updated: None,
+            status: None,
+        }
+    }
+
+    fn create_test_edge(from: &str, to: &str) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            relation: "supports".to_string(),
+            domain: "philosophy".to_string(),
+            weight: Some(0.9),
+            metadata: None,
+        }
+    }
+
+    /// Two triangles (a,b,c) and (d,e,f) joined by a single edge c → d
+    fn build_two_communities() -> GraphStore {
+        let mut store = GraphStore::new();
+        for id in ["a", "b", "c"] {
+            store.add_node(create_test_node(id, "philosophy"));
+        }
+        for id in ["d", "e", "f"] {
+            store.add_node(create_test_node(id, "mathematics"));
+        }
+        for (from, to) in [
+            ("a", "b"),
+            ("b", "c"),
+            ("a", "c"),
+            ("d", "e"),
+            ("e", "f"),
+            ("d", "f"),
+            ("c", "d"),
+        ] {
+            store.add_edge(create_test_edge(from, to));
+        }
+        store.build_adjacency();
+        store
+    }
+
+    #[test]
+    fn test_cluster_by_domain() {
+        let store = build_two_communities();
+        let clustering = cluster_by_domain(&store);
+
+        assert_eq!(clustering.cluster_count(), 2);
+        assert_eq!(clustering.labels, vec!["philosophy", "mathematics"]);
+        assert_eq!(clustering.members(1), vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_cluster_by_depth_band() {
+        let store = build_two_communities();
+        let depths = compute_depths(&store);
+        // Depths: a=0, b=1, c=2, d=3, e=4, f=5
+        let clustering = cluster_by_depth_band(&store, &depths, 2);
+
+        assert_eq!(
+            clustering.labels,
+            vec!["depth 0-1", "depth 2-3", "depth 4-5"]
+        );
+        assert_eq!(clustering.assignment, vec![0, 0, 1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn test_cluster_by_community_separates_triangles() {
+        let store = build_two_communities();
+        let clustering = cluster_by_community(&store);
+
+        let a = clustering.assignment[0];
+        let f = clustering.assignment[5];
+        assert_eq!(clustering.assignment[1], a);
+        assert_eq!(clustering.assignment[4], f);
+        assert_ne!(a, f);
+    }
+
+    #[test]
+    fn test_cluster_by_community_isolated_nodes() {
+        let mut store = GraphStore::new();
+        store.add_node(create_test_node("a", "philosophy"));
+        store.add_node(create_test_node("b", "philosophy"));
+        store.build_adjacency();
+
+        assert_eq!(cluster_by_community(&store).cluster_count(), 2);
+    }
+
+    #[test]
+    fn test_hierarchy_is_nested() {
+        let store = build_two_communities();
+        let depths = compute_depths(&store);
+        let levels = build_cluster_hierarchy(&store, &depths, 3);
+
+        assert_eq!(levels.len(), 3);
+        for pair in levels.windows(2) {
+            let (coarse, fine) = (&pair[0], &pair[1]);
+            assert!(fine.cluster_count() >= coarse.cluster_count());
+
+            // Every fine cluster maps to exactly one coarse cluster
+            for cluster in 0..fine.cluster_count() {
+                let members = fine.members(cluster);
+                let parent = coarse.assignment[members[0]];
+                assert!(members.iter().all(|&m| coarse.assignment[m] == parent));
+            }
+        }
+    }
+}
@@ -6,7 +6,13 @@
 //! High load-bearing nodes are "pillars" - remove them and large parts of the graph collapse.
 
 use crate::graph::GraphStore;
-use std::collections::{HashSet, VecDeque};
+use std::collections::HashMap;
+
+/// Epistemic relations: a node with none of these incoming is a foundation
+const EPISTEMIC_RELATIONS: [&str; 4] = ["supports", "proves", "entails", "predicts"];
+
+/// Marks an unset index in the dominator computation
+const NONE: usize = usize::MAX;
 
 /// Compute load-bearing score for a node
 ///
@@ -19,6 +25,9 @@ use std::collections::{HashSet, VecDeque};
 /// - 0.2-0.5: Critical (20-50% depends)
 /// - >0.5: Foundational (>50% depends - likely a depth-0 axiom)
 ///
+/// Each call scores the whole graph; use [`compute_all_load_bearing`] to
+/// score more than one node.
+///
 /// # Example
 /// ```
 /// use truth_mines_engine::graph::GraphStore;
@@ -31,197 +40,211 @@ use std::collections::{HashSet, VecDeque};
 /// ```
 #[must_use]
 pub fn compute_load_bearing(graph: &GraphStore, node_id: &str) -> f32 {
-    let total_nodes = graph.node_count();
-    if total_nodes <= 1 {
-        return 0.0;
-    }
-
-    // Get node index
-    let Some(&node_idx) = graph.id_to_idx.get(node_id) else {
-        return 0.0; // Node doesn't exist
-    };
-
-    // Find all descendants (nodes reachable via outgoing edges)
-    let descendants = find_descendants(graph, node_idx);
-
-    if descendants.is_empty() {
-        return 0.0; // Leaf node, no descendants
-    }
-
-    // Count how many descendants would lose ALL foundation paths
-    let orphaned_count = descendants
-        .iter()
-        .filter(|&&desc_idx| would_lose_all_foundations(graph, desc_idx, node_idx))
-        .count();
-
-    // Normalize by total graph size
-    orphaned_count as f32 / total_nodes as f32
+    graph
+        .id_to_idx
+        .get(node_id)
+        .map_or(0.0, |&idx| load_bearing_scores(graph)[idx])
 }
 
-/// Find all descendants of a node (DFS traversal)
-fn find_descendants(graph: &GraphStore, start_idx: usize) -> HashSet<usize> {
-    let mut visited = HashSet::new();
-    let mut stack = vec![start_idx];
-
-    while let Some(idx) = stack.pop() {
-        if !visited.insert(idx) {
-            continue; // Already visited
-        }
-
-        // Add all outgoing neighbors
-        if let Some(neighbors) = graph.out_edges.get(idx) {
-            for &neighbor_idx in neighbors {
-                if !visited.contains(&neighbor_idx) {
-                    stack.push(neighbor_idx);
-                }
-            }
-        }
-    }
-
-    // Remove the start node itself
-    visited.remove(&start_idx);
-    visited
+/// Computes the load-bearing score of every node in one pass
+///
+/// Same scores as [`compute_load_bearing`], in O(E log N) for the whole
+/// graph.
+#[must_use]
+pub fn compute_all_load_bearing(graph: &GraphStore) -> HashMap<String, f32> {
+    graph
+        .nodes
+        .iter()
+        .zip(load_bearing_scores(graph))
+        .map(|(node, score)| (node.id.clone(), score))
+        .collect()
 }
 
-/// Check if a descendant would lose ALL foundation paths if removed_node is removed
+/// Load-bearing scores in node order
 ///
-/// A "foundation path" is a path from a depth-0 node to the descendant.
-/// If ALL such paths go through removed_node, then the descendant would be orphaned.
-fn would_lose_all_foundations(
-    graph: &GraphStore,
-    descendant_idx: usize,
-    removed_node_idx: usize,
-) -> bool {
-    // Find foundation nodes (depth 0 - no incoming epistemic edges)
-    let foundation_indices = find_foundation_nodes(graph);
-
-    if foundation_indices.is_empty() {
-        return false; // No foundations = can't lose what doesn't exist
+/// A descendant loses all foundation paths when a node is removed exactly
+/// when that node dominates it in the graph rooted at a virtual node with an
+/// edge to every foundation. The score is the number of nodes a node strictly
+/// dominates, so one dominator tree (Lengauer-Tarjan) scores every node.
+#[allow(clippy::cast_precision_loss)]
+fn load_bearing_scores(graph: &GraphStore) -> Vec<f32> {
+    let total_nodes = graph.node_count();
+    if total_nodes <= 1 {
+        return vec![0.0; total_nodes];
     }
 
-    // For each foundation, check if there's a path to descendant that DOESN'T go through removed_node
-    for &foundation_idx in &foundation_indices {
-        if has_path_avoiding_node(graph, foundation_idx, descendant_idx, removed_node_idx) {
-            return false; // Found at least one path that doesn't use removed_node
+    // Vertex `total_nodes` is the virtual root
+    let root = total_nodes;
+    let mut successors = vec![Vec::new(); total_nodes + 1];
+    successors[root] = find_foundation_nodes(graph);
+    for edge in &graph.edges {
+        if let (Some(&from), Some(&to)) = (
+            graph.id_to_idx.get(&edge.from),
+            graph.id_to_idx.get(&edge.to),
+        ) {
+            successors[from].push(to);
         }
     }
 
-    // All foundation paths (if any exist) go through removed_node
-    // But we need to verify at least one path exists from foundations to descendant
-    // Otherwise it was already orphaned
+    let (idom, preorder) = dominator_tree(&successors, root);
 
-    // Check if there's ANY path from foundations to descendant (with removed_node present)
-    for &foundation_idx in &foundation_indices {
-        if has_path(graph, foundation_idx, descendant_idx) {
-            return true; // Had a path, would lose it
-        }
+    // Dominator subtree sizes; a vertex's immediate dominator precedes it in
+    // preorder, so walking it backwards finishes every subtree first
+    let mut subtree = vec![1_usize; total_nodes + 1];
+    for &v in preorder.iter().skip(1).rev() {
+        subtree[idom[v]] += subtree[v];
     }
 
-    false // Was already orphaned, removing node doesn't change that
+    (0..total_nodes)
+        .map(|v| {
+            if idom[v] == NONE {
+                0.0 // Not reachable from any foundation
+            } else {
+                (subtree[v] - 1) as f32 / total_nodes as f32
+            }
+        })
+        .collect()
 }
 
 /// Find foundation nodes (depth 0: no incoming epistemic edges)
 ///
 /// Epistemic edges: supports, proves, entails, predicts
 fn find_foundation_nodes(graph: &GraphStore) -> Vec<usize> {
-    let epistemic_relations = HashSet::from(["supports", "proves", "entails", "predicts"]);
+    let mut supported = vec![false; graph.node_count()];
+    for edge in &graph.edges {
+        if EPISTEMIC_RELATIONS.contains(&edge.relation.as_str()) {
+            if let Some(&to) = graph.id_to_idx.get(&edge.to) {
+                supported[to] = true;
+            }
+        }
+    }
 
     (0..graph.node_count())
-        .filter(|&idx| {
-            // Count incoming epistemic edges
-            let incoming_epistemic = graph
-                .edges
-                .iter()
-                .filter(|e| {
-                    graph.id_to_idx.get(&e.to) == Some(&idx) && epistemic_relations.contains(e.relation.as_str())
-                })
-                .count();
-
-            incoming_epistemic == 0
-        })
+        .filter(|&idx| !supported[idx])
         .collect()
 }
 
-/// Check if there's a path from start to end (BFS)
-fn has_path(graph: &GraphStore, start_idx: usize, end_idx: usize) -> bool {
-    if start_idx == end_idx {
-        return true;
+/// Immediate dominators of the vertices reachable from `root`
+///
+/// Returns `idom` (`NONE` for the root and unreachable vertices) and the
+/// reachable vertices in DFS preorder, starting with `root`.
+/// Lengauer-Tarjan with path compression, iterative throughout so that long
+/// chains do not overflow the stack.
+fn dominator_tree(successors: &[Vec<usize>], root: usize) -> (Vec<usize>, Vec<usize>) {
+    let vertex_count = successors.len();
+
+    // DFS numbering; everything below works on DFS numbers
+    let mut number = vec![NONE; vertex_count];
+    let mut preorder = Vec::new();
+    let mut parent = Vec::new();
+    let mut stack = vec![(root, NONE)];
+    while let Some((v, pushed_by)) = stack.pop() {
+        if number[v] != NONE {
+            continue;
+        }
+        number[v] = preorder.len();
+        preorder.push(v);
+        parent.push(pushed_by);
+        for &w in successors[v].iter().rev() {
+            if number[w] == NONE {
+                stack.push((w, number[v]));
+            }
+        }
     }
 
-    let mut visited = HashSet::new();
-    let mut queue = VecDeque::new();
-    queue.push_back(start_idx);
-    visited.insert(start_idx);
-
-    while let Some(idx) = queue.pop_front() {
-        if let Some(neighbors) = graph.out_edges.get(idx) {
-            for &neighbor_idx in neighbors {
-                if neighbor_idx == end_idx {
-                    return true;
-                }
-
-                if visited.insert(neighbor_idx) {
-                    queue.push_back(neighbor_idx);
-                }
-            }
+    let reached = preorder.len();
+    let mut predecessors = vec![Vec::new(); reached];
+    for (n, &v) in preorder.iter().enumerate() {
+        for &w in &successors[v] {
+            predecessors[number[w]].push(n);
         }
     }
 
-    false
-}
+    let mut forest = Forest {
+        semi: (0..reached).collect(),
+        ancestor: vec![NONE; reached],
+        label: (0..reached).collect(),
+    };
+    let mut idom = vec![NONE; reached];
+    let mut bucket = vec![Vec::new(); reached];
 
-/// Check if there's a path from start to end that avoids a specific node (BFS)
-fn has_path_avoiding_node(
-    graph: &GraphStore,
-    start_idx: usize,
-    end_idx: usize,
-    avoid_idx: usize,
-) -> bool {
-    if start_idx == end_idx {
-        return true;
+    for w in (1..reached).rev() {
+        for &v in &predecessors[w] {
+            let u = forest.eval(v);
+            forest.semi[w] = forest.semi[w].min(forest.semi[u]);
+        }
+        bucket[forest.semi[w]].push(w);
+
+        let p = parent[w];
+        forest.ancestor[w] = p;
+        for v in std::mem::take(&mut bucket[p]) {
+            let u = forest.eval(v);
+            idom[v] = if forest.semi[u] < forest.semi[v] {
+                u
+            } else {
+                p
+            };
+        }
     }
-
-    if start_idx == avoid_idx || end_idx == avoid_idx {
-        return false; // Can't reach if start/end is the avoided node
+    for w in 1..reached {
+        if idom[w] != forest.semi[w] {
+            idom[w] = idom[idom[w]];
+        }
     }
 
-    let mut visited = HashSet::new();
-    let mut queue = VecDeque::new();
-    queue.push_back(start_idx);
-    visited.insert(start_idx);
+    let mut dominators = vec![NONE; vertex_count];
+    for w in 1..reached {
+        dominators[preorder[w]] = preorder[idom[w]];
+    }
+    (dominators, preorder)
+}
 
-    while let Some(idx) = queue.pop_front() {
-        if let Some(neighbors) = graph.out_edges.get(idx) {
-            for &neighbor_idx in neighbors {
-                if neighbor_idx == avoid_idx {
-                    continue; // Skip the avoided node
-                }
+/// Link-eval forest of the Lengauer-Tarjan algorithm, on DFS numbers
+struct Forest {
+    semi: Vec<usize>,
+    ancestor: Vec<usize>,
+    label: Vec<usize>,
+}
 
-                if neighbor_idx == end_idx {
-                    return true;
-                }
+impl Forest {
+    /// Returns the vertex with the smallest semidominator on the forest path
+    /// above `v`
+    fn eval(&mut self, v: usize) -> usize {
+        if self.ancestor[v] == NONE {
+            return v;
+        }
 
-                if visited.insert(neighbor_idx) {
-                    queue.push_back(neighbor_idx);
-                }
+        // Compress the path, nodes nearest the forest root first
+        let mut path = Vec::new();
+        let mut u = v;
+        while self.ancestor[self.ancestor[u]] != NONE {
+            path.push(u);
+            u = self.ancestor[u];
+        }
+        while let Some(x) = path.pop() {
+            let a = self.ancestor[x];
+            if self.semi[self.label[a]] < self.semi[self.label[x]] {
+                self.label[x] = self.label[a];
             }
+            self.ancestor[x] = self.ancestor[a];
         }
-    }
 
-    false
+        self.label[v]
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::graph::{Edge, Node};
+    use std::collections::{HashSet, VecDeque};
 
     fn create_test_node(id: &str, node_type: &str) -> Node {
         Node {
             id: id.to_string(),
             r#type: node_type.to_string(),
             domain: "mathematics".to_string(),
-            title: format!("Test {}", id),
+            title: format!("Test {id}"),
             content: None,
             formal: None,
             tags: vec![],
@@ -229,6 +252,7 @@ mod tests {
             sources: vec![],
             created: None,
             updated: None,
+            status: None,
         }
     }
 
@@ -243,6 +267,156 @@ mod tests {
         }
     }
 
+    /// The original per-node definition, kept as a reference: descendants of
+    /// the node that some foundation reaches only through it
+    #[allow(clippy::cast_precision_loss)]
+    fn reference_load_bearing(graph: &GraphStore, node_id: &str) -> f32 {
+        let total_nodes = graph.node_count();
+        if total_nodes <= 1 {
+            return 0.0;
+        }
+        let Some(&node_idx) = graph.id_to_idx.get(node_id) else {
+            return 0.0;
+        };
+
+        let orphaned_count = find_descendants(graph, node_idx)
+            .iter()
+            .filter(|&&desc_idx| would_lose_all_foundations(graph, desc_idx, node_idx))
+            .count();
+        orphaned_count as f32 / total_nodes as f32
+    }
+
+    /// Find all descendants of a node (DFS traversal)
+    fn find_descendants(graph: &GraphStore, start_idx: usize) -> HashSet<usize> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![start_idx];
+
+        while let Some(idx) = stack.pop() {
+            if !visited.insert(idx) {
+                continue; // Already visited
+            }
+
+            // Add all outgoing neighbors
+            if let Some(neighbors) = graph.out_edges.get(idx) {
+                for &neighbor_idx in neighbors {
+                    if !visited.contains(&neighbor_idx) {
+                        stack.push(neighbor_idx);
+                    }
+                }
+            }
+        }
+
+        // Remove the start node itself
+        visited.remove(&start_idx);
+        visited
+    }
+
+    /// Check if a descendant would lose ALL foundation paths if `removed_node` is removed
+    ///
+    /// A "foundation path" is a path from a depth-0 node to the descendant.
+    /// If ALL such paths go through `removed_node`, then the descendant would be orphaned.
+    fn would_lose_all_foundations(
+        graph: &GraphStore,
+        descendant_idx: usize,
+        removed_node_idx: usize,
+    ) -> bool {
+        // Find foundation nodes (depth 0 - no incoming epistemic edges)
+        let foundation_indices = find_foundation_nodes(graph);
+
+        if foundation_indices.is_empty() {
+            return false; // No foundations = can't lose what doesn't exist
+        }
+
+        // For each foundation, check if there's a path to descendant that DOESN'T go through removed_node
+        for &foundation_idx in &foundation_indices {
+            if has_path_avoiding_node(graph, foundation_idx, descendant_idx, removed_node_idx) {
+                return false; // Found at least one path that doesn't use removed_node
+            }
+        }
+
+        // All foundation paths (if any exist) go through removed_node
+        // But we need to verify at least one path exists from foundations to descendant
+        // Otherwise it was already orphaned
+
+        // Check if there's ANY path from foundations to descendant (with removed_node present)
+        for &foundation_idx in &foundation_indices {
+            if has_path(graph, foundation_idx, descendant_idx) {
+                return true; // Had a path, would lose it
+            }
+        }
+
+        false // Was already orphaned, removing node doesn't change that
+    }
+
+    /// Check if there's a path from start to end (BFS)
+    fn has_path(graph: &GraphStore, start_idx: usize, end_idx: usize) -> bool {
+        if start_idx == end_idx {
+            return true;
+        }
+
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(start_idx);
+        visited.insert(start_idx);
+
+        while let Some(idx) = queue.pop_front() {
+            if let Some(neighbors) = graph.out_edges.get(idx) {
+                for &neighbor_idx in neighbors {
+                    if neighbor_idx == end_idx {
+                        return true;
+                    }
+
+                    if visited.insert(neighbor_idx) {
+                        queue.push_back(neighbor_idx);
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Check if there's a path from start to end that avoids a specific node (BFS)
+    fn has_path_avoiding_node(
+        graph: &GraphStore,
+        start_idx: usize,
+        end_idx: usize,
+        avoid_idx: usize,
+    ) -> bool {
+        if start_idx == end_idx {
+            return true;
+        }
+
+        if start_idx == avoid_idx || end_idx == avoid_idx {
+            return false; // Can't reach if start/end is the avoided node
+        }
+
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(start_idx);
+        visited.insert(start_idx);
+
+        while let Some(idx) = queue.pop_front() {
+            if let Some(neighbors) = graph.out_edges.get(idx) {
+                for &neighbor_idx in neighbors {
+                    if neighbor_idx == avoid_idx {
+                        continue; // Skip the avoided node
+                    }
+
+                    if neighbor_idx == end_idx {
+                        return true;
+                    }
+
+                    if visited.insert(neighbor_idx) {
+                        queue.push_back(neighbor_idx);
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
     #[test]
     fn test_load_bearing_isolated_node() {
         let mut graph = GraphStore::new();
@@ -389,6 +563,73 @@ mod tests {
         assert!((load - 0.333).abs() < 0.01);
     }
 
+    #[test]
+    fn test_all_load_bearing_matches_reference() {
+        // Pseudo-random graphs with cycles, several foundations, unreachable
+        // nodes and non-epistemic edges
+        let relations = ["supports", "proves", "defines", "attacks", "entails"];
+        for seed in 0..20_u64 {
+            let mut graph = GraphStore::new();
+            for i in 0..30 {
+                graph.add_node(create_test_node(&format!("n{i}"), "theorem"));
+            }
+            let mut state = seed;
+            let mut next = |bound: u64| {
+                state = state
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1_442_695_040_888_963_407);
+                usize::try_from((state >> 33) % bound).unwrap()
+            };
+            for _ in 0..45 {
+                let (from, to, relation) = (next(30), next(30), relations[next(5)]);
+                graph.add_edge(create_test_edge(
+                    &format!("n{from}"),
+                    &format!("n{to}"),
+                    relation,
+                ));
+            }
+            graph.build_adjacency();
+
+            let scores = compute_all_load_bearing(&graph);
+            for node in graph.nodes() {
+                let expected = reference_load_bearing(&graph, &node.id);
+                assert!(
+                    (scores[&node.id] - expected).abs() < 1e-6,
+                    "seed {seed}, {}: {} != {expected}",
+                    node.id,
+                    scores[&node.id]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_load_bearing_long_chain() {
+        // Used to be quartic: a 400-node chain did not finish in minutes
+        let count = 100_000;
+        let mut graph = GraphStore::new();
+        for i in 0..count {
+            graph.add_node(create_test_node(&format!("n{i}"), "theorem"));
+        }
+        for i in 1..count {
+            graph.add_edge(create_test_edge(
+                &format!("n{}", i - 1),
+                &format!("n{i}"),
+                "supports",
+            ));
+        }
+        graph.build_adjacency();
+
+        let start = std::time::Instant::now();
+        let scores = compute_all_load_bearing(&graph);
+        let elapsed = start.elapsed();
+
+        assert!((scores["n0"] - 0.999_99).abs() < 1e-6);
+        assert!((scores["n50000"] - 0.499_99).abs() < 1e-6);
+        assert_eq!(scores[&format!("n{}", count - 1)], 0.0);
+        assert!(elapsed.as_secs() < 5, "took {elapsed:?}");
+    }
+
     #[test]
     fn test_find_foundation_nodes() {
         let mut graph = GraphStore::new();
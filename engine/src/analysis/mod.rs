@@ -2,8 +2,11 @@
 //!
 //! This module contains algorithms for computing graph metrics:
 //! - Load-bearing analysis: identifies structurally critical nodes
+//! - Clustering: domain, depth-band and community groupings for LOD rendering
 //! - Tension metrics: (computed in TypeScript, but could be moved here)
 
+pub mod clustering;
 pub mod load_bearing;
 
-pub use load_bearing::compute_load_bearing;
+pub use clustering::{build_cluster_hierarchy, Clustering};
+pub use load_bearing::{compute_all_load_bearing, compute_load_bearing};
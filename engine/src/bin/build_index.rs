@@ -0,0 +1,78 @@
+//! Builds `dist/manifest.json` and `dist/graph.json` for the web app
+//!
+//! Usage: `build_index [GRAPH_DIR] [--out DIST_DIR]`
+//!
+//! `GRAPH_DIR` defaults to the current directory and `DIST_DIR` to
+//! `GRAPH_DIR/dist`. Files that fail to load are reported as warnings and
+//! left out of the index, as `scripts/build_index.py` did.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use truth_mines_engine::index::{build_repository_index, write_index, IndexOptions};
+
+const USAGE: &str = "Usage: build_index [GRAPH_DIR] [--out DIST_DIR]";
+
+fn main() -> ExitCode {
+    let mut graph_dir = None;
+    let mut dist_dir = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            "--out" => {
+                let Some(dir) = args.next() else {
+                    eprintln!("--out requires a directory\n{USAGE}");
+                    return ExitCode::FAILURE;
+                };
+                dist_dir = Some(PathBuf::from(dir));
+            }
+            _ if graph_dir.is_none() && !arg.starts_with('-') => {
+                graph_dir = Some(PathBuf::from(arg));
+            }
+            _ => {
+                eprintln!("Unexpected argument '{arg}'\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let graph_dir = graph_dir.unwrap_or_else(|| PathBuf::from("."));
+    let dist_dir = dist_dir.unwrap_or_else(|| graph_dir.join("dist"));
+
+    println!("Loading repository from {}...", graph_dir.display());
+    let (index, diagnostics) = build_repository_index(&graph_dir, &IndexOptions::default());
+    for diagnostic in &diagnostics {
+        eprintln!("Warning: {diagnostic}");
+    }
+    println!(
+        "Loaded {} nodes and {} edges",
+        index.manifest.stats.total_nodes, index.manifest.stats.total_edges
+    );
+
+    let (manifest_path, graph_path) = match write_index(&index, &dist_dir) {
+        Ok(paths) => paths,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("✓ Created {}", manifest_path.display());
+    println!("✓ Created {}", graph_path.display());
+
+    let stats = &index.manifest.stats;
+    println!();
+    println!("Summary:");
+    println!("  Total nodes: {}", stats.total_nodes);
+    println!("  Total edges: {}", stats.total_edges);
+    if !stats.by_domain.is_empty() {
+        println!("  By domain: {:?}", stats.by_domain);
+    }
+    if !stats.by_type.is_empty() {
+        println!("  By type: {:?}", stats.by_type);
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,73 @@
+//! Git merge driver for node and edge files
+//!
+//! Usage: `truth-mines-merge BASE OURS THEIRS [PATH]`
+//!
+//! Merges the three versions of one `nodes/*.json` or `edges/*.jsonl` file
+//! field by field (see `truth_mines_engine::merge`) and writes the result
+//! over `OURS`. `PATH` is the file's path in the repository, used to tell
+//! node files from edge files (default: `OURS`). Exits 1 if there were
+//! conflicts, which are listed on standard error; the written file then
+//! holds both sides of each conflicting line between git-style conflict
+//! markers, to be resolved like any other git conflict. Register it with:
+//!
+//! ```text
+//! git config merge.truth-mines.name "Truth Mines semantic merge"
+//! git config merge.truth-mines.driver "truth-mines-merge %O %A %B %P"
+//! ```
+//!
+//! and assign it in `.gitattributes` (`nodes/**/*.json merge=truth-mines`).
+
+use std::path::Path;
+use std::process::ExitCode;
+use truth_mines_engine::merge::{merge_edge_files, merge_node_files};
+
+const USAGE: &str = "Usage: truth-mines-merge BASE OURS THEIRS [PATH]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+    let (base, ours, theirs, path) = match args.as_slice() {
+        [base, ours, theirs] => (base, ours, theirs, ours),
+        [base, ours, theirs, path] => (base, ours, theirs, path),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(Path::new(base), Path::new(ours), Path::new(theirs), path) {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("truth-mines-merge: {path}: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(base: &Path, ours: &Path, theirs: &Path, path: &str) -> Result<ExitCode, String> {
+    let read = |file: &Path| {
+        std::fs::read_to_string(file).map_err(|e| format!("Failed to read {}: {e}", file.display()))
+    };
+    let (base_source, our_source, their_source) = (read(base)?, read(ours)?, read(theirs)?);
+
+    let merged = match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+        Some("json") => merge_node_files(&base_source, &our_source, &their_source),
+        Some("jsonl") => merge_edge_files(&base_source, &our_source, &their_source),
+        _ => return Err("only .json node files and .jsonl edge files can be merged".to_string()),
+    }
+    .map_err(|e| e.to_string())?;
+
+    std::fs::write(ours, merged.with_conflict_markers())
+        .map_err(|e| format!("Failed to write {}: {e}", ours.display()))?;
+
+    if merged.conflicts.is_empty() {
+        return Ok(ExitCode::SUCCESS);
+    }
+    for conflict in &merged.conflicts {
+        eprintln!("{path}: conflict: {conflict}");
+    }
+    Ok(ExitCode::FAILURE)
+}
@@ -0,0 +1,844 @@
+//! Subcommand implementations
+
+use crate::{Cli, Command, ExportFormat, WeightArg};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::Path;
+use std::process::{ExitCode, Stdio};
+use truth_mines_engine::analysis::compute_all_load_bearing;
+use truth_mines_engine::diff::{diff_with, DiffOptions, FieldChange, GraphDiff};
+use truth_mines_engine::export::{write_dot, write_gexf, write_graphml, ExportOptions};
+use truth_mines_engine::format::{check_repository, format_repository};
+use truth_mines_engine::graph::ids::is_valid_id;
+use truth_mines_engine::graph::ids::{domain_prefix, IdOptions, IdStrategy};
+use truth_mines_engine::graph::refactor::{RefactorReport, SplitPart, WeightPolicy};
+use truth_mines_engine::index::{manifest_stats, IndexOptions};
+use truth_mines_engine::layout::depth::compute_depths;
+use truth_mines_engine::layout::truth_mine::compute_truth_mine_layout;
+use truth_mines_engine::lint::{lint, LintConfig, Severity, RULES};
+use truth_mines_engine::parsers::loader::load_repository_lenient;
+use truth_mines_engine::parsers::toon::{write_toon_document, ToonDocument};
+use truth_mines_engine::parsers::writer::apply_refactor;
+use truth_mines_engine::validate::validate_repository;
+use truth_mines_engine::{Edge, Error, GraphStore, Node};
+
+/// Lint config looked up under the repository root
+const LINT_CONFIG_FILE: &str = "lint.toml";
+
+/// Runs the parsed command
+///
+/// # Errors
+///
+/// Returns a message for the user if the command could not run at all
+pub fn run(cli: &Cli) -> Result<ExitCode, String> {
+    let (root, as_json) = (cli.root.as_path(), cli.json);
+    let success = |()| ExitCode::SUCCESS;
+
+    match &cli.command {
+        Command::Validate => Ok(validate(root, as_json)),
+        Command::Fmt { check } => Ok(fmt(root, *check, as_json)),
+        Command::Lint { config } => run_lint(&load(root), root, config.as_deref(), as_json),
+        Command::Stats => {
+            stats(&load(root), as_json);
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Neighbors { id, depth } => {
+            neighbors(&load(root), id, *depth, as_json).map(success)
+        }
+        Command::Paths {
+            from,
+            to,
+            max_depth,
+        } => paths(&load(root), from, to, *max_depth, as_json).map(success),
+        Command::Depth { id } => depth(&load(root), id, as_json).map(success),
+        Command::LoadBearing { top } => {
+            load_bearing(&load(root), *top, as_json);
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Diff {
+            before,
+            after,
+            threshold,
+        } => {
+            let before = load_version(root, before)?;
+            let after = match after {
+                Some(after) => load_version(root, after)?,
+                None => load(root),
+            };
+            let options = DiffOptions {
+                load_bearing_threshold: *threshold,
+            };
+            print_diff(&diff_with(&before, &after, &options), as_json);
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::NewId {
+            from,
+            domain,
+            prefix,
+            count,
+        } => new_id(
+            &load(root),
+            from.as_deref(),
+            domain.as_deref(),
+            prefix.as_deref(),
+            *count,
+            as_json,
+        )
+        .map(success),
+        Command::Rename { old, new, dry_run } => {
+            refactor(root, *dry_run, as_json, |graph| graph.rename_node(old, new))
+        }
+        Command::Merge {
+            keep,
+            drop,
+            weights,
+            dry_run,
+        } => refactor(root, *dry_run, as_json, |graph| {
+            graph.merge_nodes(keep, drop, weight_policy(*weights))
+        }),
+        Command::Split {
+            id,
+            parts,
+            assignments,
+            dry_run,
+        } => refactor(root, *dry_run, as_json, |graph| {
+            split(graph, id, parts, assignments)
+        }),
+        Command::Export {
+            format,
+            output,
+            domains,
+            around,
+            depth,
+            positions,
+        } => {
+            let graph = select_subgraph(&load(root), domains, around.as_deref(), *depth)?;
+            export(&graph, *format, *positions, output.as_deref()).map(success)
+        }
+    }
+}
+
+/// Loads the repository, warning about anything that failed to load
+fn load(root: &Path) -> GraphStore {
+    let report = load_repository_lenient(root);
+    if !report.is_clean() {
+        eprintln!(
+            "warning: {} problem(s) while loading; run `truth-mines validate` for details",
+            report.diagnostics.len()
+        );
+    }
+    report.into_graph_store()
+}
+
+fn validate(root: &Path, as_json: bool) -> ExitCode {
+    let report = validate_repository(root);
+    let (nodes, edges) = (report.nodes.len(), report.edges.len());
+
+    if as_json {
+        print_json(&json!({
+            "valid": report.is_clean(),
+            "nodes": nodes,
+            "edges": edges,
+            "diagnostics": report.diagnostics.iter().map(error_json).collect::<Vec<_>>(),
+        }));
+    } else {
+        for diagnostic in &report.diagnostics {
+            println!("{diagnostic}");
+        }
+        if report.is_clean() {
+            println!("✓ {nodes} nodes and {edges} edges are valid");
+        } else {
+            println!(
+                "✗ {} problem(s) across {nodes} nodes and {edges} edges",
+                report.diagnostics.len()
+            );
+        }
+    }
+
+    if report.is_clean() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn fmt(root: &Path, check: bool, as_json: bool) -> ExitCode {
+    let report = if check {
+        check_repository(root)
+    } else {
+        format_repository(root)
+    };
+
+    if as_json {
+        print_json(&json!({
+            "checked": report.checked,
+            "changed": report.changed.iter().map(|path| path.display().to_string()).collect::<Vec<_>>(),
+            "diagnostics": report.diagnostics.iter().map(error_json).collect::<Vec<_>>(),
+        }));
+    } else {
+        for diagnostic in &report.diagnostics {
+            eprintln!("{diagnostic}");
+        }
+        for path in &report.changed {
+            println!("{}", path.display());
+        }
+        let verb = if check {
+            "would be reformatted"
+        } else {
+            "reformatted"
+        };
+        println!(
+            "{} of {} file(s) {verb}",
+            report.changed.len(),
+            report.checked
+        );
+    }
+
+    if !report.diagnostics.is_empty() || (check && !report.changed.is_empty()) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn run_lint(
+    graph: &GraphStore,
+    root: &Path,
+    config_path: Option<&Path>,
+    as_json: bool,
+) -> Result<ExitCode, String> {
+    let default_path = root.join(LINT_CONFIG_FILE);
+    let config_path =
+        config_path.or_else(|| default_path.exists().then_some(default_path.as_path()));
+    let config = match config_path {
+        Some(path) => {
+            let toml = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+            LintConfig::from_toml(&toml).map_err(|e| e.with_path(path).to_string())?
+        }
+        None => LintConfig::default(),
+    };
+
+    let diagnostics = lint(graph, &config);
+    if as_json {
+        print_json(&serde_json::to_value(&diagnostics).unwrap_or_default());
+    } else {
+        for diagnostic in &diagnostics {
+            println!("{diagnostic}");
+        }
+        let count = |severity| {
+            diagnostics
+                .iter()
+                .filter(|d| d.severity == severity)
+                .count()
+        };
+        println!(
+            "{} error(s), {} warning(s), {} info across {} rule(s)",
+            count(Severity::Error),
+            count(Severity::Warning),
+            count(Severity::Info),
+            RULES.len()
+        );
+    }
+
+    if diagnostics.iter().any(|d| d.severity == Severity::Error) {
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+fn stats(graph: &GraphStore, as_json: bool) {
+    let stats = manifest_stats(graph);
+    let depths = compute_depths(graph);
+    let max_depth = depths.values().copied().max().unwrap_or(0);
+    let foundations = depths.values().filter(|&&depth| depth == 0).count();
+
+    if as_json {
+        let mut value = serde_json::to_value(&stats).unwrap_or_default();
+        value["max_depth"] = json!(max_depth);
+        value["foundations"] = json!(foundations);
+        print_json(&value);
+        return;
+    }
+
+    println!("Nodes: {}", stats.total_nodes);
+    println!("Edges: {}", stats.total_edges);
+    println!("Foundations: {foundations}");
+    println!("Max depth: {max_depth}");
+    for (title, counts) in [
+        ("By domain", &stats.by_domain),
+        ("By type", &stats.by_type),
+        ("By relation", &stats.by_relation),
+    ] {
+        if counts.is_empty() {
+            continue;
+        }
+        println!("{title}:");
+        for (name, count) in counts {
+            println!("  {name:<24} {count}");
+        }
+    }
+}
+
+fn neighbors(graph: &GraphStore, id: &str, depth: u32, as_json: bool) -> Result<(), String> {
+    require_node(graph, id)?;
+    let found = graph.neighbors(id, depth);
+
+    if as_json {
+        print_json(&Value::Array(found.into_iter().map(node_json).collect()));
+    } else if found.is_empty() {
+        println!("No neighbors within {depth} hop(s) of {id}");
+    } else {
+        for node in found {
+            println!("{}", node_line(node));
+        }
+    }
+    Ok(())
+}
+
+fn paths(
+    graph: &GraphStore,
+    from: &str,
+    to: &str,
+    max_depth: u32,
+    as_json: bool,
+) -> Result<(), String> {
+    require_node(graph, from)?;
+    require_node(graph, to)?;
+    let found = graph.find_paths(from, to, max_depth);
+
+    if as_json {
+        let ids: Vec<Vec<&str>> = found
+            .iter()
+            .map(|path| path.iter().map(|node| node.id.as_str()).collect())
+            .collect();
+        print_json(&json!(ids));
+    } else if found.is_empty() {
+        println!("No path from {from} to {to} within {max_depth} hop(s)");
+    } else {
+        for path in found {
+            let ids: Vec<&str> = path.iter().map(|node| node.id.as_str()).collect();
+            println!("{}", ids.join(" → "));
+        }
+    }
+    Ok(())
+}
+
+fn depth(graph: &GraphStore, id: &str, as_json: bool) -> Result<(), String> {
+    let node = require_node(graph, id)?;
+    let depth = compute_depths(graph).get(id).copied().unwrap_or(0);
+
+    if as_json {
+        print_json(&json!({ "id": id, "depth": depth }));
+    } else {
+        println!("{}: depth {depth}", node_line(node));
+    }
+    Ok(())
+}
+
+fn load_bearing(graph: &GraphStore, top: usize, as_json: bool) {
+    let load_bearing = compute_all_load_bearing(graph);
+    let mut scores: Vec<(&Node, f32)> = graph
+        .nodes()
+        .iter()
+        .map(|node| (node, load_bearing[&node.id]))
+        .collect();
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.id.cmp(&b.0.id)));
+    scores.truncate(top);
+
+    if as_json {
+        print_json(&Value::Array(
+            scores
+                .into_iter()
+                .map(|(node, score)| {
+                    let mut value = node_json(node);
+                    value["load_bearing"] = json!(score);
+                    value
+                })
+                .collect(),
+        ));
+    } else {
+        for (node, score) in scores {
+            println!("{score:.3}  {}", node_line(node));
+        }
+    }
+}
+
+/// Loads a repository directory, or the repository under `root` at a git revision
+fn load_version(root: &Path, version: &str) -> Result<GraphStore, String> {
+    let dir = Path::new(version);
+    if dir.is_dir() {
+        return Ok(load(dir));
+    }
+
+    let checkout = std::env::temp_dir().join(format!(
+        "truth-mines-{}-{}",
+        std::process::id(),
+        version.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+    ));
+    let _ = std::fs::remove_dir_all(&checkout);
+    std::fs::create_dir_all(&checkout)
+        .map_err(|e| format!("Failed to create {}: {e}", checkout.display()))?;
+
+    let result = extract_revision(root, version, &checkout).map(|()| load(&checkout));
+    let _ = std::fs::remove_dir_all(&checkout);
+    result
+}
+
+/// Extracts `nodes/` and `edges/` at a git revision with `git archive | tar -x`
+fn extract_revision(root: &Path, revision: &str, dest: &Path) -> Result<(), String> {
+    let mut git = std::process::Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args([
+            "archive",
+            "--format=tar",
+            &format!("{revision}:./"),
+            "nodes",
+            "edges",
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    let archive = git.stdout.take().ok_or("Failed to read git output")?;
+    let tar = std::process::Command::new("tar")
+        .arg("-x")
+        .arg("-C")
+        .arg(dest)
+        .stdin(archive)
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| format!("Failed to run tar: {e}"))?;
+
+    let git = git
+        .wait_with_output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    if !git.status.success() {
+        return Err(format!(
+            "'{revision}' is neither a directory nor a git revision: {}",
+            String::from_utf8_lossy(&git.stderr).trim()
+        ));
+    }
+    if !tar.success() {
+        return Err(format!("Failed to extract revision '{revision}'"));
+    }
+    Ok(())
+}
+
+fn print_diff(diff: &GraphDiff, as_json: bool) {
+    if as_json {
+        print_json(&serde_json::to_value(diff).unwrap_or_default());
+        return;
+    }
+    if diff.is_empty() {
+        println!("No changes");
+        return;
+    }
+
+    for node in &diff.added_nodes {
+        println!("+ {}", node_line(node));
+    }
+    for node in &diff.removed_nodes {
+        println!("- {}", node_line(node));
+    }
+    for node in &diff.modified_nodes {
+        println!("~ {}", node.id);
+        print_field_changes(&node.changes);
+    }
+    for edge in &diff.added_edges {
+        println!("+ {}", edge_line(edge));
+    }
+    for edge in &diff.removed_edges {
+        println!("- {}", edge_line(edge));
+    }
+    for edge in &diff.modified_edges {
+        println!("~ {} -[{}]-> {}", edge.from, edge.relation, edge.to);
+        print_field_changes(&edge.changes);
+    }
+
+    let has_impact = !diff.depth_changes.is_empty()
+        || !diff.foundations_gained.is_empty()
+        || !diff.foundations_lost.is_empty()
+        || !diff.load_bearing_shifts.is_empty();
+    if has_impact {
+        println!();
+        println!("Impact:");
+    }
+    let depth = |depth: Option<u32>| depth.map_or_else(|| "none".to_string(), |d| d.to_string());
+    for change in &diff.depth_changes {
+        println!(
+            "  depth         {}: {} -> {}",
+            change.id,
+            depth(change.before),
+            depth(change.after)
+        );
+    }
+    for id in &diff.foundations_gained {
+        println!("  foundation +  {id}");
+    }
+    for id in &diff.foundations_lost {
+        println!("  foundation -  {id}");
+    }
+    for shift in &diff.load_bearing_shifts {
+        println!(
+            "  load-bearing  {}: {:.3} -> {:.3}",
+            shift.id, shift.before, shift.after
+        );
+    }
+}
+
+fn print_field_changes(changes: &[FieldChange]) {
+    let value = |value: &Option<Value>| {
+        value
+            .as_ref()
+            .map_or_else(|| "(none)".to_string(), Value::to_string)
+    };
+    for change in changes {
+        println!(
+            "    {}: {} -> {}",
+            change.field,
+            value(&change.before),
+            value(&change.after)
+        );
+    }
+}
+
+fn new_id(
+    graph: &GraphStore,
+    from: Option<&str>,
+    domain: Option<&str>,
+    prefix: Option<&str>,
+    count: usize,
+    as_json: bool,
+) -> Result<(), String> {
+    let strategy = from.map_or_else(IdStrategy::random, |text| {
+        IdStrategy::Content(text.to_string())
+    });
+    let mut options = IdOptions::new(strategy);
+    if let Some(domain) = domain {
+        let prefix = domain_prefix(domain)
+            .ok_or_else(|| format!("No ID prefix convention for domain '{domain}'"))?;
+        options = options.with_prefix(prefix);
+    }
+    if let Some(prefix) = prefix {
+        options = options.with_prefix(prefix);
+    }
+
+    let ids = graph
+        .generate_ids(&options, count)
+        .map_err(|e| e.to_string())?;
+    if as_json {
+        print_json(&json!(ids));
+    } else {
+        for id in ids {
+            println!("{id}");
+        }
+    }
+    Ok(())
+}
+
+/// Restricts the graph to the given domains and to the neighbourhood of a node
+fn select_subgraph(
+    graph: &GraphStore,
+    domains: &[String],
+    around: Option<&str>,
+    depth: u32,
+) -> Result<GraphStore, String> {
+    let mut selected: Vec<&Node> = match around {
+        Some(id) => {
+            let node = graph
+                .get_node(id)
+                .ok_or_else(|| format!("Unknown node '{id}'"))?;
+            std::iter::once(node)
+                .chain(graph.neighbors(id, depth))
+                .collect()
+        }
+        None => graph.nodes().iter().collect(),
+    };
+    if !domains.is_empty() {
+        selected.retain(|node| domains.contains(&node.domain));
+    }
+    Ok(graph.subgraph(selected.iter().map(|node| node.id.as_str())))
+}
+
+fn export(
+    graph: &GraphStore,
+    format: ExportFormat,
+    with_positions: bool,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    let content = match format {
+        ExportFormat::Toon => write_toon_document(&ToonDocument::from_graph(graph)),
+        ExportFormat::Json => {
+            let value = json!({ "nodes": graph.nodes(), "edges": graph.edges() });
+            serde_json::to_string_pretty(&value).map_err(|e| e.to_string())? + "\n"
+        }
+        ExportFormat::Jsonl => {
+            let mut lines = String::new();
+            for edge in graph.edges() {
+                lines += &serde_json::to_string(edge).map_err(|e| e.to_string())?;
+                lines.push('\n');
+            }
+            lines
+        }
+        ExportFormat::Graphml => with_layout(graph, with_positions, write_graphml),
+        ExportFormat::Gexf => with_layout(graph, with_positions, write_gexf),
+        ExportFormat::Dot => with_layout(graph, with_positions, write_dot),
+    };
+
+    if let Some(path) = output {
+        return std::fs::write(path, content)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()));
+    }
+    print!("{content}");
+    Ok(())
+}
+
+/// Runs an interchange-format writer with depths and, optionally, positions
+///
+/// Positions use the same layer spacing as `build_index`, so they match
+/// `dist/graph.json`.
+fn with_layout(
+    graph: &GraphStore,
+    with_positions: bool,
+    write: fn(&GraphStore, &ExportOptions) -> String,
+) -> String {
+    let depths = compute_depths(graph);
+    let positions = with_positions
+        .then(|| compute_truth_mine_layout(graph, &depths, IndexOptions::default().depth_spacing));
+    let options = ExportOptions {
+        depths: Some(&depths),
+        positions: positions.as_ref(),
+    };
+    write(graph, &options)
+}
+
+/// Applies a refactoring to the repository and writes the touched files
+fn refactor(
+    root: &Path,
+    dry_run: bool,
+    as_json: bool,
+    operation: impl FnOnce(&mut GraphStore) -> Result<RefactorReport, Error>,
+) -> Result<ExitCode, String> {
+    let loaded = load_repository_lenient(root);
+    if !loaded.is_clean() {
+        return Err(format!(
+            "{} problem(s) while loading; run `truth-mines validate` and fix them first",
+            loaded.diagnostics.len()
+        ));
+    }
+    let node_files = loaded.node_files();
+    let mut graph = loaded.into_graph_store();
+    let report = operation(&mut graph).map_err(|e| e.to_string())?;
+
+    let written = (!dry_run).then(|| apply_refactor(root, &graph, &report, &node_files));
+    let diagnostics = written.as_ref().map_or(&[][..], |w| &w.diagnostics[..]);
+
+    if as_json {
+        let paths = |paths: &[std::path::PathBuf]| {
+            paths
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+        };
+        print_json(&json!({
+            "changes": report,
+            "written": written.as_ref().map(|w| paths(&w.written)),
+            "removed": written.as_ref().map(|w| paths(&w.removed)),
+            "diagnostics": diagnostics.iter().map(error_json).collect::<Vec<_>>(),
+        }));
+    } else {
+        print_refactor(&report);
+        for diagnostic in diagnostics {
+            eprintln!("{diagnostic}");
+        }
+        match &written {
+            Some(written) => println!(
+                "{} file(s) written, {} removed",
+                written.written.len(),
+                written.removed.len()
+            ),
+            None => println!("dry run: no files written"),
+        }
+    }
+
+    if diagnostics.is_empty() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+/// Splits a node, taking edge assignments from `--assign` or the terminal
+fn split(
+    graph: &mut GraphStore,
+    id: &str,
+    parts: &[String],
+    assignments: &[String],
+) -> Result<RefactorReport, Error> {
+    let domain = graph
+        .get_node(id)
+        .ok_or_else(|| Error::integrity(format!("Unknown node '{id}'")))?
+        .domain
+        .clone();
+
+    let mut split_parts = Vec::with_capacity(parts.len());
+    for part in parts {
+        let split_part = match part.split_once('=') {
+            Some((part_id, title)) if is_valid_id(part_id) => SplitPart::new(part_id, title),
+            _ => {
+                let options =
+                    IdOptions::new(IdStrategy::Content(part.clone())).with_domain(&domain);
+                SplitPart::new(graph.generate_id(&options)?, part.as_str())
+            }
+        };
+        split_parts.push(split_part);
+    }
+
+    let mut by_neighbor = HashMap::new();
+    for assignment in assignments {
+        let (neighbor, part) = assignment.split_once('=').ok_or_else(|| {
+            Error::schema(format!("--assign '{assignment}' must be NEIGHBOR=PART"))
+        })?;
+        let pos = part_position(&split_parts, part)
+            .ok_or_else(|| Error::schema(format!("--assign '{assignment}': no part '{part}'")))?;
+        by_neighbor.insert(neighbor.to_string(), pos);
+    }
+
+    let interactive = std::io::stdin().is_terminal();
+    graph.split_node(id, &split_parts, |edge, parts| {
+        let neighbor = if edge.from == id {
+            &edge.to
+        } else {
+            &edge.from
+        };
+        if let Some(&pos) = by_neighbor.get(neighbor) {
+            return Ok(pos);
+        }
+        if !interactive {
+            return Err(Error::integrity(format!(
+                "No part for edge {}; add --assign {neighbor}=PART",
+                edge_line(edge)
+            )));
+        }
+        prompt_part(edge, parts)
+    })
+}
+
+/// Finds a part by ID or 1-based position
+fn part_position(parts: &[SplitPart], part: &str) -> Option<usize> {
+    parts.iter().position(|p| p.id == part).or_else(|| {
+        part.parse::<usize>()
+            .ok()
+            .filter(|pos| (1..=parts.len()).contains(pos))
+            .map(|pos| pos - 1)
+    })
+}
+
+/// Asks on the terminal which part takes over an edge
+fn prompt_part(edge: &Edge, parts: &[SplitPart]) -> Result<usize, Error> {
+    let mut stderr = std::io::stderr();
+    let _ = writeln!(stderr, "edge {}", edge_line(edge));
+    for (pos, part) in parts.iter().enumerate() {
+        let _ = writeln!(stderr, "  {}) {}  {}", pos + 1, part.id, part.title);
+    }
+
+    let mut line = String::new();
+    loop {
+        let _ = write!(stderr, "part [1-{}]: ", parts.len());
+        let _ = stderr.flush();
+        line.clear();
+        let read = std::io::stdin()
+            .lock()
+            .read_line(&mut line)
+            .map_err(|e| Error::io(e.to_string()))?;
+        if read == 0 {
+            return Err(Error::io("Split cancelled"));
+        }
+        if let Some(pos) = part_position(parts, line.trim()) {
+            return Ok(pos);
+        }
+    }
+}
+
+fn print_refactor(report: &RefactorReport) {
+    for (old, new) in &report.renamed_nodes {
+        println!("renamed  {old} -> {new}");
+    }
+    for id in &report.added_nodes {
+        println!("added    {id}");
+    }
+    for id in &report.updated_nodes {
+        println!("updated  {id}");
+    }
+    for id in &report.removed_nodes {
+        println!("removed  {id}");
+    }
+    for change in &report.changed_edges {
+        println!(
+            "edge     {}  =>  {}",
+            edge_line(&change.before),
+            edge_line(&change.after)
+        );
+    }
+    for edge in &report.removed_edges {
+        println!("removed  {}", edge_line(edge));
+    }
+}
+
+const fn weight_policy(weights: WeightArg) -> WeightPolicy {
+    match weights {
+        WeightArg::Max => WeightPolicy::Max,
+        WeightArg::Min => WeightPolicy::Min,
+        WeightArg::Mean => WeightPolicy::Mean,
+        WeightArg::First => WeightPolicy::First,
+    }
+}
+
+fn require_node<'a>(graph: &'a GraphStore, id: &str) -> Result<&'a Node, String> {
+    graph
+        .get_node(id)
+        .ok_or_else(|| format!("Unknown node '{id}'"))
+}
+
+fn node_line(node: &Node) -> String {
+    format!(
+        "{}  [{}/{}]  {}",
+        node.id, node.domain, node.r#type, node.title
+    )
+}
+
+fn edge_line(edge: &Edge) -> String {
+    let weight = edge.weight.map(|w| format!(" ({w})")).unwrap_or_default();
+    format!("{} -[{}]-> {}{weight}", edge.from, edge.relation, edge.to)
+}
+
+fn node_json(node: &Node) -> Value {
+    json!({
+        "id": node.id,
+        "type": node.r#type,
+        "domain": node.domain,
+        "title": node.title,
+    })
+}
+
+fn error_json(err: &Error) -> Value {
+    let location = err.location();
+    json!({
+        "kind": err.kind(),
+        "message": err.message(),
+        "path": location.path.as_ref().map(|path| path.display().to_string()),
+        "line": location.line,
+        "column": location.column,
+    })
+}
+
+fn print_json(value: &Value) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+    );
+}
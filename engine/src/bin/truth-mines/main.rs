@@ -0,0 +1,205 @@
+//! `truth-mines`: inspect a Truth Mines repository from the command line
+//!
+//! Every subcommand loads the repository under `--root` (default: the
+//! current directory) into a `GraphStore` and prints human-readable text,
+//! or JSON with `--json`.
+
+mod commands;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Command-line tool for a Truth Mines knowledge base
+#[derive(Debug, Parser)]
+#[command(name = "truth-mines", version, about)]
+struct Cli {
+    /// Repository root containing `nodes/` and `edges/`
+    #[arg(long, global = true, default_value = ".")]
+    root: PathBuf,
+
+    /// Print machine-readable JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Check every node and edge against the schemas and the graph
+    Validate,
+    /// Rewrite node and edge files in canonical form
+    Fmt {
+        /// Only list the files that would change; exit 1 if there are any
+        #[arg(long)]
+        check: bool,
+    },
+    /// Check the graph against the epistemic lint rules
+    Lint {
+        /// Rule severities (default: `lint.toml` under the root, if present)
+        #[arg(long)]
+        config: Option<PathBuf>,
+    },
+    /// Count nodes and edges by domain, type and relation
+    Stats,
+    /// List the nodes reachable from a node
+    Neighbors {
+        /// Starting node ID
+        id: String,
+        /// Maximum number of hops
+        #[arg(long, default_value_t = 1)]
+        depth: u32,
+    },
+    /// List the paths between two nodes
+    Paths {
+        /// Starting node ID
+        from: String,
+        /// Ending node ID
+        to: String,
+        /// Maximum path length
+        #[arg(long, default_value_t = 10)]
+        max_depth: u32,
+    },
+    /// Show the epistemic depth of a node
+    Depth {
+        /// Node ID
+        id: String,
+    },
+    /// Rank nodes by how much of the graph depends on them
+    LoadBearing {
+        /// Number of nodes to show
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+    },
+    /// Generate node IDs that no existing node uses
+    NewId {
+        /// Derive the ID from this text (e.g. the title) instead of at random
+        #[arg(long)]
+        from: Option<String>,
+        /// Start the ID with this domain's conventional prefix
+        #[arg(long, conflicts_with = "prefix")]
+        domain: Option<String>,
+        /// Start the ID with these characters
+        #[arg(long)]
+        prefix: Option<String>,
+        /// Number of IDs to generate
+        #[arg(long, default_value_t = 1)]
+        count: usize,
+    },
+    /// Compare two versions of the repository
+    Diff {
+        /// Old version: a repository directory or a git revision of `--root`
+        before: String,
+        /// New version, like `before` (default: the files under `--root`)
+        after: Option<String>,
+        /// Smallest load-bearing change to report
+        #[arg(long, default_value_t = 0.05)]
+        threshold: f32,
+    },
+    /// Rename a node, rewriting every edge that refers to it
+    Rename {
+        /// Current node ID
+        old: String,
+        /// New node ID
+        new: String,
+        /// Print the changes without writing any files
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Merge one node into another, moving its edges, tags and sources
+    Merge {
+        /// Node that remains
+        keep: String,
+        /// Node that is merged into `keep` and removed
+        drop: String,
+        /// How the weights of edges that become duplicates are combined
+        #[arg(long, value_enum, default_value_t = WeightArg::Max)]
+        weights: WeightArg,
+        /// Print the changes without writing any files
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Split a node into parts, reassigning each of its edges to one part
+    ///
+    /// Edges not covered by `--assign` are asked about interactively when
+    /// standard input is a terminal.
+    Split {
+        /// Node to split
+        id: String,
+        /// A part as `ID=TITLE`, or `TITLE` to generate its ID (repeat for each part)
+        #[arg(long = "part", required = true, num_args = 1)]
+        parts: Vec<String>,
+        /// Give the edges between the node and NEIGHBOR to PART, as
+        /// `NEIGHBOR=PART` with PART a part ID or 1-based position
+        #[arg(long = "assign")]
+        assignments: Vec<String>,
+        /// Print the changes without writing any files
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Write the graph, or part of it, in another format
+    Export {
+        /// Output format
+        #[arg(long, value_enum)]
+        format: ExportFormat,
+        /// Output file (default: standard output)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        /// Only export nodes in this domain (repeatable)
+        #[arg(long = "domain", value_name = "DOMAIN")]
+        domains: Vec<String>,
+        /// Only export this node and the nodes reachable from it
+        #[arg(long, value_name = "ID")]
+        around: Option<String>,
+        /// Maximum number of hops from `--around`
+        #[arg(long, default_value_t = 2, requires = "around")]
+        depth: u32,
+        /// Carry truth-mine layout positions (graphml, gexf and dot formats)
+        #[arg(long)]
+        positions: bool,
+    },
+}
+
+/// Formats understood by `export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ExportFormat {
+    /// TOON document with a `nodes` table and one table per relation
+    Toon,
+    /// JSON object with `nodes` and `edges` arrays
+    Json,
+    /// One edge per line
+    Jsonl,
+    /// `GraphML` document with typed node and edge attributes
+    Graphml,
+    /// GEXF 1.2 document with typed node and edge attributes
+    Gexf,
+    /// Graphviz DOT digraph
+    Dot,
+}
+
+/// Weight policies understood by `merge`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum WeightArg {
+    /// Strongest weight wins
+    Max,
+    /// Weakest weight wins
+    Min,
+    /// Average of the weights
+    Mean,
+    /// Weight of the edge that was in the graph first
+    First,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match commands::run(&cli) {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("error: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
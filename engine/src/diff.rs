@@ -0,0 +1,533 @@
+//! Structural diff between two versions of a graph
+//!
+//! [`diff`] compares nodes by ID and edges by `(from, to, relation)` and
+//! reports what was added, removed or modified, field by field (metadata
+//! keys are compared one by one, as `metadata.<key>`). It also reports the
+//! derived impact of the change on nodes present in both versions: changed
+//! epistemic depths, foundations gained or lost, and load-bearing scores
+//! that moved by at least [`DiffOptions::load_bearing_threshold`].
+
+use crate::analysis::compute_all_load_bearing;
+use crate::graph::{Edge, GraphStore, Node};
+use crate::layout::depth::compute_depths;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Options for [`diff_with`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffOptions {
+    /// Smallest load-bearing change worth reporting
+    pub load_bearing_threshold: f32,
+}
+
+impl Default for DiffOptions {
+    /// Reports shifts of at least 0.05, the "significant" band of
+    /// [`compute_load_bearing`](crate::analysis::compute_load_bearing)
+    fn default() -> Self {
+        Self {
+            load_bearing_threshold: 0.05,
+        }
+    }
+}
+
+/// A field whose value differs; `None` means the field is absent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldChange {
+    /// Field name as in the data files, `metadata.<key>` for metadata
+    pub field: String,
+    /// Value in the old version
+    pub before: Option<Value>,
+    /// Value in the new version
+    pub after: Option<Value>,
+}
+
+/// A node present in both versions with different fields
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NodeModification {
+    /// Node ID
+    pub id: String,
+    /// Changed fields, sorted by name
+    pub changes: Vec<FieldChange>,
+}
+
+/// An edge present in both versions with different weight, domain or metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EdgeModification {
+    /// Source node ID
+    pub from: String,
+    /// Target node ID
+    pub to: String,
+    /// Relation type
+    pub relation: String,
+    /// Changed fields (`w` for the weight), sorted by name
+    pub changes: Vec<FieldChange>,
+}
+
+impl EdgeModification {
+    /// Weight before and after, if it changed
+    #[must_use]
+    pub fn weight_change(&self) -> Option<&FieldChange> {
+        self.changes.iter().find(|change| change.field == "w")
+    }
+}
+
+/// A node whose epistemic depth changed; `None` means it has no depth
+/// (it is only reachable through a cycle)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DepthChange<'a> {
+    /// Node ID
+    pub id: &'a str,
+    /// Depth in the old version
+    pub before: Option<u32>,
+    /// Depth in the new version
+    pub after: Option<u32>,
+}
+
+/// A node whose load-bearing score moved by at least the threshold
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct LoadBearingShift<'a> {
+    /// Node ID
+    pub id: &'a str,
+    /// Score in the old version
+    pub before: f32,
+    /// Score in the new version
+    pub after: f32,
+}
+
+/// Everything that differs between two versions of a graph
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GraphDiff<'a> {
+    /// Nodes only in the new version, in its order
+    pub added_nodes: Vec<&'a Node>,
+    /// Nodes only in the old version, in its order
+    pub removed_nodes: Vec<&'a Node>,
+    /// Nodes in both versions with different fields, by ID
+    pub modified_nodes: Vec<NodeModification>,
+    /// Edges only in the new version, in its order
+    pub added_edges: Vec<&'a Edge>,
+    /// Edges only in the old version, in its order
+    pub removed_edges: Vec<&'a Edge>,
+    /// Edges in both versions with different fields, in new-version order
+    pub modified_edges: Vec<EdgeModification>,
+    /// Nodes in both versions whose depth changed, by ID
+    pub depth_changes: Vec<DepthChange<'a>>,
+    /// Nodes in both versions that became foundations (depth 0), by ID
+    pub foundations_gained: Vec<&'a str>,
+    /// Nodes in both versions that are no longer foundations, by ID
+    pub foundations_lost: Vec<&'a str>,
+    /// Nodes in both versions whose load-bearing score moved, largest first
+    pub load_bearing_shifts: Vec<LoadBearingShift<'a>>,
+}
+
+impl GraphDiff<'_> {
+    /// Returns true if no node or edge was added, removed or modified
+    ///
+    /// The derived impact is then necessarily empty too.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.modified_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.modified_edges.is_empty()
+    }
+}
+
+/// Compares two versions of a graph with the default [`DiffOptions`]
+#[must_use]
+pub fn diff<'a>(before: &'a GraphStore, after: &'a GraphStore) -> GraphDiff<'a> {
+    diff_with(before, after, &DiffOptions::default())
+}
+
+/// Compares two versions of a graph
+#[must_use]
+pub fn diff_with<'a>(
+    before: &'a GraphStore,
+    after: &'a GraphStore,
+    options: &DiffOptions,
+) -> GraphDiff<'a> {
+    let mut result = GraphDiff {
+        added_nodes: after
+            .nodes
+            .iter()
+            .filter(|node| before.get_node(&node.id).is_none())
+            .collect(),
+        removed_nodes: before
+            .nodes
+            .iter()
+            .filter(|node| after.get_node(&node.id).is_none())
+            .collect(),
+        ..GraphDiff::default()
+    };
+
+    let common: BTreeMap<&str, (&Node, &Node)> = before
+        .nodes
+        .iter()
+        .filter_map(|old| Some((old.id.as_str(), (old, after.get_node(&old.id)?))))
+        .collect();
+    for (&id, (old, new)) in &common {
+        let changes = field_changes(&node_fields(old), &node_fields(new));
+        if !changes.is_empty() {
+            result.modified_nodes.push(NodeModification {
+                id: id.to_string(),
+                changes,
+            });
+        }
+    }
+
+    diff_edges(before, after, &mut result);
+
+    let (old_depths, new_depths) = (compute_depths(before), compute_depths(after));
+    for &id in common.keys() {
+        let (old_depth, new_depth) = (old_depths.get(id).copied(), new_depths.get(id).copied());
+        if old_depth != new_depth {
+            result.depth_changes.push(DepthChange {
+                id,
+                before: old_depth,
+                after: new_depth,
+            });
+        }
+        match (old_depth == Some(0), new_depth == Some(0)) {
+            (false, true) => result.foundations_gained.push(id),
+            (true, false) => result.foundations_lost.push(id),
+            _ => {}
+        }
+    }
+
+    // Scores only move when edges or nodes do; skip the work otherwise
+    let structural = !result.added_nodes.is_empty()
+        || !result.removed_nodes.is_empty()
+        || !result.added_edges.is_empty()
+        || !result.removed_edges.is_empty();
+    if structural {
+        let (old_scores, new_scores) = (
+            compute_all_load_bearing(before),
+            compute_all_load_bearing(after),
+        );
+        for &id in common.keys() {
+            let score = |scores: &HashMap<String, f32>| scores.get(id).copied().unwrap_or(0.0);
+            let (old_score, new_score) = (score(&old_scores), score(&new_scores));
+            if (new_score - old_score).abs() >= options.load_bearing_threshold {
+                result.load_bearing_shifts.push(LoadBearingShift {
+                    id,
+                    before: old_score,
+                    after: new_score,
+                });
+            }
+        }
+        result.load_bearing_shifts.sort_by(|a, b| {
+            (b.after - b.before)
+                .abs()
+                .total_cmp(&(a.after - a.before).abs())
+                .then_with(|| a.id.cmp(b.id))
+        });
+    }
+
+    result
+}
+
+/// Pairs edges by `(from, to, relation)`; parallel duplicates pair up in order
+fn diff_edges<'a>(before: &'a GraphStore, after: &'a GraphStore, result: &mut GraphDiff<'a>) {
+    let mut old_edges: HashMap<(&str, &str, &str), Vec<usize>> = HashMap::new();
+    for (index, edge) in before.edges.iter().enumerate().rev() {
+        old_edges.entry(edge_key(edge)).or_default().push(index);
+    }
+
+    for new in &after.edges {
+        let Some(old) = old_edges.get_mut(&edge_key(new)).and_then(Vec::pop) else {
+            result.added_edges.push(new);
+            continue;
+        };
+        let old = &before.edges[old];
+        let changes = field_changes(&edge_fields(old), &edge_fields(new));
+        if !changes.is_empty() {
+            result.modified_edges.push(EdgeModification {
+                from: new.from.clone(),
+                to: new.to.clone(),
+                relation: new.relation.clone(),
+                changes,
+            });
+        }
+    }
+
+    let mut unmatched: Vec<usize> = old_edges.into_values().flatten().collect();
+    unmatched.sort_unstable();
+    result.removed_edges = unmatched
+        .into_iter()
+        .map(|index| &before.edges[index])
+        .collect();
+}
+
+fn edge_key(edge: &Edge) -> (&str, &str, &str) {
+    (&edge.from, &edge.to, &edge.relation)
+}
+
+/// Node fields as in its JSON file, with metadata flattened into `metadata.<key>`
+pub(crate) fn node_fields(node: &Node) -> Map<String, Value> {
+    let mut fields = match serde_json::to_value(node) {
+        Ok(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    };
+    fields.remove("id");
+    flatten_metadata(&mut fields);
+    fields
+}
+
+/// Edge fields other than the identifying endpoints and relation
+pub(crate) fn edge_fields(edge: &Edge) -> Map<String, Value> {
+    let mut fields = Map::new();
+    fields.insert("domain".to_string(), Value::from(edge.domain.as_str()));
+    if let Some(weight) = edge.weight {
+        // Through the shortest f32 text, so 0.9 is not shown as 0.8999999761581421
+        let weight = weight
+            .to_string()
+            .parse::<f64>()
+            .map_or(Value::Null, Value::from);
+        fields.insert("w".to_string(), weight);
+    }
+    if let Some(metadata) = &edge.metadata {
+        fields.insert("metadata".to_string(), metadata.clone());
+    }
+    flatten_metadata(&mut fields);
+    fields
+}
+
+fn flatten_metadata(fields: &mut Map<String, Value>) {
+    match fields.remove("metadata") {
+        Some(Value::Object(metadata)) => {
+            for (key, value) in metadata {
+                fields.insert(format!("metadata.{key}"), value);
+            }
+        }
+        Some(metadata) => {
+            fields.insert("metadata".to_string(), metadata);
+        }
+        None => {}
+    }
+}
+
+/// Compares two field maps, treating `null` as absent
+fn field_changes(before: &Map<String, Value>, after: &Map<String, Value>) -> Vec<FieldChange> {
+    let present = |fields: &Map<String, Value>, key: &str| {
+        fields.get(key).filter(|value| !value.is_null()).cloned()
+    };
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let (old, new) = (present(before, key), present(after, key));
+            (old != new).then(|| FieldChange {
+                field: key.clone(),
+                before: old,
+                after: new,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn create_test_node(id: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            r#type: "proposition".to_string(),
+            domain: "philosophy".to_string(),
+            title: format!("Node {id}"),
+            content: None,
+            formal: None,
+            tags: vec![],
+            metadata: Value::Null,
+            sources: vec![],
+            created: None,
+            updated: None,
+            status: None,
+        }
+    }
+
+    fn create_test_edge(from: &str, to: &str, relation: &str, weight: Option<f32>) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            relation: relation.to_string(),
+            domain: "philosophy".to_string(),
+            weight,
+            metadata: None,
+        }
+    }
+
+    fn create_graph(nodes: Vec<Node>, edges: Vec<Edge>) -> GraphStore {
+        let mut graph = GraphStore::new();
+        for node in nodes {
+            graph.add_node(node);
+        }
+        for edge in edges {
+            graph.add_edge(edge);
+        }
+        graph.build_adjacency();
+        graph
+    }
+
+    fn chain() -> GraphStore {
+        create_graph(
+            vec![
+                create_test_node("a"),
+                create_test_node("b"),
+                create_test_node("c"),
+            ],
+            vec![
+                create_test_edge("a", "b", "supports", Some(0.9)),
+                create_test_edge("b", "c", "supports", Some(0.8)),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_diff_scales_to_long_chains() {
+        let count = 20_000;
+        let ids: Vec<String> = (0..count).map(|i| format!("n{i}")).collect();
+        let nodes = || ids.iter().map(|id| create_test_node(id)).collect();
+        let edges = |skip: usize| {
+            (1..count)
+                .filter(|&i| i != skip)
+                .map(|i| create_test_edge(&ids[i - 1], &ids[i], "supports", None))
+                .collect()
+        };
+        let before = create_graph(nodes(), edges(0));
+        let after = create_graph(nodes(), edges(count / 2));
+
+        let start = std::time::Instant::now();
+        let result = diff(&before, &after);
+        let elapsed = start.elapsed();
+
+        assert_eq!(result.removed_edges.len(), 1);
+        assert_eq!(result.removed_edges[0].to, ids[count / 2]);
+        assert_eq!(result.foundations_gained, vec![ids[count / 2].as_str()]);
+        assert!(!result.load_bearing_shifts.is_empty());
+        assert!(
+            elapsed < std::time::Duration::from_secs(10),
+            "took {elapsed:?}"
+        );
+    }
+
+    #[test]
+    fn test_identical_graphs_have_no_diff() {
+        let (before, after) = (chain(), chain());
+        let result = diff(&before, &after);
+        assert!(result.is_empty());
+        assert_eq!(result, GraphDiff::default());
+    }
+
+    #[test]
+    fn test_node_field_changes() {
+        let before = chain();
+        let mut after = chain();
+        let mut b = create_test_node("b");
+        b.title = "Renamed".to_string();
+        b.tags = vec!["new".to_string()];
+        b.metadata = json!({"certainty": 0.5});
+        after.add_node(b);
+        after.add_node(create_test_node("d"));
+
+        let result = diff(&before, &after);
+
+        assert_eq!(result.added_nodes.len(), 1);
+        assert_eq!(result.added_nodes[0].id, "d");
+        assert_eq!(result.modified_nodes.len(), 1);
+        let fields: Vec<_> = result.modified_nodes[0]
+            .changes
+            .iter()
+            .map(|change| change.field.as_str())
+            .collect();
+        assert_eq!(fields, vec!["metadata.certainty", "tags", "title"]);
+        assert_eq!(result.modified_nodes[0].changes[0].before, None);
+        assert_eq!(
+            result.modified_nodes[0].changes[2].after,
+            Some(json!("Renamed"))
+        );
+    }
+
+    #[test]
+    fn test_edge_changes() {
+        let before = chain();
+        let after = create_graph(
+            vec![
+                create_test_node("a"),
+                create_test_node("b"),
+                create_test_node("c"),
+            ],
+            vec![
+                create_test_edge("a", "b", "supports", Some(0.5)),
+                create_test_edge("a", "c", "attacks", None),
+            ],
+        );
+
+        let result = diff(&before, &after);
+
+        assert_eq!(result.added_edges, vec![&after.edges()[1]]);
+        assert_eq!(result.removed_edges, vec![&before.edges()[1]]);
+        assert_eq!(result.modified_edges.len(), 1);
+        let weight = result.modified_edges[0].weight_change().unwrap();
+        assert_eq!(weight.before, Some(json!(0.9)));
+        assert_eq!(weight.after, Some(json!(0.5)));
+    }
+
+    #[test]
+    fn test_derived_impact() {
+        // a → b → c becomes a → b, c: c becomes a foundation, and a and b
+        // no longer carry it
+        let before = chain();
+        let after = create_graph(
+            vec![
+                create_test_node("a"),
+                create_test_node("b"),
+                create_test_node("c"),
+            ],
+            vec![create_test_edge("a", "b", "supports", Some(0.9))],
+        );
+
+        let result = diff(&before, &after);
+
+        assert_eq!(
+            result.depth_changes,
+            vec![DepthChange {
+                id: "c",
+                before: Some(2),
+                after: Some(0)
+            }]
+        );
+        assert_eq!(result.foundations_gained, vec!["c"]);
+        assert!(result.foundations_lost.is_empty());
+        let shifted: Vec<_> = result.load_bearing_shifts.iter().map(|s| s.id).collect();
+        assert_eq!(shifted, vec!["a", "b"]);
+        assert!(result.load_bearing_shifts[0].after < result.load_bearing_shifts[0].before);
+
+        let options = DiffOptions {
+            load_bearing_threshold: 1.0,
+        };
+        assert!(diff_with(&before, &after, &options)
+            .load_bearing_shifts
+            .is_empty());
+    }
+
+    #[test]
+    fn test_parallel_edges_pair_in_order() {
+        let edges = |weights: &[f32]| {
+            weights
+                .iter()
+                .map(|&w| create_test_edge("a", "b", "supports", Some(w)))
+                .collect()
+        };
+        let nodes = || vec![create_test_node("a"), create_test_node("b")];
+        let before = create_graph(nodes(), edges(&[0.1, 0.2]));
+        let after = create_graph(nodes(), edges(&[0.1]));
+
+        let result = diff(&before, &after);
+
+        assert!(result.modified_edges.is_empty());
+        assert_eq!(result.removed_edges, vec![&before.edges()[1]]);
+    }
+}
@@ -0,0 +1,339 @@
+//! Crate-wide error type
+//!
+//! Every loader and parser reports failures as an [`Error`], which records
+//! what kind of input was at fault and, where known, the file, line and
+//! column. The `Display` form follows the familiar `path:line:column: message`
+//! layout so editors and terminals can jump to the location.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Result alias used throughout the crate
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Where in the input an error occurred
+///
+/// Lines and columns are 1-based; columns count characters, not bytes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Location {
+    /// Source file, if the input came from disk
+    pub path: Option<PathBuf>,
+    /// Line number
+    pub line: Option<usize>,
+    /// Column number
+    pub column: Option<usize>,
+}
+
+impl Location {
+    /// Location of a line/column within unnamed input
+    #[must_use]
+    pub const fn at(line: usize, column: usize) -> Self {
+        Self {
+            path: None,
+            line: Some(line),
+            column: Some(column),
+        }
+    }
+
+    /// Location of a line/column given as a byte offset into `source`
+    #[must_use]
+    pub fn from_offset(source: &str, offset: usize) -> Self {
+        let before = &source[..offset.min(source.len())];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        Self::at(line, before[line_start..].chars().count() + 1)
+    }
+
+    /// Returns true if nothing about the location is known
+    #[must_use]
+    pub const fn is_unknown(&self) -> bool {
+        self.path.is_none() && self.line.is_none()
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(path) = &self.path {
+            parts.push(path.display().to_string());
+        }
+        if let Some(line) = self.line {
+            parts.push(line.to_string());
+            if let Some(column) = self.column {
+                parts.push(column.to_string());
+            }
+        }
+        write!(f, "{}", parts.join(":"))
+    }
+}
+
+/// Error raised while loading, parsing or checking graph data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// Reading a file or directory failed
+    Io {
+        /// What went wrong
+        message: String,
+        /// Where it went wrong
+        location: Location,
+    },
+    /// JSON or JSONL input is malformed or does not match the data model
+    Json {
+        /// What went wrong
+        message: String,
+        /// Where it went wrong
+        location: Location,
+    },
+    /// TOON input is malformed
+    Toon {
+        /// What went wrong
+        message: String,
+        /// Where it went wrong
+        location: Location,
+    },
+    /// TOML style configuration is malformed
+    Toml {
+        /// What went wrong
+        message: String,
+        /// Where it went wrong
+        location: Location,
+    },
+    /// Data is well-formed but violates a schema rule
+    Schema {
+        /// What went wrong
+        message: String,
+        /// Where it went wrong
+        location: Location,
+    },
+    /// Data is individually valid but inconsistent as a graph
+    Integrity {
+        /// What went wrong
+        message: String,
+        /// Where it went wrong
+        location: Location,
+    },
+}
+
+impl Error {
+    /// Creates an I/O error without a location
+    pub fn io(message: impl Into<String>) -> Self {
+        Self::Io {
+            message: message.into(),
+            location: Location::default(),
+        }
+    }
+
+    /// Creates a JSON error without a location
+    pub fn json(message: impl Into<String>) -> Self {
+        Self::Json {
+            message: message.into(),
+            location: Location::default(),
+        }
+    }
+
+    /// Creates a TOON error without a location
+    pub fn toon(message: impl Into<String>) -> Self {
+        Self::Toon {
+            message: message.into(),
+            location: Location::default(),
+        }
+    }
+
+    /// Creates a TOML error without a location
+    pub fn toml(message: impl Into<String>) -> Self {
+        Self::Toml {
+            message: message.into(),
+            location: Location::default(),
+        }
+    }
+
+    /// Creates a schema error without a location
+    pub fn schema(message: impl Into<String>) -> Self {
+        Self::Schema {
+            message: message.into(),
+            location: Location::default(),
+        }
+    }
+
+    /// Creates an integrity error without a location
+    pub fn integrity(message: impl Into<String>) -> Self {
+        Self::Integrity {
+            message: message.into(),
+            location: Location::default(),
+        }
+    }
+
+    /// Converts a `serde_json` error, keeping its line and column
+    #[must_use]
+    pub fn from_json(err: &serde_json::Error) -> Self {
+        let location = if err.line() == 0 {
+            Location::default()
+        } else {
+            Location::at(err.line(), err.column())
+        };
+        // serde_json appends " at line X column Y"; the location carries that
+        let message = err.to_string();
+        let message = message
+            .rsplit_once(" at line ")
+            .map_or(message.as_str(), |(head, _)| head)
+            .to_string();
+
+        Self::Json { message, location }
+    }
+
+    /// Converts a `toml` error, resolving its byte span against `source`
+    #[must_use]
+    pub fn from_toml(err: &toml::de::Error, source: &str) -> Self {
+        Self::Toml {
+            message: err.message().to_string(),
+            location: err
+                .span()
+                .map(|span| Location::from_offset(source, span.start))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Returns the error kind as a lowercase name (`"io"`, `"json"`, ...)
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::Io { .. } => "io",
+            Self::Json { .. } => "json",
+            Self::Toon { .. } => "toon",
+            Self::Toml { .. } => "toml",
+            Self::Schema { .. } => "schema",
+            Self::Integrity { .. } => "integrity",
+        }
+    }
+
+    /// Returns the message without location
+    #[must_use]
+    pub fn message(&self) -> &str {
+        let (Self::Io { message, .. }
+        | Self::Json { message, .. }
+        | Self::Toon { message, .. }
+        | Self::Toml { message, .. }
+        | Self::Schema { message, .. }
+        | Self::Integrity { message, .. }) = self;
+        message
+    }
+
+    /// Returns where the error occurred
+    #[must_use]
+    pub const fn location(&self) -> &Location {
+        let (Self::Io { location, .. }
+        | Self::Json { location, .. }
+        | Self::Toon { location, .. }
+        | Self::Toml { location, .. }
+        | Self::Schema { location, .. }
+        | Self::Integrity { location, .. }) = self;
+        location
+    }
+
+    const fn location_mut(&mut self) -> &mut Location {
+        let (Self::Io { location, .. }
+        | Self::Json { location, .. }
+        | Self::Toon { location, .. }
+        | Self::Toml { location, .. }
+        | Self::Schema { location, .. }
+        | Self::Integrity { location, .. }) = self;
+        location
+    }
+
+    /// Sets the source file
+    #[must_use]
+    pub fn with_path(mut self, path: &Path) -> Self {
+        self.location_mut().path = Some(path.to_path_buf());
+        self
+    }
+
+    /// Sets the line and column
+    #[must_use]
+    pub const fn at(mut self, line: usize, column: usize) -> Self {
+        let location = self.location_mut();
+        location.line = Some(line);
+        location.column = Some(column);
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let location = self.location();
+        if !location.is_unknown() {
+            write!(f, "{location}: ")?;
+        }
+        write!(f, "{} error: {}", self.kind(), self.message())
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Self::io(err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Self::from_json(&err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_with_location() {
+        let err = Error::toon("Field count mismatch")
+            .with_path(Path::new("dist/edges.toon"))
+            .at(12, 5);
+
+        assert_eq!(
+            err.to_string(),
+            "dist/edges.toon:12:5: toon error: Field count mismatch"
+        );
+        assert_eq!(err.kind(), "toon");
+        assert_eq!(err.location().line, Some(12));
+    }
+
+    #[test]
+    fn test_display_without_location() {
+        let err = Error::integrity("Edge target missing");
+
+        assert_eq!(err.to_string(), "integrity error: Edge target missing");
+    }
+
+    #[test]
+    fn test_location_from_offset() {
+        let source = "first\nsecond line\nthird";
+
+        assert_eq!(Location::from_offset(source, 0), Location::at(1, 1));
+        assert_eq!(Location::from_offset(source, 13), Location::at(2, 8));
+        assert_eq!(Location::from_offset(source, 999), Location::at(3, 6));
+    }
+
+    #[test]
+    fn test_from_json_keeps_position() {
+        let err: Error = serde_json::from_str::<serde_json::Value>("{\n  \"a\": ?\n}")
+            .unwrap_err()
+            .into();
+
+        assert_eq!(err.kind(), "json");
+        assert_eq!(err.location().line, Some(2));
+        assert!(!err.message().contains("at line"));
+    }
+
+    #[test]
+    fn test_from_toml_resolves_span() {
+        let source = "[colors]\nbackground = ";
+        let toml_err = toml::from_str::<toml::Table>(source).unwrap_err();
+
+        let err = Error::from_toml(&toml_err, source);
+
+        assert_eq!(err.kind(), "toml");
+        assert_eq!(err.location().line, Some(2));
+    }
+}
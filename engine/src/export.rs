@@ -0,0 +1,637 @@
+//! Export to graph interchange formats: `GraphML`, GEXF and DOT
+//!
+//! Each writer serialises a whole [`GraphStore`]; to export part of a graph,
+//! pass a [`GraphStore::subgraph`]. Nodes carry `type`, `domain`, `title`,
+//! `status` and `tags` (`;`-separated), edges `relation`, `domain` and
+//! `weight`, and both carry their scalar metadata keys as typed attributes.
+//! Depths and layout positions are added when given in [`ExportOptions`].
+//! Edges whose endpoints are not in the graph are left out, since none of
+//! the formats allows them.
+
+use crate::graph::{Edge, GraphStore, Node};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+
+/// Computed values to carry along with the graph's own attributes
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExportOptions<'a> {
+    /// Node depths, as from [`crate::layout::depth::compute_depths`]
+    pub depths: Option<&'a HashMap<String, u32>>,
+    /// Node positions, as from
+    /// [`crate::layout::truth_mine::compute_truth_mine_layout`] (a 2D
+    /// layout can use `z = 0`)
+    pub positions: Option<&'a HashMap<String, [f32; 3]>>,
+}
+
+/// Value type of an attribute column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AttrType {
+    Boolean,
+    Integer,
+    Double,
+    String,
+}
+
+impl AttrType {
+    /// Narrowest type that holds every value of a metadata key
+    fn of<'v>(values: impl Iterator<Item = &'v Value>) -> Self {
+        values
+            .fold(None, |kind, value| {
+                let this = match value {
+                    Value::Bool(_) => Self::Boolean,
+                    Value::Number(n) if n.is_i64() || n.is_u64() => Self::Integer,
+                    Value::Number(_) => Self::Double,
+                    _ => Self::String,
+                };
+                Some(match (kind, this) {
+                    (None, this) => this,
+                    (Some(kind), this) if kind == this => kind,
+                    (Some(Self::Integer | Self::Double), Self::Integer | Self::Double) => {
+                        Self::Double
+                    }
+                    _ => Self::String,
+                })
+            })
+            .unwrap_or(Self::String)
+    }
+
+    const fn graphml(self) -> &'static str {
+        match self {
+            Self::Boolean => "boolean",
+            Self::Integer => "int",
+            Self::Double => "double",
+            Self::String => "string",
+        }
+    }
+
+    const fn gexf(self) -> &'static str {
+        match self {
+            Self::Boolean => "boolean",
+            Self::Integer => "integer",
+            Self::Double => "double",
+            Self::String => "string",
+        }
+    }
+}
+
+/// Attribute columns and, per node or edge, their values as text
+struct Table {
+    columns: Vec<(String, AttrType)>,
+    rows: Vec<Vec<Option<String>>>,
+}
+
+impl Table {
+    fn nodes(nodes: &[&Node], options: &ExportOptions) -> Self {
+        let mut columns = vec![
+            ("type".to_string(), AttrType::String),
+            ("domain".to_string(), AttrType::String),
+            ("title".to_string(), AttrType::String),
+            ("status".to_string(), AttrType::String),
+            ("tags".to_string(), AttrType::String),
+        ];
+        if options.depths.is_some() {
+            columns.push(("depth".to_string(), AttrType::Integer));
+        }
+        let metadata = metadata_columns(nodes.iter().map(|node| &node.metadata), &columns);
+
+        let rows = nodes
+            .iter()
+            .map(|node| {
+                let mut row = vec![
+                    Some(node.r#type.clone()),
+                    Some(node.domain.clone()),
+                    Some(node.title.clone()),
+                    node.status.clone(),
+                    (!node.tags.is_empty()).then(|| node.tags.join(";")),
+                ];
+                if let Some(depths) = options.depths {
+                    row.push(depths.get(&node.id).map(u32::to_string));
+                }
+                row.extend(metadata_values(&node.metadata, &metadata));
+                row
+            })
+            .collect();
+        Self::with_metadata(columns, metadata, rows)
+    }
+
+    fn edges(edges: &[&Edge]) -> Self {
+        let columns = vec![
+            ("relation".to_string(), AttrType::String),
+            ("domain".to_string(), AttrType::String),
+            ("weight".to_string(), AttrType::Double),
+        ];
+        let metadata = metadata_columns(edges.iter().map(|edge| edge_metadata(edge)), &columns);
+
+        let rows = edges
+            .iter()
+            .map(|edge| {
+                let mut row = vec![
+                    Some(edge.relation.clone()),
+                    Some(edge.domain.clone()),
+                    edge.weight.map(|weight| weight.to_string()),
+                ];
+                row.extend(metadata_values(edge_metadata(edge), &metadata));
+                row
+            })
+            .collect();
+        Self::with_metadata(columns, metadata, rows)
+    }
+
+    fn with_metadata(
+        mut columns: Vec<(String, AttrType)>,
+        metadata: Vec<MetadataColumn>,
+        rows: Vec<Vec<Option<String>>>,
+    ) -> Self {
+        columns.extend(
+            metadata
+                .into_iter()
+                .map(|column| (column.name, column.kind)),
+        );
+        Self { columns, rows }
+    }
+}
+
+fn edge_metadata(edge: &Edge) -> &Value {
+    static NO_METADATA: Value = Value::Null;
+    edge.metadata.as_ref().unwrap_or(&NO_METADATA)
+}
+
+/// A metadata key exported as its own column
+struct MetadataColumn {
+    key: String,
+    name: String,
+    kind: AttrType,
+}
+
+/// Columns for every metadata key in use, sorted; a key that clashes with a
+/// fixed column is exported as `metadata_<key>`
+fn metadata_columns<'v>(
+    metadata: impl Iterator<Item = &'v Value>,
+    fixed: &[(String, AttrType)],
+) -> Vec<MetadataColumn> {
+    let mut values: BTreeMap<&str, Vec<&Value>> = BTreeMap::new();
+    for object in metadata.filter_map(Value::as_object) {
+        for (key, value) in object.iter().filter(|(_, value)| !value.is_null()) {
+            values.entry(key).or_default().push(value);
+        }
+    }
+    values
+        .into_iter()
+        .map(|(key, values)| {
+            let name = if fixed.iter().any(|(name, _)| name == key) {
+                format!("metadata_{key}")
+            } else {
+                key.to_string()
+            };
+            MetadataColumn {
+                key: key.to_string(),
+                name,
+                kind: AttrType::of(values.into_iter()),
+            }
+        })
+        .collect()
+}
+
+fn metadata_values<'c>(
+    metadata: &'c Value,
+    columns: &'c [MetadataColumn],
+) -> impl Iterator<Item = Option<String>> + 'c {
+    columns
+        .iter()
+        .map(move |column| match metadata.get(&column.key) {
+            None | Some(Value::Null) => None,
+            Some(Value::String(text)) => Some(text.clone()),
+            Some(value) => Some(value.to_string()),
+        })
+}
+
+/// Edges whose endpoints are both in the graph
+fn exported_edges(graph: &GraphStore) -> Vec<&Edge> {
+    let ids: HashSet<&str> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+    graph
+        .edges
+        .iter()
+        .filter(|edge| ids.contains(edge.from.as_str()) && ids.contains(edge.to.as_str()))
+        .collect()
+}
+
+/// Serialises a graph as `GraphML`
+///
+/// Attributes are declared as `<key>`s with ids `n0`, `n1`, … for nodes
+/// and `e0`, … for edges; positions become the node keys `x`, `y` and `z`.
+#[must_use]
+pub fn write_graphml(graph: &GraphStore, options: &ExportOptions) -> String {
+    let nodes: Vec<&Node> = graph.nodes.iter().collect();
+    let edges = exported_edges(graph);
+    let (node_table, edge_table) = (Table::nodes(&nodes, options), Table::edges(&edges));
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out += "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\" \
+            xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+            xsi:schemaLocation=\"http://graphml.graphdrawing.org/xmlns \
+            http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd\">\n";
+    for (prefix, target, table) in [("n", "node", &node_table), ("e", "edge", &edge_table)] {
+        for (idx, (name, kind)) in table.columns.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "  <key id=\"{prefix}{idx}\" for=\"{target}\" attr.name=\"{}\" attr.type=\"{}\"/>",
+                xml_escape(name),
+                kind.graphml()
+            );
+        }
+    }
+    if options.positions.is_some() {
+        for axis in ["x", "y", "z"] {
+            let _ = writeln!(
+                out,
+                "  <key id=\"{axis}\" for=\"node\" attr.name=\"{axis}\" attr.type=\"double\"/>"
+            );
+        }
+    }
+
+    out += "  <graph id=\"truth-mines\" edgedefault=\"directed\">\n";
+    for (node, row) in nodes.iter().zip(&node_table.rows) {
+        let _ = writeln!(out, "    <node id=\"{}\">", xml_escape(&node.id));
+        write_graphml_data(&mut out, "n", row);
+        if let Some(position) = options.positions.and_then(|p| p.get(&node.id)) {
+            for (axis, value) in ["x", "y", "z"].iter().zip(position) {
+                let _ = writeln!(out, "      <data key=\"{axis}\">{value}</data>");
+            }
+        }
+        out += "    </node>\n";
+    }
+    for (idx, (edge, row)) in edges.iter().zip(&edge_table.rows).enumerate() {
+        let _ = writeln!(
+            out,
+            "    <edge id=\"e{idx}\" source=\"{}\" target=\"{}\">",
+            xml_escape(&edge.from),
+            xml_escape(&edge.to)
+        );
+        write_graphml_data(&mut out, "e", row);
+        out += "    </edge>\n";
+    }
+    out += "  </graph>\n</graphml>\n";
+    out
+}
+
+fn write_graphml_data(out: &mut String, prefix: &str, row: &[Option<String>]) {
+    for (idx, value) in row.iter().enumerate() {
+        if let Some(value) = value {
+            let _ = writeln!(
+                out,
+                "      <data key=\"{prefix}{idx}\">{}</data>",
+                xml_escape(value)
+            );
+        }
+    }
+}
+
+/// Serialises a graph as GEXF 1.2
+///
+/// Titles become node labels and relations edge labels, as Gephi shows
+/// them; weights are also written as the edge `weight`, and positions as
+/// `viz:position`.
+#[must_use]
+pub fn write_gexf(graph: &GraphStore, options: &ExportOptions) -> String {
+    let nodes: Vec<&Node> = graph.nodes.iter().collect();
+    let edges = exported_edges(graph);
+    let (node_table, edge_table) = (Table::nodes(&nodes, options), Table::edges(&edges));
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out += "<gexf xmlns=\"http://www.gexf.net/1.2draft\" xmlns:viz=\"http://www.gexf.net/1.2draft/viz\" version=\"1.2\">\n";
+    out += "  <graph defaultedgetype=\"directed\" mode=\"static\">\n";
+    for (class, table) in [("node", &node_table), ("edge", &edge_table)] {
+        let _ = writeln!(out, "    <attributes class=\"{class}\">");
+        for (idx, (name, kind)) in table.columns.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "      <attribute id=\"{idx}\" title=\"{}\" type=\"{}\"/>",
+                xml_escape(name),
+                kind.gexf()
+            );
+        }
+        out += "    </attributes>\n";
+    }
+
+    out += "    <nodes>\n";
+    for (node, row) in nodes.iter().zip(&node_table.rows) {
+        let _ = writeln!(
+            out,
+            "      <node id=\"{}\" label=\"{}\">",
+            xml_escape(&node.id),
+            xml_escape(&node.title)
+        );
+        write_gexf_attvalues(&mut out, row);
+        if let Some([x, y, z]) = options.positions.and_then(|p| p.get(&node.id)) {
+            let _ = writeln!(out, "        <viz:position x=\"{x}\" y=\"{y}\" z=\"{z}\"/>");
+        }
+        out += "      </node>\n";
+    }
+    out += "    </nodes>\n    <edges>\n";
+    for (idx, (edge, row)) in edges.iter().zip(&edge_table.rows).enumerate() {
+        let weight = edge
+            .weight
+            .map(|weight| format!(" weight=\"{weight}\""))
+            .unwrap_or_default();
+        let _ = writeln!(
+            out,
+            "      <edge id=\"{idx}\" source=\"{}\" target=\"{}\" label=\"{}\"{weight}>",
+            xml_escape(&edge.from),
+            xml_escape(&edge.to),
+            xml_escape(&edge.relation)
+        );
+        write_gexf_attvalues(&mut out, row);
+        out += "      </edge>\n";
+    }
+    out += "    </edges>\n  </graph>\n</gexf>\n";
+    out
+}
+
+fn write_gexf_attvalues(out: &mut String, row: &[Option<String>]) {
+    if row.iter().all(Option::is_none) {
+        return;
+    }
+    let indent = "        ";
+    let _ = writeln!(out, "{indent}<attvalues>");
+    for (idx, value) in row.iter().enumerate() {
+        if let Some(value) = value {
+            let _ = writeln!(
+                out,
+                "{indent}  <attvalue for=\"{idx}\" value=\"{}\"/>",
+                xml_escape(value)
+            );
+        }
+    }
+    let _ = writeln!(out, "{indent}</attvalues>");
+}
+
+/// Serialises a graph as a Graphviz DOT digraph
+///
+/// Titles and relations become `label`s. The edge weight is written as
+/// `w`, since Graphviz's own `weight` must be an integer for `dot`, and
+/// positions as `pos="x,y"` (for `neato -n`) plus `z`.
+#[must_use]
+pub fn write_dot(graph: &GraphStore, options: &ExportOptions) -> String {
+    let nodes: Vec<&Node> = graph.nodes.iter().collect();
+    let edges = exported_edges(graph);
+    let (node_table, edge_table) = (Table::nodes(&nodes, options), Table::edges(&edges));
+
+    let mut out = String::from("digraph \"truth-mines\" {\n");
+    for (node, row) in nodes.iter().zip(&node_table.rows) {
+        let mut attributes = vec![("label".to_string(), node.title.clone())];
+        attributes.extend(dot_attributes(&node_table.columns, row));
+        if let Some([x, y, z]) = options.positions.and_then(|p| p.get(&node.id)) {
+            attributes.push(("pos".to_string(), format!("{x},{y}")));
+            attributes.push(("z".to_string(), z.to_string()));
+        }
+        let _ = writeln!(out, "  {} [{}];", dot_id(&node.id), dot_list(&attributes));
+    }
+    for (edge, row) in edges.iter().zip(&edge_table.rows) {
+        let mut attributes = vec![("label".to_string(), edge.relation.clone())];
+        attributes.extend(
+            dot_attributes(&edge_table.columns, row).map(|(name, value)| {
+                (
+                    if name == "weight" {
+                        "w".to_string()
+                    } else {
+                        name
+                    },
+                    value,
+                )
+            }),
+        );
+        let _ = writeln!(
+            out,
+            "  {} -> {} [{}];",
+            dot_id(&edge.from),
+            dot_id(&edge.to),
+            dot_list(&attributes)
+        );
+    }
+    out += "}\n";
+    out
+}
+
+fn dot_attributes<'r>(
+    columns: &'r [(String, AttrType)],
+    row: &'r [Option<String>],
+) -> impl Iterator<Item = (String, String)> + 'r {
+    columns
+        .iter()
+        .zip(row)
+        .filter_map(|((name, _), value)| Some((name.clone(), value.clone()?)))
+}
+
+fn dot_list(attributes: &[(String, String)]) -> String {
+    attributes
+        .iter()
+        .map(|(name, value)| format!("{}={}", dot_id(name), dot_id(value)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// DOT keywords, which are case-insensitive and must be quoted as IDs
+const DOT_KEYWORDS: [&str; 6] = ["node", "edge", "graph", "digraph", "subgraph", "strict"];
+
+/// Quotes a DOT identifier unless it is a plain alphanumeric name
+fn dot_id(text: &str) -> String {
+    let plain = text
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !DOT_KEYWORDS
+            .iter()
+            .any(|keyword| keyword.eq_ignore_ascii_case(text));
+    if plain {
+        return text.to_string();
+    }
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => {}
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn create_test_node(id: &str, title: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            r#type: "axiom".to_string(),
+            domain: "mathematics".to_string(),
+            title: title.to_string(),
+            content: None,
+            formal: None,
+            tags: vec![],
+            metadata: Value::Null,
+            sources: vec![],
+            created: None,
+            updated: None,
+            status: None,
+        }
+    }
+
+    fn create_graph() -> GraphStore {
+        let mut a = create_test_node("a", "A & \"B\"");
+        a.tags = vec!["logic".to_string(), "sets".to_string()];
+        a.metadata = json!({"certainty": 0.9, "importance": 5, "type": "clash"});
+        let mut b = create_test_node("b", "B");
+        b.metadata = json!({"certainty": 1, "contested": true});
+
+        let mut graph = GraphStore::new();
+        graph.add_node(a);
+        graph.add_node(b);
+        graph.add_edge(Edge {
+            from: "a".to_string(),
+            to: "b".to_string(),
+            relation: "proves".to_string(),
+            domain: "mathematics".to_string(),
+            weight: Some(0.9),
+            metadata: None,
+        });
+        graph.add_edge(Edge {
+            from: "a".to_string(),
+            to: "missing".to_string(),
+            relation: "supports".to_string(),
+            domain: "mathematics".to_string(),
+            weight: None,
+            metadata: None,
+        });
+        graph.build_adjacency();
+        graph
+    }
+
+    #[test]
+    fn test_metadata_columns_are_typed() {
+        let graph = create_graph();
+        let nodes: Vec<&Node> = graph.nodes.iter().collect();
+        let table = Table::nodes(&nodes, &ExportOptions::default());
+
+        let metadata: Vec<_> = table.columns[5..]
+            .iter()
+            .map(|(name, kind)| (name.as_str(), *kind))
+            .collect();
+        assert_eq!(
+            metadata,
+            vec![
+                ("certainty", AttrType::Double),
+                ("contested", AttrType::Boolean),
+                ("importance", AttrType::Integer),
+                ("metadata_type", AttrType::String),
+            ]
+        );
+        assert_eq!(table.rows[0][4].as_deref(), Some("logic;sets"));
+        assert_eq!(table.rows[1][7], None);
+    }
+
+    #[test]
+    fn test_graphml() {
+        let graph = create_graph();
+        let depths = HashMap::from([("a".to_string(), 0), ("b".to_string(), 1)]);
+        let positions = HashMap::from([("a".to_string(), [1.5, 0.0, -2.0])]);
+        let options = ExportOptions {
+            depths: Some(&depths),
+            positions: Some(&positions),
+        };
+
+        let graphml = write_graphml(&graph, &options);
+
+        assert!(
+            graphml.contains("<key id=\"n5\" for=\"node\" attr.name=\"depth\" attr.type=\"int\"/>")
+        );
+        assert!(graphml.contains("<data key=\"n2\">A &amp; &quot;B&quot;</data>"));
+        assert!(graphml.contains("<data key=\"x\">1.5</data>"));
+        assert!(graphml.contains("<edge id=\"e0\" source=\"a\" target=\"b\">"));
+        assert!(graphml.contains("<data key=\"e2\">0.9</data>"));
+        assert!(!graphml.contains("missing"), "dangling edge left out");
+        assert_eq!(graphml.matches("<node ").count(), 2);
+    }
+
+    #[test]
+    fn test_gexf() {
+        let graph = create_graph();
+        let positions = HashMap::from([("b".to_string(), [1.0, 2.0, 3.0])]);
+        let options = ExportOptions {
+            positions: Some(&positions),
+            ..ExportOptions::default()
+        };
+
+        let gexf = write_gexf(&graph, &options);
+
+        assert!(gexf.contains(
+            "<gexf xmlns=\"http://www.gexf.net/1.2draft\" xmlns:viz=\"http://www.gexf.net/1.2draft/viz\" version=\"1.2\">"
+        ));
+        assert!(gexf.contains("<node id=\"a\" label=\"A &amp; &quot;B&quot;\">"));
+        assert!(gexf.contains("<viz:position x=\"1\" y=\"2\" z=\"3\"/>"));
+        assert!(gexf
+            .contains("<edge id=\"0\" source=\"a\" target=\"b\" label=\"proves\" weight=\"0.9\">"));
+        assert!(gexf.contains("<attribute id=\"2\" title=\"weight\" type=\"double\"/>"));
+        assert!(gexf.contains("<attvalue for=\"6\" value=\"true\"/>"));
+    }
+
+    #[test]
+    fn test_dot() {
+        let graph = create_graph();
+        let depths = HashMap::from([("a".to_string(), 0)]);
+        let options = ExportOptions {
+            depths: Some(&depths),
+            ..ExportOptions::default()
+        };
+
+        let dot = write_dot(&graph, &options);
+
+        assert!(dot.starts_with("digraph \"truth-mines\" {\n"));
+        assert!(dot.contains("  a [label=\"A & \\\"B\\\"\", type=axiom, domain=mathematics,"));
+        assert!(dot.contains("depth=\"0\""));
+        assert!(dot
+            .contains("  a -> b [label=proves, relation=proves, domain=mathematics, w=\"0.9\"];"));
+        assert!(!dot.contains("missing"));
+    }
+
+    #[test]
+    fn test_subgraph_export() {
+        let graph = create_graph();
+        let dot = write_dot(&graph.subgraph(["b"]), &ExportOptions::default());
+        assert_eq!(dot.lines().count(), 3);
+        assert!(!dot.contains("->"));
+    }
+
+    #[test]
+    fn test_dot_id() {
+        assert_eq!(dot_id("abc_1"), "abc_1");
+        assert_eq!(dot_id("0lg001"), "\"0lg001\"");
+        assert_eq!(dot_id("a\nb"), "\"a\\nb\"");
+        assert_eq!(dot_id("node"), "\"node\"");
+        assert_eq!(dot_id("Strict"), "\"Strict\"");
+        assert_eq!(dot_id("SUBGRAPH"), "\"SUBGRAPH\"");
+        assert_eq!(dot_id("nodes"), "nodes");
+    }
+}
@@ -0,0 +1,559 @@
+//! Canonical formatting of node JSON and edge JSONL files
+//!
+//! Node files are written with two-space indentation, keys of the node,
+//! `metadata` and `metadata.domain_of_validity` in schema order (unknown keys
+//! after, sorted), other object keys sorted, `tags` sorted and arrays of plain
+//! values on one line. Edge files get one compact object per line with keys
+//! in schema order, lines sorted by `(f, t)` and `w` always written with a
+//! decimal point. Other numbers keep the text they were written with
+//! (`1e26` stays `1e26`). Values are never dropped, so unknown metadata
+//! survives formatting.
+
+use crate::error::{Error, Result};
+use crate::parsers::loader::{collect_files, read_file, EDGES_DIR, NODES_DIR};
+use crate::validate::{
+    DOMAIN_OF_VALIDITY_PROPERTIES, EDGE_PROPERTIES, NODE_METADATA_PROPERTIES, NODE_PROPERTIES,
+};
+use serde::Serialize;
+use serde_json::ser::{CompactFormatter, Formatter};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Key order of node files, down to the nested objects the schema describes
+const NODE_ORDER: KeyOrder = KeyOrder {
+    keys: NODE_PROPERTIES,
+    nested: &[(
+        "metadata",
+        KeyOrder {
+            keys: NODE_METADATA_PROPERTIES,
+            nested: &[(
+                "domain_of_validity",
+                KeyOrder {
+                    keys: DOMAIN_OF_VALIDITY_PROPERTIES,
+                    nested: &[],
+                },
+            )],
+        },
+    )],
+};
+
+/// Outcome of formatting a repository
+#[derive(Debug, Default)]
+pub struct FormatReport {
+    /// Files checked
+    pub checked: usize,
+    /// Files that were rewritten, or would be in check mode
+    pub changed: Vec<PathBuf>,
+    /// Files that could not be read, parsed or written; left untouched
+    pub diagnostics: Vec<Error>,
+}
+
+impl FormatReport {
+    /// Returns true if every file was already canonical and readable
+    #[must_use]
+    pub const fn is_clean(&self) -> bool {
+        self.changed.is_empty() && self.diagnostics.is_empty()
+    }
+}
+
+/// Formats one node JSON document
+///
+/// # Errors
+///
+/// Returns error if the JSON is malformed or not an object
+pub fn format_node_json(source: &str) -> Result<String> {
+    format_node_json_as(source, source)
+}
+
+/// Formats a node JSON document serialized from values read out of
+/// `written`, spelling numbers as they were written there
+pub(crate) fn format_node_json_as(source: &str, written: &str) -> Result<String> {
+    let value: Value = serde_json::from_str(source).map_err(|e| Error::from_json(&e))?;
+    let Value::Object(mut node) = value else {
+        return Err(Error::schema("node file must contain a JSON object"));
+    };
+
+    if let Some(Value::Array(tags)) = node.get_mut("tags") {
+        tags.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+    }
+
+    let numbers = WrittenNumbers::scan(written);
+    let mut out = String::new();
+    write_object(&mut out, &node, NODE_ORDER, &numbers, 0);
+    out.push('\n');
+    Ok(out)
+}
+
+/// Formats one edge JSONL document
+///
+/// Blank lines are dropped and edges are sorted by `(f, t)`, keeping the
+/// original order of edges between the same pair of nodes.
+///
+/// # Errors
+///
+/// Returns the first malformed line, located by line and column
+pub fn format_edges_jsonl(source: &str) -> Result<String> {
+    format_edges_jsonl_as(source, source)
+}
+
+/// Formats an edge JSONL document serialized from values read out of
+/// `written`, spelling numbers as they were written there
+pub(crate) fn format_edges_jsonl_as(source: &str, written: &str) -> Result<String> {
+    let mut edges = Vec::new();
+    for (line_num, line) in source.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value: Value = serde_json::from_str(line)
+            .map_err(|e| Error::from_json(&e).at(line_num + 1, e.column()))?;
+        let Value::Object(edge) = value else {
+            return Err(Error::schema("edge line must contain a JSON object").at(line_num + 1, 1));
+        };
+        edges.push(edge);
+    }
+
+    let endpoint = |edge: &Map<String, Value>, key: &str| {
+        edge.get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    edges.sort_by_cached_key(|edge| (endpoint(edge, "f"), endpoint(edge, "t")));
+
+    let numbers = WrittenNumbers::scan(written);
+    let mut out = String::new();
+    for edge in &edges {
+        let fields: Vec<String> = schema_ordered(edge, EDGE_PROPERTIES)
+            .map(|(key, value)| {
+                let value = if key == "w" {
+                    format_weight(value, &numbers)
+                } else {
+                    to_json(value, &numbers)
+                };
+                format!("{}:{value}", Value::from(key))
+            })
+            .collect();
+        out.push('{');
+        out.push_str(&fields.join(","));
+        out.push_str("}\n");
+    }
+    Ok(out)
+}
+
+/// Rewrites every node and edge file under `root` that is not canonical
+///
+/// TOON edge files are left alone.
+#[must_use]
+pub fn format_repository(root: &Path) -> FormatReport {
+    format_files(root, true)
+}
+
+/// Reports the files under `root` that [`format_repository`] would rewrite
+#[must_use]
+pub fn check_repository(root: &Path) -> FormatReport {
+    format_files(root, false)
+}
+
+fn format_files(root: &Path, write: bool) -> FormatReport {
+    let mut report = FormatReport::default();
+    let nodes = collect_files(&root.join(NODES_DIR), &["json"], &mut report.diagnostics);
+    let edges = collect_files(&root.join(EDGES_DIR), &["jsonl"], &mut report.diagnostics);
+
+    let jobs = nodes
+        .into_iter()
+        .map(|path| (path, format_node_json as fn(&str) -> Result<String>))
+        .chain(
+            edges
+                .into_iter()
+                .map(|path| (path, format_edges_jsonl as _)),
+        );
+
+    for (path, format) in jobs {
+        report.checked += 1;
+        let result = read_file(&path).and_then(|source| {
+            let formatted = format(&source).map_err(|e| e.with_path(&path))?;
+            if formatted == source {
+                return Ok(false);
+            }
+            if write {
+                std::fs::write(&path, formatted).map_err(|e| Error::from(e).with_path(&path))?;
+            }
+            Ok(true)
+        });
+        match result {
+            Ok(true) => report.changed.push(path),
+            Ok(false) => {}
+            Err(err) => report.diagnostics.push(err),
+        }
+    }
+
+    report
+}
+
+/// Returns the entries of `object` with `order` keys first, then the rest sorted
+fn schema_ordered<'a>(
+    object: &'a Map<String, Value>,
+    order: &'a [&str],
+) -> impl Iterator<Item = (&'a str, &'a Value)> {
+    let known = order
+        .iter()
+        .filter_map(|&key| object.get(key).map(|value| (key, value)));
+    let unknown = object
+        .iter()
+        .filter(|(key, _)| !order.contains(&key.as_str()))
+        .map(|(key, value)| (key.as_str(), value));
+    known.chain(unknown)
+}
+
+/// Writes a weight as its shortest round-trip form, always with a decimal point
+fn format_weight(value: &Value, numbers: &WrittenNumbers) -> String {
+    match value.as_f64() {
+        Some(weight) if weight.is_finite() && weight.fract() == 0.0 => format!("{weight:.1}"),
+        Some(weight) => weight.to_string(),
+        None => to_json(value, numbers),
+    }
+}
+
+/// Schema key order of an object and of the objects under some of its keys
+#[derive(Clone, Copy)]
+struct KeyOrder {
+    keys: &'static [&'static str],
+    nested: &'static [(&'static str, Self)],
+}
+
+impl KeyOrder {
+    /// Order for objects the schema says nothing about: keys sorted
+    const NONE: Self = Self {
+        keys: &[],
+        nested: &[],
+    };
+
+    fn of(self, key: &str) -> Self {
+        self.nested
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map_or(Self::NONE, |&(_, order)| order)
+    }
+}
+
+fn write_object(
+    out: &mut String,
+    object: &Map<String, Value>,
+    order: KeyOrder,
+    numbers: &WrittenNumbers,
+    indent: usize,
+) {
+    if object.is_empty() {
+        out.push_str("{}");
+        return;
+    }
+
+    out.push_str("{\n");
+    for (i, (key, value)) in schema_ordered(object, order.keys).enumerate() {
+        if i > 0 {
+            out.push_str(",\n");
+        }
+        push_indent(out, indent + 1);
+        out.push_str(&Value::from(key).to_string());
+        out.push_str(": ");
+        write_value(out, value, order.of(key), numbers, indent + 1);
+    }
+    out.push('\n');
+    push_indent(out, indent);
+    out.push('}');
+}
+
+fn write_value(
+    out: &mut String,
+    value: &Value,
+    order: KeyOrder,
+    numbers: &WrittenNumbers,
+    indent: usize,
+) {
+    match value {
+        Value::Object(map) => write_object(out, map, order, numbers, indent),
+        Value::Array(items) if items.iter().any(|item| item.is_object() || item.is_array()) => {
+            out.push_str("[\n");
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(",\n");
+                }
+                push_indent(out, indent + 1);
+                write_value(out, item, KeyOrder::NONE, numbers, indent + 1);
+            }
+            out.push('\n');
+            push_indent(out, indent);
+            out.push(']');
+        }
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(|item| to_json(item, numbers)).collect();
+            out.push('[');
+            out.push_str(&items.join(", "));
+            out.push(']');
+        }
+        _ => out.push_str(&to_json(value, numbers)),
+    }
+}
+
+/// Serializes a value compactly, writing floats as they appear in the source
+fn to_json(value: &Value, numbers: &WrittenNumbers) -> String {
+    let mut out = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, NumberText(numbers));
+    // Writing a `Value` to memory cannot fail
+    let _ = value.serialize(&mut serializer);
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Source text of the numbers in a document, by value
+///
+/// A value written two different ways has no text, so both are written in
+/// canonical form and formatting stays idempotent.
+struct WrittenNumbers(HashMap<u64, Option<String>>);
+
+impl WrittenNumbers {
+    /// Collects the number literals of a JSON or JSONL document
+    fn scan(source: &str) -> Self {
+        let mut numbers = HashMap::new();
+        let mut record = |text: &str| {
+            if let Ok(value) = text.parse::<f64>() {
+                numbers
+                    .entry(value.to_bits())
+                    .and_modify(|written: &mut Option<String>| {
+                        if written.as_deref() != Some(text) {
+                            *written = None;
+                        }
+                    })
+                    .or_insert_with(|| Some(text.to_string()));
+            }
+        };
+
+        let (mut in_string, mut escaped, mut start) = (false, false, None);
+        for (i, byte) in source.bytes().enumerate() {
+            if in_string {
+                match byte {
+                    _ if escaped => escaped = false,
+                    b'\\' => escaped = true,
+                    b'"' => in_string = false,
+                    _ => {}
+                }
+            } else if matches!(byte, b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-') {
+                start.get_or_insert(i);
+            } else {
+                if let Some(start) = start.take() {
+                    record(&source[start..i]);
+                }
+                in_string = byte == b'"';
+            }
+        }
+        if let Some(start) = start {
+            record(&source[start..]);
+        }
+
+        Self(numbers)
+    }
+
+    fn text(&self, value: f64) -> Option<&str> {
+        self.0.get(&value.to_bits()).and_then(Option::as_deref)
+    }
+}
+
+/// Compact JSON writing floats with their source text, or else without the
+/// `+` `serde_json` puts in positive exponents
+struct NumberText<'a>(&'a WrittenNumbers);
+
+impl Formatter for NumberText<'_> {
+    fn write_f64<W: ?Sized + io::Write>(&mut self, writer: &mut W, value: f64) -> io::Result<()> {
+        if let Some(text) = self.0.text(value) {
+            return writer.write_all(text.as_bytes());
+        }
+        let mut text = Vec::new();
+        CompactFormatter.write_f64(&mut text, value)?;
+        text.retain(|&byte| byte != b'+');
+        writer.write_all(&text)
+    }
+}
+
+fn push_indent(out: &mut String, indent: usize) {
+    out.push_str(&"  ".repeat(indent));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_node_orders_keys_and_sorts_tags() {
+        let source =
+            r#"{"title":"T","tags":["b","a"],"id":"abc123","domain":"physics","type":"law"}"#;
+        let formatted = format_node_json(source).unwrap();
+
+        assert_eq!(
+            formatted,
+            "{\n  \"id\": \"abc123\",\n  \"type\": \"law\",\n  \"domain\": \"physics\",\n  \"title\": \"T\",\n  \"tags\": [\"a\", \"b\"]\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_format_node_preserves_unknown_metadata() {
+        let source = r#"{
+            "id": "abc123", "type": "law", "domain": "physics", "title": "T",
+            "x_custom": 1,
+            "metadata": {"zeta": {"nested": [1, {"deep": true}]}, "importance": 9.0}
+        }"#;
+        let formatted = format_node_json(source).unwrap();
+
+        let before: Value = serde_json::from_str(source).unwrap();
+        let after: Value = serde_json::from_str(&formatted).unwrap();
+        assert_eq!(before, after);
+        assert!(formatted.contains("\"importance\": 9.0"));
+        assert!(formatted.find("\"metadata\"").unwrap() < formatted.find("\"x_custom\"").unwrap());
+    }
+
+    #[test]
+    fn test_format_node_orders_nested_schema_keys() {
+        let source = r#"{"id":"abc123","type":"theory","domain":"physics","title":"T",
+            "metadata":{"domain_of_validity":{"units":"m","max_scale":1e26,"min_scale":1e-18}}}"#;
+        let formatted = format_node_json(source).unwrap();
+
+        assert!(
+            formatted.contains(concat!(
+                "    \"domain_of_validity\": {\n",
+                "      \"min_scale\": 1e-18,\n",
+                "      \"max_scale\": 1e26,\n",
+                "      \"units\": \"m\"\n",
+                "    }"
+            )),
+            "{formatted}"
+        );
+        assert_eq!(format_node_json(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_node_keeps_number_text() {
+        let source = r#"{"id":"abc123","type":"law","domain":"physics","title":"T",
+            "metadata":{"importance":9.50,"scales":[1e15, 1E5, 2.5e-3]}}"#;
+        let formatted = format_node_json(source).unwrap();
+
+        assert!(formatted.contains("\"importance\": 9.50"), "{formatted}");
+        assert!(
+            formatted.contains("\"scales\": [1e15, 1E5, 2.5e-3]"),
+            "{formatted}"
+        );
+    }
+
+    #[test]
+    fn test_format_node_writes_ambiguous_numbers_canonically() {
+        let source = r#"{"id":"abc123","type":"law","domain":"physics","title":"T",
+            "metadata":{"a":1e26,"b":10e25,"label":"1e99"}}"#;
+        let formatted = format_node_json(source).unwrap();
+
+        assert!(formatted.contains("\"a\": 1e26"), "{formatted}");
+        assert!(formatted.contains("\"b\": 1e26"), "{formatted}");
+        assert_eq!(format_node_json(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_edges_keeps_number_text() {
+        let source = "{\"f\":\"a\",\"t\":\"b\",\"w\":1,\"metadata\":{\"scale\":1e26}}\n";
+        let formatted = format_edges_jsonl(source).unwrap();
+
+        assert_eq!(
+            formatted,
+            "{\"f\":\"a\",\"t\":\"b\",\"w\":1.0,\"metadata\":{\"scale\":1e26}}\n"
+        );
+    }
+
+    #[test]
+    fn test_format_node_is_idempotent() {
+        let source = r#"{"id":"abc123","type":"law","domain":"physics","title":"T",
+            "metadata":{"certainty":0.5,"conditions":[{"a":1}]},"sources":["s2","s1"]}"#;
+        let once = format_node_json(source).unwrap();
+
+        assert_eq!(format_node_json(&once).unwrap(), once);
+        assert!(
+            once.contains("\"sources\": [\"s2\", \"s1\"]"),
+            "sources keep their order"
+        );
+    }
+
+    #[test]
+    fn test_format_node_rejects_non_object() {
+        let err = format_node_json("[1, 2]").unwrap_err();
+        assert!(err.message().contains("JSON object"));
+    }
+
+    #[test]
+    fn test_format_edges_sorts_and_orders_keys() {
+        let source = concat!(
+            "{\"domain\":\"physics\",\"w\":1,\"t\":\"bbb111\",\"f\":\"zzz999\",\"relation\":\"supports\"}\n",
+            "\n",
+            "{\"f\":\"aaa000\",\"t\":\"ccc222\",\"relation\":\"supports\",\"w\":0.90,\"domain\":\"physics\",\"note\":\"kept\"}\n",
+        );
+        let formatted = format_edges_jsonl(source).unwrap();
+
+        assert_eq!(
+            formatted,
+            concat!(
+                "{\"f\":\"aaa000\",\"t\":\"ccc222\",\"relation\":\"supports\",\"w\":0.9,\"domain\":\"physics\",\"note\":\"kept\"}\n",
+                "{\"f\":\"zzz999\",\"t\":\"bbb111\",\"relation\":\"supports\",\"w\":1.0,\"domain\":\"physics\"}\n",
+            )
+        );
+        assert_eq!(format_edges_jsonl(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_edges_reports_bad_line() {
+        let source = "{\"f\":\"a\",\"t\":\"b\"}\n{\"f\":\n";
+        let err = format_edges_jsonl(source).unwrap_err();
+        assert_eq!(err.location().line, Some(2));
+    }
+
+    #[test]
+    fn test_check_and_format_repository() {
+        let root = std::env::temp_dir().join(format!("truth_mines_format_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join(NODES_DIR)).unwrap();
+        std::fs::create_dir_all(root.join(EDGES_DIR)).unwrap();
+        let node_path = root.join(NODES_DIR).join("abc123.json");
+        std::fs::write(
+            &node_path,
+            r#"{"type":"law","id":"abc123","domain":"physics","title":"T"}"#,
+        )
+        .unwrap();
+        std::fs::write(root.join(NODES_DIR).join("broken.json"), "{").unwrap();
+        let canonical = "{\"f\":\"abc123\",\"t\":\"abc123\",\"relation\":\"supports\",\"domain\":\"physics\"}\n";
+        std::fs::write(root.join(EDGES_DIR).join("supports.jsonl"), canonical).unwrap();
+
+        let check = check_repository(&root);
+        assert_eq!(check.checked, 3);
+        assert_eq!(check.changed, vec![node_path.clone()]);
+        assert_eq!(check.diagnostics.len(), 1);
+        assert!(std::fs::read_to_string(&node_path)
+            .unwrap()
+            .starts_with("{\"type\""));
+
+        let format = format_repository(&root);
+        assert_eq!(format.changed, vec![node_path]);
+        assert!(check_repository(&root).changed.is_empty());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_project_data_is_canonical() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        if !root.join(NODES_DIR).exists() {
+            return;
+        }
+
+        let report = check_repository(root);
+        assert!(report.diagnostics.is_empty(), "{:?}", report.diagnostics);
+        assert!(
+            report.changed.is_empty(),
+            "Run `truth-mines fmt` to format: {:?}",
+            report.changed
+        );
+    }
+}
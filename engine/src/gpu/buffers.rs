@@ -1,10 +1,16 @@
 //! GPU buffer generation
 
+use super::flags::{structural_edge_flags, structural_node_flags};
+use super::picking::edge_record_indices;
+use super::{GpuEdge, GpuNode};
 use crate::graph::GraphStore;
 use crate::style::StyleConfig;
 use std::collections::HashMap;
 
-/// Generates GPU node buffer
+/// Builds GPU node records
+///
+/// Index `i` of the result corresponds to `graph.nodes[i]`. Structural
+/// render flags (see `gpu::flags`) are filled in.
 ///
 /// # Arguments
 ///
@@ -12,44 +18,63 @@ use std::collections::HashMap;
 /// * `layout` - 3D positions for each node
 /// * `depths` - Depth values for each node
 /// * `style` - Style configuration
-///
-/// # Returns
-///
-/// Byte vector ready for GPU upload
 #[must_use]
 #[allow(clippy::implicit_hasher)]
-pub fn generate_node_buffer(
+pub fn build_gpu_nodes(
     graph: &GraphStore,
     layout: &HashMap<String, [f32; 3]>,
     depths: &HashMap<String, u32>,
     style: &StyleConfig,
-) -> Vec<u8> {
+) -> Vec<GpuNode> {
     let mut gpu_nodes = Vec::with_capacity(graph.nodes.len());
 
-    for node in &graph.nodes {
+    for (node, flags) in graph.nodes.iter().zip(structural_node_flags(graph)) {
         let position = layout.get(&node.id).copied().unwrap_or([0.0, 0.0, 0.0]);
         let depth = depths.get(&node.id).copied().unwrap_or(0);
 
-        let gpu_node = style.map_node_to_gpu(node, depth, position);
+        let mut gpu_node = style.map_node_to_gpu(node, depth, position);
+        gpu_node.flags = flags.bits();
         gpu_nodes.push(gpu_node);
     }
 
-    bytemuck::cast_slice(&gpu_nodes).to_vec()
+    gpu_nodes
 }
 
-/// Generates GPU edge buffer
+/// Generates GPU node buffer
 ///
 /// # Arguments
 ///
 /// * `graph` - Graph store
+/// * `layout` - 3D positions for each node
+/// * `depths` - Depth values for each node
 /// * `style` - Style configuration
 ///
 /// # Returns
 ///
 /// Byte vector ready for GPU upload
 #[must_use]
+#[allow(clippy::implicit_hasher)]
+pub fn generate_node_buffer(
+    graph: &GraphStore,
+    layout: &HashMap<String, [f32; 3]>,
+    depths: &HashMap<String, u32>,
+    style: &StyleConfig,
+) -> Vec<u8> {
+    bytemuck::cast_slice(&build_gpu_nodes(graph, layout, depths, style)).to_vec()
+}
+
+/// Builds GPU edge records
+///
+/// Edges whose endpoints are not both present in the graph are skipped.
+/// Structural render flags (see `gpu::flags`) are filled in.
+///
+/// # Arguments
+///
+/// * `graph` - Graph store
+/// * `style` - Style configuration
+#[must_use]
 #[allow(clippy::cast_possible_truncation)]
-pub fn generate_edge_buffer(graph: &GraphStore, style: &StyleConfig) -> Vec<u8> {
+pub fn build_gpu_edges(graph: &GraphStore, style: &StyleConfig) -> Vec<GpuEdge> {
     let mut gpu_edges = Vec::with_capacity(graph.edges.len());
 
     for edge in &graph.edges {
@@ -62,13 +87,60 @@ pub fn generate_edge_buffer(graph: &GraphStore, style: &StyleConfig) -> Vec<u8>
         }
     }
 
-    bytemuck::cast_slice(&gpu_edges).to_vec()
+    for (gpu_edge, flags) in gpu_edges.iter_mut().zip(structural_edge_flags(graph)) {
+        gpu_edge.flags = flags.bits();
+    }
+
+    gpu_edges
+}
+
+/// Generates GPU edge buffer
+///
+/// # Arguments
+///
+/// * `graph` - Graph store
+/// * `style` - Style configuration
+///
+/// # Returns
+///
+/// Byte vector ready for GPU upload
+#[must_use]
+pub fn generate_edge_buffer(graph: &GraphStore, style: &StyleConfig) -> Vec<u8> {
+    bytemuck::cast_slice(&build_gpu_edges(graph, style)).to_vec()
+}
+
+/// Rewrites only the colour channel of existing GPU node records
+///
+/// Used when switching themes: positions, metrics and flags are left intact.
+///
+/// # Arguments
+///
+/// * `gpu_nodes` - Records previously built by [`build_gpu_nodes`]
+/// * `graph` - Graph store the records were built from
+/// * `style` - New style configuration
+pub fn recolor_nodes(gpu_nodes: &mut [GpuNode], graph: &GraphStore, style: &StyleConfig) {
+    for (gpu_node, node) in gpu_nodes.iter_mut().zip(&graph.nodes) {
+        gpu_node.color = style.get_domain_color(&node.domain);
+    }
+}
+
+/// Rewrites only the colour channel of existing GPU edge records
+///
+/// # Arguments
+///
+/// * `gpu_edges` - Records previously built by [`build_gpu_edges`]
+/// * `graph` - Graph store the records were built from
+/// * `style` - New style configuration
+pub fn recolor_edges(gpu_edges: &mut [GpuEdge], graph: &GraphStore, style: &StyleConfig) {
+    for (gpu_edge, idx) in gpu_edges.iter_mut().zip(edge_record_indices(graph)) {
+        gpu_edge.color = style.get_relation_color(&graph.edges[idx].relation);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::gpu::{GpuEdge, GpuNode};
+    use crate::gpu::RenderFlags;
     use crate::graph::{Edge, Node};
     use crate::layout::{depth::compute_depths, truth_mine::compute_truth_mine_layout};
 
@@ -85,6 +157,7 @@ mod tests {
             sources: vec![],
             created: None,
             updated: None,
+            status: None,
         }
     }
 
@@ -125,7 +198,31 @@ mod tests {
         let style = StyleConfig::default();
         let buffer = generate_edge_buffer(&store, &style);
 
-        assert_eq!(buffer.len(), 1 * std::mem::size_of::<GpuEdge>());
+        assert_eq!(buffer.len(), std::mem::size_of::<GpuEdge>());
+    }
+
+    #[test]
+    fn test_build_populates_structural_flags() {
+        let mut store = GraphStore::new();
+        store.add_node(create_test_node("a"));
+        store.add_node(create_test_node("b"));
+        store.add_edge(Edge {
+            from: "a".to_string(),
+            to: "b".to_string(),
+            relation: "supports".to_string(),
+            domain: "bridge:phil→math".to_string(),
+            weight: None,
+            metadata: None,
+        });
+        store.build_adjacency();
+
+        let style = StyleConfig::default();
+        let nodes = build_gpu_nodes(&store, &HashMap::new(), &HashMap::new(), &style);
+        let edges = build_gpu_edges(&store, &style);
+
+        assert_eq!(nodes[0].flags, RenderFlags::FOUNDATION.bits());
+        assert_eq!(nodes[1].flags, 0);
+        assert_eq!(edges[0].flags, RenderFlags::BRIDGE.bits());
     }
 
     #[test]
@@ -152,7 +249,57 @@ mod tests {
             let _edge_buffer = generate_edge_buffer(&store, &style);
 
             assert!(!node_buffer.is_empty());
-            assert!(node_buffer.len() % std::mem::size_of::<GpuNode>() == 0);
+            assert!(node_buffer
+                .len()
+                .is_multiple_of(std::mem::size_of::<GpuNode>()));
         }
     }
+
+    #[test]
+    fn test_recolor_changes_only_color() {
+        let mut store = GraphStore::new();
+        store.add_node(create_test_node("a"));
+        store.add_node(create_test_node("b"));
+        store.add_edge(Edge {
+            from: "a".to_string(),
+            to: "b".to_string(),
+            relation: "supports".to_string(),
+            domain: "philosophy".to_string(),
+            weight: Some(0.9),
+            metadata: None,
+        });
+        // Dangling edge is not rendered and must not shift the recolor mapping
+        store.add_edge(Edge {
+            from: "a".to_string(),
+            to: "missing".to_string(),
+            relation: "attacks".to_string(),
+            domain: "philosophy".to_string(),
+            weight: None,
+            metadata: None,
+        });
+
+        let layout = HashMap::from([("b".to_string(), [1.0, 2.0, 3.0])]);
+        let light = StyleConfig::default();
+        let dark = StyleConfig::from_toml(
+            r"
+[colors.domains]
+philosophy = [255, 0, 0]
+
+[colors.relations]
+supports = [0, 255, 0]
+",
+        )
+        .unwrap();
+
+        let mut nodes = build_gpu_nodes(&store, &layout, &HashMap::new(), &light);
+        let mut edges = build_gpu_edges(&store, &light);
+        recolor_nodes(&mut nodes, &store, &dark);
+        recolor_edges(&mut edges, &store, &dark);
+
+        assert_eq!(nodes[1].color, [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(nodes[1].position, [1.0, 2.0, 3.0]);
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].color, [0.0, 1.0, 0.0, 1.0]);
+        assert!((edges[0].weight - 0.9).abs() < 0.001);
+    }
 }
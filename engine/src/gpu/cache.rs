@@ -0,0 +1,122 @@
+//! GPU records kept between frames
+//!
+//! A [`GpuCache`] holds the node and edge records last handed to the
+//! renderer so that later changes can be uploaded as patches. When the graph,
+//! layout or theme changes wholesale the cache is rebuilt; a rebuilt cache
+//! starts with every record dirty and reports [`GpuCache::take_rebuilt`], so
+//! a renderer that only applies patches knows to reallocate its buffers.
+
+use super::buffers::{build_gpu_edges, build_gpu_nodes};
+use super::dirty::TrackedBuffer;
+use super::{GpuEdge, GpuNode};
+use crate::graph::GraphStore;
+use crate::style::StyleConfig;
+use std::collections::HashMap;
+
+/// Node and edge records with change tracking
+#[derive(Debug, Clone)]
+pub struct GpuCache {
+    /// Node records, in node order
+    pub nodes: TrackedBuffer<GpuNode>,
+    /// Edge records, in edge buffer order
+    pub edges: TrackedBuffer<GpuEdge>,
+    rebuilt: bool,
+}
+
+impl GpuCache {
+    /// Builds records for the current graph; every record starts dirty
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - Graph store
+    /// * `layout` - 3D positions for each node
+    /// * `depths` - Depth values for each node
+    /// * `style` - Style configuration
+    #[must_use]
+    #[allow(clippy::implicit_hasher)]
+    pub fn build(
+        graph: &GraphStore,
+        layout: &HashMap<String, [f32; 3]>,
+        depths: &HashMap<String, u32>,
+        style: &StyleConfig,
+    ) -> Self {
+        let mut nodes = TrackedBuffer::new(build_gpu_nodes(graph, layout, depths, style));
+        let mut edges = TrackedBuffer::new(build_gpu_edges(graph, style));
+        nodes.mark_all();
+        edges.mark_all();
+
+        Self {
+            nodes,
+            edges,
+            rebuilt: true,
+        }
+    }
+
+    /// Returns true once if the cache was rebuilt since the last upload
+    ///
+    /// Record counts may then differ from the renderer's buffers, and the
+    /// pending patches cover every record.
+    pub fn take_rebuilt(&mut self) -> bool {
+        std::mem::take(&mut self.rebuilt)
+    }
+
+    /// Records a full upload: pending patches and the rebuilt signal are dropped
+    pub fn mark_uploaded(&mut self) {
+        self.nodes.mark_clean();
+        self.edges.mark_clean();
+        self.rebuilt = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Node;
+
+    fn create_test_node(id: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            r#type: "proposition".to_string(),
+            domain: "philosophy".to_string(),
+            title: format!("Node {id}"),
+            content: None,
+            formal: None,
+            tags: vec![],
+            metadata: serde_json::Value::Null,
+            sources: vec![],
+            created: None,
+            updated: None,
+            status: None,
+        }
+    }
+
+    fn build(graph: &GraphStore) -> GpuCache {
+        GpuCache::build(
+            graph,
+            &HashMap::new(),
+            &HashMap::new(),
+            &StyleConfig::default(),
+        )
+    }
+
+    #[test]
+    fn test_rebuild_after_reload_reports_every_record() {
+        let mut graph = GraphStore::new();
+        graph.add_node(create_test_node("a"));
+        let mut cache = build(&graph);
+        cache.mark_uploaded();
+        assert!(!cache.take_rebuilt());
+        assert!(cache.nodes.take_patches().is_empty());
+
+        graph.add_node(create_test_node("b"));
+        graph.add_node(create_test_node("c"));
+        let mut cache = build(&graph);
+
+        assert!(cache.take_rebuilt());
+        assert!(!cache.take_rebuilt());
+        let patches = cache.nodes.take_patches();
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].offset, 0);
+        assert_eq!(patches[0].bytes.len(), 3 * std::mem::size_of::<GpuNode>());
+    }
+}
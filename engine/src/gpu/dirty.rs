@@ -0,0 +1,263 @@
+//! Dirty-range tracking for partial GPU buffer updates
+//!
+//! Regenerating and re-uploading full buffers on every interaction is wasteful
+//! on large graphs when only a few records (salience, highlight flags) change.
+//! [`TrackedBuffer`] keeps the typed records alongside the set of modified
+//! indices and hands out coalesced [`BufferPatch`]es for just those records.
+
+use bytemuck::Pod;
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+/// Set of modified record indices
+#[derive(Debug, Clone, Default)]
+pub struct DirtyRanges {
+    indices: BTreeSet<usize>,
+    all: bool,
+}
+
+impl DirtyRanges {
+    /// Creates an empty (clean) set
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks a single record as modified
+    pub fn mark(&mut self, index: usize) {
+        if !self.all {
+            self.indices.insert(index);
+        }
+    }
+
+    /// Marks every record as modified
+    pub fn mark_all(&mut self) {
+        self.all = true;
+        self.indices.clear();
+    }
+
+    /// Returns true if nothing has been modified
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        !self.all && self.indices.is_empty()
+    }
+
+    /// Returns the modified records as coalesced index ranges and clears the set
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - Current number of records; ranges are clamped to it
+    #[allow(clippy::single_range_in_vec_init)] // one range covering every record
+    pub fn take(&mut self, len: usize) -> Vec<Range<usize>> {
+        let ranges = if self.all {
+            if len == 0 {
+                Vec::new()
+            } else {
+                vec![0..len]
+            }
+        } else {
+            let mut ranges: Vec<Range<usize>> = Vec::new();
+            for &index in self.indices.iter().take_while(|&&i| i < len) {
+                match ranges.last_mut() {
+                    Some(last) if last.end == index => last.end = index + 1,
+                    _ => ranges.push(index..index + 1),
+                }
+            }
+            ranges
+        };
+
+        self.all = false;
+        self.indices.clear();
+        ranges
+    }
+}
+
+/// A contiguous run of changed bytes
+///
+/// `offset` is relative to the first record, i.e. it excludes any header
+/// added by `gpu::format`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BufferPatch {
+    /// Byte offset of the first changed record
+    pub offset: usize,
+    /// Replacement bytes (a whole number of records)
+    pub bytes: Vec<u8>,
+}
+
+/// Typed GPU records with change tracking
+#[derive(Debug, Clone)]
+pub struct TrackedBuffer<T: Pod> {
+    records: Vec<T>,
+    dirty: DirtyRanges,
+}
+
+impl<T: Pod> TrackedBuffer<T> {
+    /// Wraps freshly built records; the buffer starts clean
+    #[must_use]
+    pub fn new(records: Vec<T>) -> Self {
+        Self {
+            records,
+            dirty: DirtyRanges::new(),
+        }
+    }
+
+    /// Returns all records
+    #[must_use]
+    pub fn records(&self) -> &[T] {
+        &self.records
+    }
+
+    /// Returns the number of records
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns true if there are no records
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Returns true if any record changed since the last `take_patches`
+    #[must_use]
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty.is_empty()
+    }
+
+    /// Modifies one record, marking it dirty only if its bytes changed
+    ///
+    /// # Returns
+    ///
+    /// `false` if `index` is out of range
+    pub fn update(&mut self, index: usize, f: impl FnOnce(&mut T)) -> bool {
+        let Some(record) = self.records.get_mut(index) else {
+            return false;
+        };
+
+        let before = *record;
+        f(record);
+        if bytemuck::bytes_of(&before) != bytemuck::bytes_of(record) {
+            self.dirty.mark(index);
+        }
+        true
+    }
+
+    /// Gives mutable access to every record and marks the whole buffer dirty
+    pub fn modify_all(&mut self) -> &mut [T] {
+        self.dirty.mark_all();
+        &mut self.records
+    }
+
+    /// Marks every record dirty, e.g. after the records were rebuilt
+    pub fn mark_all(&mut self) {
+        self.dirty.mark_all();
+    }
+
+    /// Returns byte patches for all records changed since the last call
+    pub fn take_patches(&mut self) -> Vec<BufferPatch> {
+        let stride = std::mem::size_of::<T>();
+
+        self.dirty
+            .take(self.records.len())
+            .into_iter()
+            .map(|range| BufferPatch {
+                offset: range.start * stride,
+                bytes: bytemuck::cast_slice(&self.records[range]).to_vec(),
+            })
+            .collect()
+    }
+
+    /// Discards pending changes, e.g. after a full upload
+    pub fn mark_clean(&mut self) {
+        self.dirty.take(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::GpuNode;
+    use bytemuck::Zeroable;
+
+    #[test]
+    fn test_dirty_ranges_coalesce_adjacent() {
+        let mut dirty = DirtyRanges::new();
+        for index in [7, 2, 3, 4, 9] {
+            dirty.mark(index);
+        }
+
+        assert_eq!(dirty.take(10), vec![2..5, 7..8, 9..10]);
+        assert!(dirty.is_empty());
+    }
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn test_dirty_ranges_mark_all_and_clamp() {
+        let mut dirty = DirtyRanges::new();
+        dirty.mark(20);
+        assert!(dirty.take(10).is_empty());
+
+        dirty.mark_all();
+        dirty.mark(3);
+        assert_eq!(dirty.take(5), vec![0..5]);
+    }
+
+    #[test]
+    fn test_tracked_buffer_starts_clean() {
+        let mut buffer = TrackedBuffer::new(vec![GpuNode::zeroed(); 4]);
+
+        assert!(!buffer.is_dirty());
+        assert!(buffer.take_patches().is_empty());
+    }
+
+    #[test]
+    fn test_tracked_buffer_patch_offsets() {
+        let mut buffer = TrackedBuffer::new(vec![GpuNode::zeroed(); 4]);
+
+        buffer.update(1, |n| n.flags = 1);
+        buffer.update(2, |n| n.scalar = 0.5);
+
+        let patches = buffer.take_patches();
+        let stride = std::mem::size_of::<GpuNode>();
+
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].offset, stride);
+        assert_eq!(patches[0].bytes.len(), 2 * stride);
+        assert!(!buffer.is_dirty());
+    }
+
+    #[test]
+    fn test_tracked_buffer_ignores_noop_updates() {
+        let mut buffer = TrackedBuffer::new(vec![GpuNode::zeroed(); 2]);
+
+        assert!(buffer.update(0, |n| n.flags = 0));
+        assert!(!buffer.update(5, |n| n.flags = 1));
+
+        assert!(!buffer.is_dirty());
+    }
+
+    #[test]
+    fn test_tracked_buffer_modify_all() {
+        let mut buffer = TrackedBuffer::new(vec![GpuNode::zeroed(); 3]);
+
+        for node in buffer.modify_all() {
+            node.color = [1.0, 0.0, 0.0, 1.0];
+        }
+
+        let patches = buffer.take_patches();
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].offset, 0);
+        assert_eq!(patches[0].bytes.len(), 3 * std::mem::size_of::<GpuNode>());
+    }
+
+    #[test]
+    fn test_tracked_buffer_mark_clean() {
+        let mut buffer = TrackedBuffer::new(vec![GpuNode::zeroed(); 2]);
+        buffer.update(0, |n| n.flags = 4);
+
+        buffer.mark_clean();
+
+        assert!(buffer.take_patches().is_empty());
+    }
+}
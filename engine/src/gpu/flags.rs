@@ -0,0 +1,445 @@
+//! Typed render flags for `GpuNode.flags` and `GpuEdge.flags`
+//!
+//! Flags fall into two groups:
+//!
+//! - **Structural** flags are derived from the graph itself and written when
+//!   buffers are built (foundation, in-cycle, bridge, cross-domain,
+//!   dangling-source).
+//! - **Interaction** flags are driven by the viewer (selected, hovered,
+//!   focus path, search highlight) and applied to a [`TrackedBuffer`] so that
+//!   only the affected records are re-uploaded.
+
+use super::dirty::TrackedBuffer;
+use super::{GpuEdge, GpuNode};
+use crate::graph::GraphStore;
+use bytemuck::Pod;
+use std::collections::HashSet;
+use std::ops::{BitAnd, BitOr, BitOrAssign};
+
+/// Relations that make a node non-foundational (as in `compute_depths`)
+const EPISTEMIC_RELATIONS: [&str; 3] = ["supports", "proves", "entails"];
+
+/// Bitfield stored in the `flags` field of GPU records
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct RenderFlags(u32);
+
+impl RenderFlags {
+    /// No flags set
+    pub const NONE: Self = Self(0);
+    /// Node is the current selection
+    pub const SELECTED: Self = Self(1 << 0);
+    /// Node is under the pointer
+    pub const HOVERED: Self = Self(1 << 1);
+    /// Node or edge lies on the focused path
+    pub const FOCUS_PATH: Self = Self(1 << 2);
+    /// Node has no incoming supports/proves/entails edges
+    pub const FOUNDATION: Self = Self(1 << 3);
+    /// Node or edge is part of a directed cycle
+    pub const IN_CYCLE: Self = Self(1 << 4);
+    /// Edge domain is a `bridge:` domain
+    pub const BRIDGE: Self = Self(1 << 5);
+    /// Edge connects nodes of different domains
+    pub const CROSS_DOMAIN: Self = Self(1 << 6);
+    /// Node is the source of an edge whose target does not exist
+    pub const DANGLING_SOURCE: Self = Self(1 << 7);
+    /// Node matches the current search
+    pub const SEARCH_MATCH: Self = Self(1 << 8);
+
+    /// Flags derived from graph structure (rewritten on rebuild only)
+    pub const STRUCTURAL: Self = Self(
+        Self::FOUNDATION.0
+            | Self::IN_CYCLE.0
+            | Self::BRIDGE.0
+            | Self::CROSS_DOMAIN.0
+            | Self::DANGLING_SOURCE.0,
+    );
+
+    /// Wraps raw bits (unknown bits are kept)
+    #[must_use]
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw bits
+    #[must_use]
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns true if every flag in `other` is set
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns true if no flags are set
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Sets or clears the flags in `other`
+    pub const fn set(&mut self, other: Self, value: bool) {
+        if value {
+            self.0 |= other.0;
+        } else {
+            self.0 &= !other.0;
+        }
+    }
+
+    /// Replaces every non-structural bit with the one in `bits`
+    ///
+    /// Structural bits are owned by the build step and survive explicit
+    /// overrides.
+    #[must_use]
+    pub const fn with_overrides(self, bits: u32) -> Self {
+        Self((self.0 & Self::STRUCTURAL.0) | (bits & !Self::STRUCTURAL.0))
+    }
+}
+
+impl BitOr for RenderFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for RenderFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for RenderFlags {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+/// GPU records carrying a render flags field
+pub trait Flagged: Pod {
+    /// Returns the raw flags field
+    fn flags_mut(&mut self) -> &mut u32;
+}
+
+impl Flagged for GpuNode {
+    fn flags_mut(&mut self) -> &mut u32 {
+        &mut self.flags
+    }
+}
+
+impl Flagged for GpuEdge {
+    fn flags_mut(&mut self) -> &mut u32 {
+        &mut self.flags
+    }
+}
+
+/// Computes structural flags for every node, in node buffer order
+#[must_use]
+pub fn structural_node_flags(graph: &GraphStore) -> Vec<RenderFlags> {
+    let mut flags = vec![RenderFlags::FOUNDATION; graph.nodes.len()];
+
+    for edge in &graph.edges {
+        match (
+            graph.id_to_idx.get(&edge.from),
+            graph.id_to_idx.get(&edge.to),
+        ) {
+            (Some(_), Some(&to)) if EPISTEMIC_RELATIONS.contains(&edge.relation.as_str()) => {
+                flags[to].set(RenderFlags::FOUNDATION, false);
+            }
+            (Some(&from), None) => flags[from] |= RenderFlags::DANGLING_SOURCE,
+            _ => {}
+        }
+    }
+
+    let cycles = cycle_components(graph);
+    for (idx, flag) in flags.iter_mut().enumerate() {
+        if cycles[idx].is_some() {
+            *flag |= RenderFlags::IN_CYCLE;
+        }
+    }
+
+    flags
+}
+
+/// Computes structural flags for every edge record, in edge buffer order
+///
+/// Edges with missing endpoints have no record and are skipped, as in
+/// `buffers::build_gpu_edges`.
+#[must_use]
+pub fn structural_edge_flags(graph: &GraphStore) -> Vec<RenderFlags> {
+    let cycles = cycle_components(graph);
+
+    graph
+        .edges
+        .iter()
+        .filter_map(|edge| {
+            let from = *graph.id_to_idx.get(&edge.from)?;
+            let to = *graph.id_to_idx.get(&edge.to)?;
+
+            let mut flags = RenderFlags::NONE;
+            flags.set(RenderFlags::BRIDGE, edge.domain.starts_with("bridge:"));
+            flags.set(
+                RenderFlags::CROSS_DOMAIN,
+                graph.nodes[from].domain != graph.nodes[to].domain,
+            );
+            flags.set(
+                RenderFlags::IN_CYCLE,
+                cycles[from].is_some() && cycles[from] == cycles[to],
+            );
+            Some(flags)
+        })
+        .collect()
+}
+
+/// Moves `flag` from the records in `applied` to those in `members`
+///
+/// `applied` holds the records the flag was last applied to and is replaced
+/// by `members`. Only records that joined or left the set are visited, and
+/// only those whose bytes change are marked dirty.
+#[allow(clippy::implicit_hasher)]
+pub fn apply_flag<T: Flagged>(
+    buffer: &mut TrackedBuffer<T>,
+    flag: RenderFlags,
+    applied: &mut HashSet<usize>,
+    members: HashSet<usize>,
+) {
+    for (&index, value) in applied
+        .difference(&members)
+        .map(|index| (index, false))
+        .chain(members.difference(applied).map(|index| (index, true)))
+    {
+        buffer.update(index, |record| {
+            let mut flags = RenderFlags::from_bits(*record.flags_mut());
+            flags.set(flag, value);
+            *record.flags_mut() = flags.bits();
+        });
+    }
+    *applied = members;
+}
+
+/// Returns the non-trivial strongly connected component of each node
+///
+/// `None` for nodes not on any directed cycle. Self-loops count as cycles.
+/// Iterative Tarjan over successor lists built from `graph.edges`, so the
+/// result does not depend on `build_adjacency` having been called.
+fn cycle_components(graph: &GraphStore) -> Vec<Option<usize>> {
+    let node_count = graph.nodes.len();
+    let mut successors = vec![Vec::new(); node_count];
+    for edge in &graph.edges {
+        if let (Some(&from), Some(&to)) = (
+            graph.id_to_idx.get(&edge.from),
+            graph.id_to_idx.get(&edge.to),
+        ) {
+            successors[from].push(to);
+        }
+    }
+
+    let mut index = vec![usize::MAX; node_count];
+    let mut lowlink = vec![0; node_count];
+    let mut on_stack = vec![false; node_count];
+    let mut stack = Vec::new();
+    let mut component = vec![None; node_count];
+    let mut next_index = 0;
+    let mut next_component = 0;
+
+    for root in 0..node_count {
+        if index[root] != usize::MAX {
+            continue;
+        }
+
+        // (node, next neighbour position)
+        let mut work = vec![(root, 0)];
+        index[root] = next_index;
+        lowlink[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some(&mut (node, ref mut position)) = work.last_mut() {
+            if let Some(&neighbor) = successors[node].get(*position) {
+                *position += 1;
+                if index[neighbor] == usize::MAX {
+                    index[neighbor] = next_index;
+                    lowlink[neighbor] = next_index;
+                    next_index += 1;
+                    stack.push(neighbor);
+                    on_stack[neighbor] = true;
+                    work.push((neighbor, 0));
+                } else if on_stack[neighbor] {
+                    lowlink[node] = lowlink[node].min(index[neighbor]);
+                }
+                continue;
+            }
+
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                lowlink[parent] = lowlink[parent].min(lowlink[node]);
+            }
+
+            if lowlink[node] == index[node] {
+                let mut members = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    members.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+
+                let self_loop = successors[node].contains(&node);
+                if members.len() > 1 || self_loop {
+                    for member in members {
+                        component[member] = Some(next_component);
+                    }
+                    next_component += 1;
+                }
+            }
+        }
+    }
+
+    component
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Edge, Node};
+    use bytemuck::Zeroable;
+
+    fn create_test_node(id: &str, domain: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            r#type: "proposition".to_string(),
+            domain: domain.to_string(),
+            title: format!("Node {id}"),
+            content: None,
+            formal: None,
+            tags: vec![],
+            metadata: serde_json::Value::Null,
+            sources: vec![],
+            created: None,
+            updated: None,
+            status: None,
+        }
+    }
+
+    fn create_test_edge(from: &str, to: &str, domain: &str) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            relation: "supports".to_string(),
+            domain: domain.to_string(),
+            weight: Some(0.9),
+            metadata: None,
+        }
+    }
+
+    /// a → b → c → b (cycle b/c), a → d across domains, d → missing
+    fn build_graph() -> GraphStore {
+        let mut store = GraphStore::new();
+        store.add_node(create_test_node("a", "philosophy"));
+        store.add_node(create_test_node("b", "philosophy"));
+        store.add_node(create_test_node("c", "philosophy"));
+        store.add_node(create_test_node("d", "mathematics"));
+        store.add_edge(create_test_edge("a", "b", "philosophy"));
+        store.add_edge(create_test_edge("b", "c", "philosophy"));
+        store.add_edge(create_test_edge("c", "b", "philosophy"));
+        store.add_edge(create_test_edge("d", "missing", "mathematics"));
+        store.add_edge(create_test_edge("a", "d", "bridge:phil→math"));
+        store.build_adjacency();
+        store
+    }
+
+    #[test]
+    fn test_flag_operations() {
+        let mut flags = RenderFlags::SELECTED | RenderFlags::HOVERED;
+        assert!(flags.contains(RenderFlags::SELECTED));
+        assert!(!flags.contains(RenderFlags::SELECTED | RenderFlags::FOUNDATION));
+
+        flags.set(RenderFlags::SELECTED, false);
+        assert_eq!(flags, RenderFlags::HOVERED);
+        assert_eq!(flags.bits(), 2);
+        assert!((flags & RenderFlags::STRUCTURAL).is_empty());
+    }
+
+    #[test]
+    fn test_structural_node_flags() {
+        let flags = structural_node_flags(&build_graph());
+
+        assert_eq!(flags[0], RenderFlags::FOUNDATION);
+        assert_eq!(flags[1], RenderFlags::IN_CYCLE);
+        assert_eq!(flags[2], RenderFlags::IN_CYCLE);
+        assert_eq!(flags[3], RenderFlags::DANGLING_SOURCE);
+    }
+
+    #[test]
+    fn test_structural_edge_flags() {
+        let flags = structural_edge_flags(&build_graph());
+
+        // Dangling edge has no record
+        assert_eq!(flags.len(), 4);
+        assert_eq!(flags[0], RenderFlags::NONE);
+        assert_eq!(flags[1], RenderFlags::IN_CYCLE);
+        assert_eq!(flags[2], RenderFlags::IN_CYCLE);
+        assert_eq!(flags[3], RenderFlags::BRIDGE | RenderFlags::CROSS_DOMAIN);
+    }
+
+    #[test]
+    fn test_cycles_found_without_adjacency() {
+        let mut store = GraphStore::new();
+        store.add_node(create_test_node("a", "philosophy"));
+        store.add_node(create_test_node("b", "philosophy"));
+        store.add_edge(create_test_edge("a", "b", "philosophy"));
+        store.add_edge(create_test_edge("b", "a", "philosophy"));
+
+        assert!(structural_node_flags(&store)[0].contains(RenderFlags::IN_CYCLE));
+        assert!(structural_edge_flags(&store)[1].contains(RenderFlags::IN_CYCLE));
+    }
+
+    #[test]
+    fn test_self_loop_is_cycle() {
+        let mut store = GraphStore::new();
+        store.add_node(create_test_node("a", "philosophy"));
+        store.add_edge(create_test_edge("a", "a", "philosophy"));
+        store.build_adjacency();
+
+        assert!(structural_node_flags(&store)[0].contains(RenderFlags::IN_CYCLE));
+    }
+
+    #[test]
+    fn test_apply_flag_moves_and_preserves_other_bits() {
+        let mut nodes = vec![GpuNode::zeroed(); 3];
+        nodes[0].flags = (RenderFlags::SELECTED | RenderFlags::FOUNDATION).bits();
+        let mut buffer = TrackedBuffer::new(nodes);
+
+        let mut applied = HashSet::from([0]);
+        apply_flag(
+            &mut buffer,
+            RenderFlags::SELECTED,
+            &mut applied,
+            HashSet::from([2]),
+        );
+
+        let records = buffer.records();
+        assert_eq!(records[0].flags, RenderFlags::FOUNDATION.bits());
+        assert_eq!(records[1].flags, 0);
+        assert_eq!(records[2].flags, RenderFlags::SELECTED.bits());
+        assert_eq!(applied, HashSet::from([2]));
+
+        // Records 0 and 2 changed; record 1 did not
+        let patches = buffer.take_patches();
+        assert_eq!(patches.len(), 2);
+    }
+
+    #[test]
+    fn test_overrides_keep_structural_flags() {
+        let built = RenderFlags::FOUNDATION | RenderFlags::SELECTED;
+        let flags = built.with_overrides(RenderFlags::HOVERED.bits());
+
+        assert_eq!(flags, RenderFlags::FOUNDATION | RenderFlags::HOVERED);
+    }
+}
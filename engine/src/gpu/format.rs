@@ -0,0 +1,357 @@
+//! Versioned, self-describing GPU buffer format
+//!
+//! Every buffer handed to the renderer starts with a small header so that a
+//! consumer built against a different `GpuNode`/`GpuEdge` layout fails loudly
+//! instead of rendering garbage:
+//!
+//! ```text
+//! offset  size  field
+//! 0       4     magic ("TMNB" for nodes, "TMEB" for edges)
+//! 4       4     format version (u32, little-endian)
+//! 8       4     stride in bytes of one record (u32)
+//! 12      4     record count (u32)
+//! 16      4     field count N (u32)
+//! 20      4*N   byte offset of each field within a record (u32)
+//! 20+4*N  ...   records, tightly packed
+//! ```
+//!
+//! Field order in the offset table matches [`BufferLayout::fields`], which can
+//! also be exported as JSON for the web side.
+
+use super::{GpuEdge, GpuNode};
+use bytemuck::Pod;
+use serde::Serialize;
+use std::mem::{offset_of, size_of};
+
+/// Current buffer format version
+///
+/// Bump whenever `GpuNode` or `GpuEdge` change layout.
+/// Version 1 was the original 48-byte `GpuNode`; version 2 is the 64-byte one.
+pub const BUFFER_FORMAT_VERSION: u32 = 2;
+
+/// Magic bytes identifying a node buffer
+pub const NODE_BUFFER_MAGIC: [u8; 4] = *b"TMNB";
+
+/// Magic bytes identifying an edge buffer
+pub const EDGE_BUFFER_MAGIC: [u8; 4] = *b"TMEB";
+
+/// Size of the fixed part of the header (before the field offset table)
+const FIXED_HEADER_SIZE: usize = 20;
+
+/// Scalar component type of a buffer field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScalarType {
+    /// 32-bit IEEE float
+    F32,
+    /// 32-bit unsigned integer
+    U32,
+}
+
+/// Description of one field within a GPU record
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldLayout {
+    /// Field name, matching the Rust struct field
+    pub name: &'static str,
+    /// Byte offset within a record
+    pub offset: u32,
+    /// Number of scalar components (e.g. 3 for a position)
+    pub components: u32,
+    /// Component type
+    #[serde(rename = "type")]
+    pub scalar_type: ScalarType,
+}
+
+/// Description of a whole GPU record layout
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BufferLayout {
+    /// Magic bytes as a string (`"TMNB"` or `"TMEB"`)
+    pub magic: &'static str,
+    /// Format version
+    pub version: u32,
+    /// Size in bytes of one record
+    pub stride: u32,
+    /// Fields in offset-table order
+    pub fields: Vec<FieldLayout>,
+}
+
+/// Header decoded from the front of a GPU buffer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BufferHeader {
+    /// Magic bytes
+    pub magic: [u8; 4],
+    /// Format version
+    pub version: u32,
+    /// Size in bytes of one record
+    pub stride: u32,
+    /// Number of records following the header
+    pub count: u32,
+    /// Byte offset of each field within a record
+    pub field_offsets: Vec<u32>,
+}
+
+impl BufferHeader {
+    /// Total header size in bytes, i.e. the offset of the first record
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        FIXED_HEADER_SIZE + 4 * self.field_offsets.len()
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+const fn field(
+    name: &'static str,
+    offset: usize,
+    components: u32,
+    scalar_type: ScalarType,
+) -> FieldLayout {
+    FieldLayout {
+        name,
+        offset: offset as u32,
+        components,
+        scalar_type,
+    }
+}
+
+/// Returns the layout descriptor for `GpuNode` buffers
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub fn node_layout() -> BufferLayout {
+    use ScalarType::{F32, U32};
+
+    BufferLayout {
+        magic: "TMNB",
+        version: BUFFER_FORMAT_VERSION,
+        stride: size_of::<GpuNode>() as u32,
+        fields: vec![
+            field("position", offset_of!(GpuNode, position), 3, F32),
+            field("size", offset_of!(GpuNode, size), 1, F32),
+            field("color", offset_of!(GpuNode, color), 4, F32),
+            field("domain_id", offset_of!(GpuNode, domain_id), 1, U32),
+            field("type_id", offset_of!(GpuNode, type_id), 1, U32),
+            field("flags", offset_of!(GpuNode, flags), 1, U32),
+            field("scalar", offset_of!(GpuNode, scalar), 1, F32),
+            field("tension", offset_of!(GpuNode, tension), 1, F32),
+            field("load_bearing", offset_of!(GpuNode, load_bearing), 1, F32),
+            field("status", offset_of!(GpuNode, status), 1, U32),
+        ],
+    }
+}
+
+/// Returns the layout descriptor for `GpuEdge` buffers
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub fn edge_layout() -> BufferLayout {
+    use ScalarType::{F32, U32};
+
+    BufferLayout {
+        magic: "TMEB",
+        version: BUFFER_FORMAT_VERSION,
+        stride: size_of::<GpuEdge>() as u32,
+        fields: vec![
+            field("from", offset_of!(GpuEdge, from), 1, U32),
+            field("to", offset_of!(GpuEdge, to), 1, U32),
+            field("color", offset_of!(GpuEdge, color), 4, F32),
+            field("weight", offset_of!(GpuEdge, weight), 1, F32),
+            field("relation_id", offset_of!(GpuEdge, relation_id), 1, U32),
+            field("flags", offset_of!(GpuEdge, flags), 1, U32),
+        ],
+    }
+}
+
+/// Encodes node records with a versioned header
+#[must_use]
+pub fn encode_node_buffer(nodes: &[GpuNode]) -> Vec<u8> {
+    encode_buffer(NODE_BUFFER_MAGIC, &node_layout(), nodes)
+}
+
+/// Encodes edge records with a versioned header
+#[must_use]
+pub fn encode_edge_buffer(edges: &[GpuEdge]) -> Vec<u8> {
+    encode_buffer(EDGE_BUFFER_MAGIC, &edge_layout(), edges)
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn encode_buffer<T: Pod>(magic: [u8; 4], layout: &BufferLayout, records: &[T]) -> Vec<u8> {
+    let data: &[u8] = bytemuck::cast_slice(records);
+    let mut buffer = Vec::with_capacity(FIXED_HEADER_SIZE + 4 * layout.fields.len() + data.len());
+
+    buffer.extend_from_slice(&magic);
+    buffer.extend_from_slice(&layout.version.to_le_bytes());
+    buffer.extend_from_slice(&layout.stride.to_le_bytes());
+    buffer.extend_from_slice(&(records.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(&(layout.fields.len() as u32).to_le_bytes());
+    for field in &layout.fields {
+        buffer.extend_from_slice(&field.offset.to_le_bytes());
+    }
+    buffer.extend_from_slice(data);
+
+    buffer
+}
+
+/// Decodes and validates the header of a GPU buffer
+///
+/// # Arguments
+///
+/// * `bytes` - Buffer produced by [`encode_node_buffer`] or [`encode_edge_buffer`]
+/// * `expected` - Layout the caller was built against
+///
+/// # Errors
+///
+/// Returns error if the buffer is truncated, the magic does not match, or the
+/// version, stride or field offsets differ from `expected`
+pub fn decode_header(bytes: &[u8], expected: &BufferLayout) -> Result<BufferHeader, String> {
+    let read_u32 = |at: usize| -> Result<u32, String> {
+        bytes
+            .get(at..at + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or_else(|| format!("Truncated GPU buffer header at byte {at}"))
+    };
+
+    let magic: [u8; 4] = bytes
+        .get(0..4)
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| "Truncated GPU buffer header at byte 0".to_string())?;
+    if magic != expected.magic.as_bytes() {
+        return Err(format!(
+            "GPU buffer magic mismatch: expected {}, got {}",
+            expected.magic,
+            String::from_utf8_lossy(&magic)
+        ));
+    }
+
+    let version = read_u32(4)?;
+    if version != expected.version {
+        return Err(format!(
+            "GPU buffer version mismatch: expected {}, got {version}",
+            expected.version
+        ));
+    }
+
+    let stride = read_u32(8)?;
+    if stride != expected.stride {
+        return Err(format!(
+            "GPU buffer stride mismatch: expected {}, got {stride}",
+            expected.stride
+        ));
+    }
+
+    let count = read_u32(12)?;
+    let field_count = read_u32(16)? as usize;
+    let field_offsets = (0..field_count)
+        .map(|i| read_u32(FIXED_HEADER_SIZE + 4 * i))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let expected_offsets: Vec<u32> = expected.fields.iter().map(|f| f.offset).collect();
+    if field_offsets != expected_offsets {
+        return Err(format!(
+            "GPU buffer field offsets mismatch: expected {expected_offsets:?}, got {field_offsets:?}"
+        ));
+    }
+
+    let header = BufferHeader {
+        magic,
+        version,
+        stride,
+        count,
+        field_offsets,
+    };
+
+    // `count` is untrusted: compare in u64 so a corrupt value cannot wrap
+    // on 32-bit targets
+    let data_len = bytes.len() - header.size().min(bytes.len());
+    if u64::from(stride).checked_mul(u64::from(count)) != Some(data_len as u64) {
+        return Err(format!(
+            "GPU buffer length mismatch: header declares {count} records of {stride} bytes, found {data_len} bytes"
+        ));
+    }
+
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::Zeroable;
+
+    #[test]
+    fn test_node_layout_matches_struct() {
+        let layout = node_layout();
+
+        assert_eq!(layout.stride as usize, size_of::<GpuNode>());
+        assert_eq!(layout.fields[0].name, "position");
+        assert_eq!(layout.fields[0].offset, 0);
+
+        let color = layout.fields.iter().find(|f| f.name == "color").unwrap();
+        assert_eq!(color.offset, 16);
+        assert_eq!(color.components, 4);
+    }
+
+    #[test]
+    fn test_edge_layout_matches_struct() {
+        let layout = edge_layout();
+
+        assert_eq!(layout.stride as usize, size_of::<GpuEdge>());
+        let weight = layout.fields.iter().find(|f| f.name == "weight").unwrap();
+        assert_eq!(weight.offset as usize, offset_of!(GpuEdge, weight));
+    }
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let nodes = vec![GpuNode::zeroed(); 3];
+        let buffer = encode_node_buffer(&nodes);
+
+        let header = decode_header(&buffer, &node_layout()).unwrap();
+
+        assert_eq!(header.magic, NODE_BUFFER_MAGIC);
+        assert_eq!(header.version, BUFFER_FORMAT_VERSION);
+        assert_eq!(header.count, 3);
+        assert_eq!(buffer.len(), header.size() + 3 * size_of::<GpuNode>());
+    }
+
+    #[test]
+    fn test_decode_rejects_wrong_magic() {
+        let buffer = encode_edge_buffer(&[GpuEdge::zeroed()]);
+
+        let result = decode_header(&buffer, &node_layout());
+
+        assert!(result.unwrap_err().contains("magic"));
+    }
+
+    #[test]
+    fn test_decode_rejects_stale_version() {
+        let mut buffer = encode_node_buffer(&[GpuNode::zeroed()]);
+        buffer[4..8].copy_from_slice(&1_u32.to_le_bytes());
+
+        let result = decode_header(&buffer, &node_layout());
+
+        assert!(result.unwrap_err().contains("version"));
+    }
+
+    #[test]
+    fn test_decode_rejects_truncated_buffer() {
+        let buffer = encode_node_buffer(&[GpuNode::zeroed(); 2]);
+
+        assert!(decode_header(&buffer[..10], &node_layout()).is_err());
+        assert!(decode_header(&buffer[..buffer.len() - 1], &node_layout()).is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_corrupt_count() {
+        let mut buffer = encode_node_buffer(&[GpuNode::zeroed()]);
+        buffer[12..16].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        let result = decode_header(&buffer, &node_layout());
+
+        assert!(result.unwrap_err().contains("length mismatch"));
+    }
+
+    #[test]
+    fn test_layout_serializes_to_json() {
+        let json = serde_json::to_value(node_layout()).unwrap();
+
+        assert_eq!(json["magic"], "TMNB");
+        assert_eq!(json["stride"], 64);
+        assert_eq!(json["fields"][0]["type"], "f32");
+    }
+}
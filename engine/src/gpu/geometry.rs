@@ -0,0 +1,588 @@
+//! Edge geometry generation
+//!
+//! `GpuEdge` only stores endpoint indices, which renders every edge as a
+//! straight line. This module turns edges into polylines:
+//!
+//! - Parallel edges between the same pair of nodes (e.g. `supports` and
+//!   `attacks` between A and B) are drawn as quadratic Bézier curves fanned
+//!   out on either side of the straight line.
+//! - Optionally, edges can be bundled with force-directed edge bundling
+//!   (Holten & van Wijk, 2009) so that dense cross-domain bridges read as a
+//!   few thick strands instead of a hairball.
+//!
+//! The result is a flat vertex buffer plus per-edge offsets: vertices of edge
+//! record `j` are `vertices[offsets[j]..offsets[j + 1]]`.
+
+use super::picking::edge_record_indices;
+use crate::graph::GraphStore;
+use bytemuck::{Pod, Zeroable};
+use std::collections::HashMap;
+
+/// A vertex of an edge polyline
+///
+/// **Size:** 24 bytes
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct GpuEdgeVertex {
+    /// 3D position [x, y, z]
+    pub position: [f32; 3],
+    /// Parameter along the edge, 0 at the source and 1 at the target
+    pub t: f32,
+    /// Index of the edge record in the edge GPU buffer
+    pub edge_index: u32,
+    /// Padding for alignment
+    pub padding: u32,
+}
+
+/// Which edges take part in bundling
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BundleScope {
+    /// Only cross-domain bridge edges (domain starting with `bridge:`)
+    Bridges,
+    /// Every edge
+    All,
+}
+
+/// Force-directed edge bundling parameters
+#[derive(Debug, Clone, Copy)]
+pub struct BundlingConfig {
+    /// Which edges are bundled
+    pub scope: BundleScope,
+    /// Spring constant pulling subdivision points towards their neighbours
+    pub stiffness: f32,
+    /// Minimum compatibility (0-1) for two edges to attract each other
+    pub compatibility_threshold: f32,
+    /// Number of refinement cycles (subdivisions double each cycle)
+    pub cycles: u32,
+    /// Iterations in the first cycle (reduced each cycle)
+    pub iterations: u32,
+    /// Initial step size (halved each cycle)
+    pub step_size: f32,
+}
+
+impl Default for BundlingConfig {
+    fn default() -> Self {
+        Self {
+            scope: BundleScope::Bridges,
+            stiffness: 0.1,
+            compatibility_threshold: 0.6,
+            cycles: 4,
+            iterations: 30,
+            step_size: 0.04,
+        }
+    }
+}
+
+/// Edge geometry parameters
+#[derive(Debug, Clone, Copy)]
+pub struct EdgeGeometryConfig {
+    /// Number of line segments per curved edge (straight edges use 1)
+    pub segments: u32,
+    /// Lateral offset between parallel edges, as a fraction of edge length
+    pub curvature: f32,
+    /// Optional force-directed bundling
+    pub bundling: Option<BundlingConfig>,
+}
+
+impl Default for EdgeGeometryConfig {
+    fn default() -> Self {
+        Self {
+            segments: 8,
+            curvature: 0.15,
+            bundling: None,
+        }
+    }
+}
+
+/// Generated edge polylines
+#[derive(Debug, Clone, Default)]
+pub struct EdgeGeometry {
+    /// All polyline vertices, edge by edge
+    pub vertices: Vec<GpuEdgeVertex>,
+    /// Start of each edge's vertices; has one extra trailing entry
+    pub offsets: Vec<u32>,
+}
+
+impl EdgeGeometry {
+    /// Returns the vertices of one edge record
+    #[must_use]
+    pub fn edge_vertices(&self, edge_index: usize) -> &[GpuEdgeVertex] {
+        match (
+            self.offsets.get(edge_index),
+            self.offsets.get(edge_index + 1),
+        ) {
+            (Some(&start), Some(&end)) => &self.vertices[start as usize..end as usize],
+            _ => &[],
+        }
+    }
+}
+
+/// Generates polyline geometry for every rendered edge
+///
+/// # Arguments
+///
+/// * `graph` - Graph store
+/// * `layout` - 3D positions for each node
+/// * `config` - Geometry parameters
+///
+/// # Returns
+///
+/// Vertex buffer and per-edge offsets, in edge GPU buffer order
+#[must_use]
+#[allow(
+    clippy::implicit_hasher,
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss
+)]
+pub fn generate_edge_geometry(
+    graph: &GraphStore,
+    layout: &HashMap<String, [f32; 3]>,
+    config: &EdgeGeometryConfig,
+) -> EdgeGeometry {
+    let records = edge_record_indices(graph);
+    let position = |id: &str| layout.get(id).copied().unwrap_or([0.0; 3]);
+
+    // Group parallel edges by unordered endpoint pair
+    let mut groups: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (record, &edge_idx) in records.iter().enumerate() {
+        let edge = &graph.edges[edge_idx];
+        let a = graph.id_to_idx[&edge.from];
+        let b = graph.id_to_idx[&edge.to];
+        groups.entry((a.min(b), a.max(b))).or_default().push(record);
+    }
+
+    let mut polylines: Vec<Vec<[f32; 3]>> = Vec::with_capacity(records.len());
+    for &edge_idx in &records {
+        let edge = &graph.edges[edge_idx];
+        polylines.push(vec![position(&edge.from), position(&edge.to)]);
+    }
+
+    for ((low, high), members) in &groups {
+        if members.len() < 2 {
+            continue;
+        }
+
+        // Offsets are measured in a direction fixed by the node pair, so that
+        // A→B and B→A edges fan out instead of overlapping
+        let a = position(&graph.nodes[*low].id);
+        let b = position(&graph.nodes[*high].id);
+        let normal = perpendicular(sub(b, a));
+        let length = norm(sub(b, a));
+
+        let center = (members.len() - 1) as f32 / 2.0;
+        for (slot, &record) in members.iter().enumerate() {
+            let lateral = (slot as f32 - center) * config.curvature * length;
+            if lateral.abs() < f32::EPSILON {
+                continue;
+            }
+
+            let [start, end] = [polylines[record][0], polylines[record][1]];
+            let control = add(scale(add(start, end), 0.5), scale(normal, 2.0 * lateral));
+            polylines[record] = sample_quadratic(start, control, end, config.segments.max(1));
+        }
+    }
+
+    if let Some(bundling) = &config.bundling {
+        let bundled: Vec<usize> = records
+            .iter()
+            .enumerate()
+            .filter(|(_, &edge_idx)| match bundling.scope {
+                BundleScope::All => true,
+                BundleScope::Bridges => graph.edges[edge_idx].domain.starts_with("bridge:"),
+            })
+            .map(|(record, _)| record)
+            .collect();
+
+        bundle_edges(&mut polylines, &bundled, bundling);
+    }
+
+    let mut geometry = EdgeGeometry {
+        vertices: Vec::new(),
+        offsets: Vec::with_capacity(polylines.len() + 1),
+    };
+    for (record, polyline) in polylines.iter().enumerate() {
+        geometry.offsets.push(geometry.vertices.len() as u32);
+
+        let last = (polyline.len() - 1).max(1) as f32;
+        for (i, &point) in polyline.iter().enumerate() {
+            geometry.vertices.push(GpuEdgeVertex {
+                position: point,
+                t: i as f32 / last,
+                edge_index: record as u32,
+                padding: 0,
+            });
+        }
+    }
+    geometry.offsets.push(geometry.vertices.len() as u32);
+
+    geometry
+}
+
+/// Force-directed edge bundling over the selected polylines
+///
+/// Each selected edge is re-subdivided into straight-line control points, then
+/// points are pulled towards the corresponding points of compatible edges
+/// while springs keep each edge smooth.
+#[allow(clippy::cast_precision_loss)]
+fn bundle_edges(polylines: &mut [Vec<[f32; 3]>], selected: &[usize], config: &BundlingConfig) {
+    if selected.len() < 2 {
+        return;
+    }
+
+    let endpoints: Vec<([f32; 3], [f32; 3])> = selected
+        .iter()
+        .map(|&record| {
+            let line = &polylines[record];
+            (line[0], line[line.len() - 1])
+        })
+        .collect();
+
+    // Pairwise compatibility is fixed by the original straight edges. Edges
+    // pointing in opposite directions attract their points in reverse order.
+    let mut compatible: Vec<Vec<(usize, bool)>> = vec![Vec::new(); selected.len()];
+    for i in 0..selected.len() {
+        for j in (i + 1)..selected.len() {
+            if compatibility(endpoints[i], endpoints[j]) >= config.compatibility_threshold {
+                let (pi, pj) = (endpoints[i], endpoints[j]);
+                let reversed = dot(sub(pi.1, pi.0), sub(pj.1, pj.0)) < 0.0;
+                compatible[i].push((j, reversed));
+                compatible[j].push((i, reversed));
+            }
+        }
+    }
+
+    // Interior subdivision points per edge (endpoints stay fixed)
+    let mut points: Vec<Vec<[f32; 3]>> = endpoints
+        .iter()
+        .map(|&(start, end)| vec![lerp(start, end, 0.5)])
+        .collect();
+
+    let mut step = config.step_size;
+    let mut iterations = config.iterations;
+    for cycle in 0..config.cycles {
+        if cycle > 0 {
+            for (line, &(start, end)) in points.iter_mut().zip(&endpoints) {
+                *line = subdivide(start, line, end);
+            }
+            step /= 2.0;
+            iterations = (iterations * 2 / 3).max(1);
+        }
+
+        for _ in 0..iterations {
+            let snapshot = points.clone();
+            for (i, line) in points.iter_mut().enumerate() {
+                let (start, end) = endpoints[i];
+                let spring = config.stiffness
+                    / (norm(sub(end, start)).max(f32::EPSILON) * (line.len() + 1) as f32);
+
+                for p in 0..line.len() {
+                    let current = snapshot[i][p];
+                    let prev = if p == 0 { start } else { snapshot[i][p - 1] };
+                    let next = if p + 1 == line.len() {
+                        end
+                    } else {
+                        snapshot[i][p + 1]
+                    };
+
+                    let mut force = scale(add(sub(prev, current), sub(next, current)), spring);
+                    for &(j, reversed) in &compatible[i] {
+                        let q = if reversed { line.len() - 1 - p } else { p };
+                        let toward = sub(snapshot[j][q], current);
+                        let distance = norm(toward);
+                        if distance > f32::EPSILON {
+                            force = add(force, scale(toward, 1.0 / distance));
+                        }
+                    }
+
+                    line[p] = add(current, scale(force, step));
+                }
+            }
+        }
+    }
+
+    for (i, &record) in selected.iter().enumerate() {
+        let (start, end) = endpoints[i];
+        let mut line = Vec::with_capacity(points[i].len() + 2);
+        line.push(start);
+        line.extend_from_slice(&points[i]);
+        line.push(end);
+        polylines[record] = line;
+    }
+}
+
+/// Edge compatibility (angle × scale × position), in [0, 1]
+fn compatibility(p: ([f32; 3], [f32; 3]), q: ([f32; 3], [f32; 3])) -> f32 {
+    let dp = sub(p.1, p.0);
+    let dq = sub(q.1, q.0);
+    let len_p = norm(dp);
+    let len_q = norm(dq);
+    if len_p < f32::EPSILON || len_q < f32::EPSILON {
+        return 0.0;
+    }
+
+    let angle = (dot(dp, dq) / (len_p * len_q)).abs();
+    let avg = f32::midpoint(len_p, len_q);
+    let scale_c = 2.0 / (avg / len_p.min(len_q) + len_p.max(len_q) / avg);
+    let mid_p = lerp(p.0, p.1, 0.5);
+    let mid_q = lerp(q.0, q.1, 0.5);
+    let position = avg / (avg + norm(sub(mid_p, mid_q)));
+
+    angle * scale_c * position
+}
+
+/// Doubles the number of interior points by inserting midpoints
+fn subdivide(start: [f32; 3], interior: &[[f32; 3]], end: [f32; 3]) -> Vec<[f32; 3]> {
+    let mut full = Vec::with_capacity(interior.len() + 2);
+    full.push(start);
+    full.extend_from_slice(interior);
+    full.push(end);
+
+    let mut result = Vec::with_capacity(interior.len() * 2 + 1);
+    for (k, pair) in full.windows(2).enumerate() {
+        result.push(lerp(pair[0], pair[1], 0.5));
+        if k + 2 < full.len() {
+            result.push(pair[1]);
+        }
+    }
+    result
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn sample_quadratic(a: [f32; 3], control: [f32; 3], b: [f32; 3], segments: u32) -> Vec<[f32; 3]> {
+    (0..=segments)
+        .map(|i| {
+            let t = i as f32 / segments as f32;
+            let u = 1.0 - t;
+            add(
+                add(scale(a, u * u), scale(control, 2.0 * u * t)),
+                scale(b, t * t),
+            )
+        })
+        .collect()
+}
+
+/// Unit vector perpendicular to `d`, preferring the horizontal plane
+fn perpendicular(d: [f32; 3]) -> [f32; 3] {
+    let candidate = cross(d, [0.0, 1.0, 0.0]);
+    let candidate = if norm(candidate) < 1e-6 {
+        cross(d, [1.0, 0.0, 0.0])
+    } else {
+        candidate
+    };
+
+    let length = norm(candidate);
+    if length < 1e-6 {
+        [1.0, 0.0, 0.0]
+    } else {
+        scale(candidate, 1.0 / length)
+    }
+}
+
+fn add(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: [f32; 3], s: f32) -> [f32; 3] {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0].mul_add(b[0], a[1].mul_add(b[1], a[2] * b[2]))
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1].mul_add(b[2], -(a[2] * b[1])),
+        a[2].mul_add(b[0], -(a[0] * b[2])),
+        a[0].mul_add(b[1], -(a[1] * b[0])),
+    ]
+}
+
+fn norm(a: [f32; 3]) -> f32 {
+    dot(a, a).sqrt()
+}
+
+fn lerp(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    add(a, scale(sub(b, a), t))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Edge, Node};
+
+    fn create_test_node(id: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            r#type: "proposition".to_string(),
+            domain: "philosophy".to_string(),
+            title: format!("Node {id}"),
+            content: None,
+            formal: None,
+            tags: vec![],
+            metadata: serde_json::Value::Null,
+            sources: vec![],
+            created: None,
+            updated: None,
+            status: None,
+        }
+    }
+
+    fn create_test_edge(from: &str, to: &str, relation: &str, domain: &str) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            relation: relation.to_string(),
+            domain: domain.to_string(),
+            weight: Some(0.9),
+            metadata: None,
+        }
+    }
+
+    fn distance_to_segment_midpoint(vertices: &[GpuEdgeVertex], a: [f32; 3], b: [f32; 3]) -> f32 {
+        let mid = vertices[vertices.len() / 2].position;
+        norm(sub(mid, lerp(a, b, 0.5)))
+    }
+
+    #[test]
+    fn test_gpu_edge_vertex_size() {
+        assert_eq!(std::mem::size_of::<GpuEdgeVertex>(), 24);
+    }
+
+    #[test]
+    fn test_single_edge_is_straight() {
+        let mut store = GraphStore::new();
+        store.add_node(create_test_node("a"));
+        store.add_node(create_test_node("b"));
+        store.add_edge(create_test_edge("a", "b", "supports", "philosophy"));
+
+        let layout = HashMap::from([
+            ("a".to_string(), [0.0, 0.0, 0.0]),
+            ("b".to_string(), [10.0, 0.0, 0.0]),
+        ]);
+        let geometry = generate_edge_geometry(&store, &layout, &EdgeGeometryConfig::default());
+
+        assert_eq!(geometry.offsets, vec![0, 2]);
+        let vertices = geometry.edge_vertices(0);
+        assert_eq!(vertices[0].position, [0.0, 0.0, 0.0]);
+        assert_eq!(vertices[1].position, [10.0, 0.0, 0.0]);
+        assert_eq!(vertices[1].t, 1.0);
+    }
+
+    #[test]
+    fn test_parallel_edges_curve_apart() {
+        let mut store = GraphStore::new();
+        store.add_node(create_test_node("a"));
+        store.add_node(create_test_node("b"));
+        store.add_edge(create_test_edge("a", "b", "supports", "philosophy"));
+        store.add_edge(create_test_edge("b", "a", "attacks", "philosophy"));
+
+        let a = [0.0, 0.0, 0.0];
+        let b = [10.0, 0.0, 0.0];
+        let layout = HashMap::from([("a".to_string(), a), ("b".to_string(), b)]);
+        let config = EdgeGeometryConfig::default();
+        let geometry = generate_edge_geometry(&store, &layout, &config);
+
+        let first = geometry.edge_vertices(0);
+        let second = geometry.edge_vertices(1);
+        assert_eq!(first.len(), config.segments as usize + 1);
+        assert_eq!(second.len(), config.segments as usize + 1);
+
+        // Endpoints are preserved, in each edge's own direction
+        assert_eq!(first[0].position, a);
+        assert_eq!(second[0].position, b);
+        assert!(second.iter().all(|v| v.edge_index == 1));
+
+        // Both bow away from the straight line, on opposite sides
+        assert!(distance_to_segment_midpoint(first, a, b) > 0.5);
+        assert!(distance_to_segment_midpoint(second, a, b) > 0.5);
+        let mid_first = first[first.len() / 2].position;
+        let mid_second = second[second.len() / 2].position;
+        assert!(norm(sub(mid_first, mid_second)) > 1.0);
+    }
+
+    #[test]
+    fn test_bundling_pulls_bridges_together() {
+        let mut store = GraphStore::new();
+        for id in ["p1", "p2", "m1", "m2"] {
+            store.add_node(create_test_node(id));
+        }
+        store.add_edge(create_test_edge(
+            "p1",
+            "m1",
+            "formalizes",
+            "bridge:phil→math",
+        ));
+        store.add_edge(create_test_edge(
+            "p2",
+            "m2",
+            "formalizes",
+            "bridge:phil→math",
+        ));
+
+        let layout = HashMap::from([
+            ("p1".to_string(), [0.0, 0.0, 0.0]),
+            ("m1".to_string(), [10.0, 0.0, 0.0]),
+            ("p2".to_string(), [0.0, 0.0, 2.0]),
+            ("m2".to_string(), [10.0, 0.0, 2.0]),
+        ]);
+        let config = EdgeGeometryConfig {
+            bundling: Some(BundlingConfig::default()),
+            ..EdgeGeometryConfig::default()
+        };
+        let geometry = generate_edge_geometry(&store, &layout, &config);
+
+        let first = geometry.edge_vertices(0);
+        let second = geometry.edge_vertices(1);
+        assert!(first.len() > 2);
+        assert_eq!(first[0].position, [0.0, 0.0, 0.0]);
+        assert_eq!(first[first.len() - 1].position, [10.0, 0.0, 0.0]);
+
+        let gap = norm(sub(
+            first[first.len() / 2].position,
+            second[second.len() / 2].position,
+        ));
+        assert!(
+            gap < 2.0,
+            "bundled midpoints should be closer than the original 2.0, got {gap}"
+        );
+    }
+
+    #[test]
+    fn test_bundling_scope_excludes_non_bridges() {
+        let mut store = GraphStore::new();
+        for id in ["a", "b", "c", "d"] {
+            store.add_node(create_test_node(id));
+        }
+        store.add_edge(create_test_edge("a", "b", "supports", "philosophy"));
+        store.add_edge(create_test_edge("c", "d", "supports", "philosophy"));
+
+        let layout = HashMap::from([
+            ("a".to_string(), [0.0, 0.0, 0.0]),
+            ("b".to_string(), [10.0, 0.0, 0.0]),
+            ("c".to_string(), [0.0, 0.0, 2.0]),
+            ("d".to_string(), [10.0, 0.0, 2.0]),
+        ]);
+        let config = EdgeGeometryConfig {
+            bundling: Some(BundlingConfig::default()),
+            ..EdgeGeometryConfig::default()
+        };
+        let geometry = generate_edge_geometry(&store, &layout, &config);
+
+        assert_eq!(geometry.edge_vertices(0).len(), 2);
+        assert_eq!(geometry.edge_vertices(1).len(), 2);
+    }
+
+    #[test]
+    fn test_subdivide_doubles_interior_points() {
+        let interior = subdivide([0.0; 3], &[[2.0, 0.0, 0.0]], [4.0, 0.0, 0.0]);
+        assert_eq!(
+            interior,
+            vec![[1.0, 0.0, 0.0], [2.0, 0.0, 0.0], [3.0, 0.0, 0.0]]
+        );
+    }
+}
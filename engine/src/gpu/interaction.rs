@@ -0,0 +1,324 @@
+//! Viewer interaction state that outlives rebuilt GPU buffers
+//!
+//! Selection, hover, focus path, search highlight and per-node overrides are
+//! kept by node ID rather than only in the GPU records, so they can be
+//! re-applied when a [`GpuCache`] is rebuilt after a reload, relayout or
+//! theme change. Between rebuilds, each flag is only moved between the
+//! records that joined or left it, so per-frame changes do not scan the
+//! whole buffer.
+
+use super::cache::GpuCache;
+use super::flags::apply_flag;
+use super::RenderFlags;
+use crate::graph::GraphStore;
+use std::collections::{HashMap, HashSet};
+
+/// Interaction state of a viewer
+#[derive(Debug, Clone, Default)]
+pub struct Interaction {
+    /// Selected node
+    pub selected: Option<String>,
+    /// Node under the pointer
+    pub hovered: Option<String>,
+    /// Nodes of the focus path, in path order
+    pub focus_path: Vec<String>,
+    /// Search query (empty for no highlight)
+    pub search: String,
+    /// Raw flags set explicitly per node
+    pub node_flags: HashMap<String, u32>,
+    /// Scalar channel values set explicitly per node
+    pub node_scalars: HashMap<String, f32>,
+    /// Records each interaction flag is currently applied to
+    applied: AppliedFlags,
+}
+
+/// Record indices carrying each interaction flag
+#[derive(Debug, Clone, Default)]
+struct AppliedFlags {
+    selected: HashSet<usize>,
+    hovered: HashSet<usize>,
+    focus_nodes: HashSet<usize>,
+    focus_edges: HashSet<usize>,
+    search: HashSet<usize>,
+}
+
+impl Interaction {
+    /// Re-applies the whole state to freshly built records
+    ///
+    /// Explicit per-node flags and scalars are written first (structural
+    /// flags are kept), then the selection, hover, focus path and search
+    /// flags on top.
+    pub fn apply(&mut self, graph: &GraphStore, cache: &mut GpuCache) {
+        self.applied = AppliedFlags::default();
+        for (id, &flags) in &self.node_flags {
+            if let Some(index) = graph.index_of(id) {
+                cache.nodes.update(index, |node| {
+                    node.flags = RenderFlags::from_bits(node.flags)
+                        .with_overrides(flags)
+                        .bits();
+                });
+            }
+        }
+        for (id, &scalar) in &self.node_scalars {
+            if let Some(index) = graph.index_of(id) {
+                cache.nodes.update(index, |node| node.scalar = scalar);
+            }
+        }
+
+        self.apply_selection(graph, cache);
+        self.apply_hover(graph, cache);
+        self.apply_focus_path(graph, cache);
+        self.apply_search(graph, cache);
+    }
+
+    /// Moves the `SELECTED` flag to the selected node
+    pub fn apply_selection(&mut self, graph: &GraphStore, cache: &mut GpuCache) {
+        let members = node_indices(graph, self.selected.iter());
+        apply_flag(
+            &mut cache.nodes,
+            RenderFlags::SELECTED,
+            &mut self.applied.selected,
+            members,
+        );
+    }
+
+    /// Moves the `HOVERED` flag to the hovered node
+    pub fn apply_hover(&mut self, graph: &GraphStore, cache: &mut GpuCache) {
+        let members = node_indices(graph, self.hovered.iter());
+        apply_flag(
+            &mut cache.nodes,
+            RenderFlags::HOVERED,
+            &mut self.applied.hovered,
+            members,
+        );
+    }
+
+    /// Flags the nodes of the focus path and the edges between consecutive ones
+    ///
+    /// Path nodes that no longer exist are skipped.
+    ///
+    /// # Returns
+    ///
+    /// Number of flagged nodes
+    pub fn apply_focus_path(&mut self, graph: &GraphStore, cache: &mut GpuCache) -> usize {
+        let path: Vec<u32> = self
+            .focus_path
+            .iter()
+            .filter_map(|id| graph.index_of(id))
+            .map(to_u32)
+            .collect();
+        let steps: HashSet<(u32, u32)> = path.windows(2).map(|pair| (pair[0], pair[1])).collect();
+        let nodes: HashSet<usize> = path.iter().map(|&index| index as usize).collect();
+        let edges: HashSet<usize> = cache
+            .edges
+            .records()
+            .iter()
+            .enumerate()
+            .filter(|(_, edge)| steps.contains(&(edge.from, edge.to)))
+            .map(|(index, _)| index)
+            .collect();
+
+        let count = nodes.len();
+        apply_flag(
+            &mut cache.nodes,
+            RenderFlags::FOCUS_PATH,
+            &mut self.applied.focus_nodes,
+            nodes,
+        );
+        apply_flag(
+            &mut cache.edges,
+            RenderFlags::FOCUS_PATH,
+            &mut self.applied.focus_edges,
+            edges,
+        );
+        count
+    }
+
+    /// Flags nodes whose ID, title or tags contain the search query
+    /// (case-insensitive)
+    ///
+    /// # Returns
+    ///
+    /// Number of matching nodes
+    pub fn apply_search(&mut self, graph: &GraphStore, cache: &mut GpuCache) -> usize {
+        let query = self.search.to_lowercase();
+        let matches: HashSet<usize> = if query.is_empty() {
+            HashSet::new()
+        } else {
+            graph
+                .nodes()
+                .iter()
+                .enumerate()
+                .filter(|(_, node)| {
+                    node.id.to_lowercase().contains(&query)
+                        || node.title.to_lowercase().contains(&query)
+                        || node
+                            .tags
+                            .iter()
+                            .any(|tag| tag.to_lowercase().contains(&query))
+                })
+                .map(|(index, _)| index)
+                .collect()
+        };
+
+        let count = matches.len();
+        apply_flag(
+            &mut cache.nodes,
+            RenderFlags::SEARCH_MATCH,
+            &mut self.applied.search,
+            matches,
+        );
+        count
+    }
+}
+
+fn node_indices<'a>(graph: &GraphStore, ids: impl Iterator<Item = &'a String>) -> HashSet<usize> {
+    ids.filter_map(|id| graph.index_of(id)).collect()
+}
+
+#[allow(clippy::cast_possible_truncation)]
+const fn to_u32(index: usize) -> u32 {
+    index as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Edge, Node};
+    use crate::style::StyleConfig;
+
+    fn create_test_node(id: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            r#type: "proposition".to_string(),
+            domain: "philosophy".to_string(),
+            title: format!("Node {id}"),
+            content: None,
+            formal: None,
+            tags: vec![],
+            metadata: serde_json::Value::Null,
+            sources: vec![],
+            created: None,
+            updated: None,
+            status: None,
+        }
+    }
+
+    fn create_test_edge(from: &str, to: &str) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            relation: "supports".to_string(),
+            domain: "philosophy".to_string(),
+            weight: None,
+            metadata: None,
+        }
+    }
+
+    fn build(graph: &GraphStore, interaction: &mut Interaction) -> GpuCache {
+        let mut cache = GpuCache::build(
+            graph,
+            &HashMap::new(),
+            &HashMap::new(),
+            &StyleConfig::default(),
+        );
+        interaction.apply(graph, &mut cache);
+        cache
+    }
+
+    fn has_flag(flags: u32, flag: RenderFlags) -> bool {
+        RenderFlags::from_bits(flags).contains(flag)
+    }
+
+    #[test]
+    fn test_state_survives_rebuild() {
+        let mut graph = GraphStore::new();
+        graph.add_node(create_test_node("a"));
+        graph.add_node(create_test_node("b"));
+        graph.add_edge(create_test_edge("a", "b"));
+
+        let mut interaction = Interaction {
+            selected: Some("b".to_string()),
+            hovered: Some("a".to_string()),
+            focus_path: vec!["a".to_string(), "b".to_string()],
+            search: "NODE B".to_string(),
+            node_scalars: HashMap::from([("a".to_string(), 0.25)]),
+            ..Interaction::default()
+        };
+
+        // Reload: records are rebuilt from scratch
+        graph.add_node(create_test_node("c"));
+        let cache = build(&graph, &mut interaction);
+        let nodes = cache.nodes.records();
+
+        assert!(has_flag(nodes[0].flags, RenderFlags::HOVERED));
+        assert!(has_flag(nodes[0].flags, RenderFlags::FOCUS_PATH));
+        assert!((nodes[0].scalar - 0.25).abs() < f32::EPSILON);
+        assert!(has_flag(nodes[1].flags, RenderFlags::SELECTED));
+        assert!(has_flag(nodes[1].flags, RenderFlags::SEARCH_MATCH));
+        assert!(!has_flag(nodes[2].flags, RenderFlags::SELECTED));
+        assert!(has_flag(
+            cache.edges.records()[0].flags,
+            RenderFlags::FOCUS_PATH
+        ));
+    }
+
+    #[test]
+    fn test_missing_nodes_are_skipped() {
+        let mut graph = GraphStore::new();
+        graph.add_node(create_test_node("a"));
+
+        let mut interaction = Interaction {
+            selected: Some("gone".to_string()),
+            focus_path: vec!["gone".to_string(), "a".to_string()],
+            ..Interaction::default()
+        };
+        let mut cache = build(&graph, &mut interaction);
+
+        assert_eq!(interaction.apply_focus_path(&graph, &mut cache), 1);
+        assert!(!has_flag(
+            cache.nodes.records()[0].flags,
+            RenderFlags::SELECTED
+        ));
+    }
+    #[test]
+    fn test_node_flags_keep_structural_flags_across_rebuild() {
+        // A viewer-defined bit outside the named flags
+        const CUSTOM: u32 = 1 << 12;
+
+        let mut graph = GraphStore::new();
+        graph.add_node(create_test_node("a"));
+
+        let mut interaction = Interaction {
+            node_flags: HashMap::from([("a".to_string(), CUSTOM)]),
+            ..Interaction::default()
+        };
+        let flags = build(&graph, &mut interaction).nodes.records()[0].flags;
+
+        assert!(has_flag(flags, RenderFlags::FOUNDATION));
+        assert_eq!(flags & CUSTOM, CUSTOM);
+    }
+
+    #[test]
+    fn test_selection_change_touches_only_old_and_new_records() {
+        let mut graph = GraphStore::new();
+        for id in ["a", "b", "c"] {
+            graph.add_node(create_test_node(id));
+        }
+
+        let mut interaction = Interaction {
+            selected: Some("a".to_string()),
+            ..Interaction::default()
+        };
+        let mut cache = build(&graph, &mut interaction);
+        cache.nodes.take_patches();
+
+        interaction.selected = Some("c".to_string());
+        interaction.apply_selection(&graph, &mut cache);
+
+        let nodes = cache.nodes.records();
+        assert!(!has_flag(nodes[0].flags, RenderFlags::SELECTED));
+        assert!(has_flag(nodes[2].flags, RenderFlags::SELECTED));
+        assert_eq!(cache.nodes.take_patches().len(), 2);
+    }
+}
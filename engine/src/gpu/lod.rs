@@ -0,0 +1,259 @@
+//! Level-of-detail cluster buffers
+//!
+//! At full-mine zoom, individual nodes are both too many to draw and too small
+//! to read. Each LOD level collapses the nodes of one `Clustering` into a
+//! single aggregate `GpuNode` per cluster, and the edges between clusters
+//! into one aggregate `GpuEdge` per (source cluster, target cluster, relation).
+//! The renderer swaps levels as the camera moves.
+
+use super::{GpuEdge, GpuNode, RenderFlags};
+use crate::analysis::clustering::Clustering;
+use bytemuck::Zeroable;
+use std::collections::HashMap;
+
+/// Aggregate GPU records for one level of detail
+#[derive(Debug, Clone, Default)]
+pub struct LodLevel {
+    /// One record per cluster, indexed by cluster
+    pub nodes: Vec<GpuNode>,
+    /// Inter-cluster edges; `from`/`to` are cluster indices
+    pub edges: Vec<GpuEdge>,
+}
+
+/// Aggregates full-detail GPU records into one LOD level
+///
+/// Per cluster:
+/// - `position` and `color` are member averages
+/// - `size` is the sum of member sizes
+/// - `scalar` is the member count
+/// - `domain_id` and `type_id` are the most common among members
+/// - `tension` and `load_bearing` are member maxima
+///
+/// Edges inside a cluster are dropped; parallel inter-cluster edges of the
+/// same relation are merged with summed weights, averaged colours and the
+/// union of their structural flags. Interaction flags (selected, hovered,
+/// focus path, search match) describe single records and are not carried.
+///
+/// # Arguments
+///
+/// * `clustering` - Cluster assignment in node buffer order
+/// * `nodes` - Full-detail node records
+/// * `edges` - Full-detail edge records (indices into `nodes`)
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+pub fn aggregate_level(clustering: &Clustering, nodes: &[GpuNode], edges: &[GpuEdge]) -> LodLevel {
+    let cluster_count = clustering.cluster_count();
+    let mut aggregated = vec![GpuNode::zeroed(); cluster_count];
+    let mut counts = vec![0_u32; cluster_count];
+    let mut domain_votes: Vec<HashMap<u32, u32>> = vec![HashMap::new(); cluster_count];
+    let mut type_votes: Vec<HashMap<u32, u32>> = vec![HashMap::new(); cluster_count];
+
+    for (node, &cluster) in nodes.iter().zip(&clustering.assignment) {
+        let target = &mut aggregated[cluster];
+        for axis in 0..3 {
+            target.position[axis] += node.position[axis];
+        }
+        for channel in 0..4 {
+            target.color[channel] += node.color[channel];
+        }
+        target.size += node.size;
+        target.tension = target.tension.max(node.tension);
+        target.load_bearing = target.load_bearing.max(node.load_bearing);
+        target.status |= node.status;
+
+        counts[cluster] += 1;
+        *domain_votes[cluster].entry(node.domain_id).or_default() += 1;
+        *type_votes[cluster].entry(node.type_id).or_default() += 1;
+    }
+
+    for (cluster, node) in aggregated.iter_mut().enumerate() {
+        let count = counts[cluster].max(1) as f32;
+        for axis in 0..3 {
+            node.position[axis] /= count;
+        }
+        for channel in 0..4 {
+            node.color[channel] /= count;
+        }
+        node.scalar = counts[cluster] as f32;
+        node.domain_id = majority(&domain_votes[cluster]);
+        node.type_id = majority(&type_votes[cluster]);
+    }
+
+    // (from cluster, to cluster, relation) → (aggregate, member count)
+    let mut merged: HashMap<(u32, u32, u32), (GpuEdge, u32)> = HashMap::new();
+    let mut order = Vec::new();
+    for edge in edges {
+        let (Some(&from), Some(&to)) = (
+            clustering.assignment.get(edge.from as usize),
+            clustering.assignment.get(edge.to as usize),
+        ) else {
+            continue;
+        };
+        if from == to {
+            continue;
+        }
+
+        let key = (from as u32, to as u32, edge.relation_id);
+        let (aggregate, count) = merged.entry(key).or_insert_with(|| {
+            order.push(key);
+            (
+                GpuEdge {
+                    from: key.0,
+                    to: key.1,
+                    relation_id: key.2,
+                    ..GpuEdge::zeroed()
+                },
+                0,
+            )
+        });
+        aggregate.weight += edge.weight;
+        for channel in 0..4 {
+            aggregate.color[channel] += edge.color[channel];
+        }
+        aggregate.flags |= edge.flags & RenderFlags::STRUCTURAL.bits();
+        *count += 1;
+    }
+
+    let edges = order
+        .into_iter()
+        .map(|key| {
+            let (mut edge, count) = merged[&key];
+            for channel in 0..4 {
+                edge.color[channel] /= count as f32;
+            }
+            edge
+        })
+        .collect();
+
+    LodLevel {
+        nodes: aggregated,
+        edges,
+    }
+}
+
+/// Aggregates one LOD level per clustering, in the given order
+#[must_use]
+pub fn build_lod_levels(
+    hierarchy: &[Clustering],
+    nodes: &[GpuNode],
+    edges: &[GpuEdge],
+) -> Vec<LodLevel> {
+    hierarchy
+        .iter()
+        .map(|clustering| aggregate_level(clustering, nodes, edges))
+        .collect()
+}
+
+/// Most common key; ties go to the smallest
+fn majority(votes: &HashMap<u32, u32>) -> u32 {
+    votes
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+        .map_or(0, |(&key, _)| key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gpu_node(position: [f32; 3], domain_id: u32) -> GpuNode {
+        GpuNode {
+            position,
+            size: 1.0,
+            color: [1.0, 0.0, 0.0, 1.0],
+            domain_id,
+            ..GpuNode::zeroed()
+        }
+    }
+
+    fn gpu_edge(from: u32, to: u32, relation_id: u32, weight: f32) -> GpuEdge {
+        GpuEdge {
+            from,
+            to,
+            weight,
+            relation_id,
+            color: [0.0, 1.0, 0.0, 1.0],
+            ..GpuEdge::zeroed()
+        }
+    }
+
+    fn two_clusters() -> Clustering {
+        Clustering {
+            assignment: vec![0, 0, 1, 1],
+            labels: vec!["philosophy".to_string(), "mathematics".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_aggregate_nodes() {
+        let nodes = vec![
+            gpu_node([0.0, 0.0, 0.0], 0),
+            gpu_node([2.0, 0.0, 0.0], 0),
+            gpu_node([0.0, 4.0, 0.0], 1),
+            gpu_node([0.0, 6.0, 0.0], 1),
+        ];
+
+        let level = aggregate_level(&two_clusters(), &nodes, &[]);
+
+        assert_eq!(level.nodes.len(), 2);
+        assert_eq!(level.nodes[0].position, [1.0, 0.0, 0.0]);
+        assert_eq!(level.nodes[1].position, [0.0, 5.0, 0.0]);
+        assert_eq!(level.nodes[0].size, 2.0);
+        assert_eq!(level.nodes[0].scalar, 2.0);
+        assert_eq!(level.nodes[1].domain_id, 1);
+        assert_eq!(level.nodes[0].color, [1.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_aggregate_edges_merges_and_drops_internal() {
+        let nodes = vec![gpu_node([0.0; 3], 0); 4];
+        let edges = vec![
+            gpu_edge(0, 1, 0, 1.0), // internal to cluster 0
+            gpu_edge(0, 2, 0, 0.5),
+            gpu_edge(1, 3, 0, 0.25), // same clusters + relation as above
+            gpu_edge(1, 3, 1, 0.9),  // different relation
+            gpu_edge(2, 0, 0, 0.3),  // opposite direction
+        ];
+
+        let level = aggregate_level(&two_clusters(), &nodes, &edges);
+
+        assert_eq!(level.edges.len(), 3);
+        let merged = level.edges[0];
+        assert_eq!((merged.from, merged.to, merged.relation_id), (0, 1, 0));
+        assert!((merged.weight - 0.75).abs() < 1e-6);
+        assert_eq!(merged.color, [0.0, 1.0, 0.0, 1.0]);
+        assert_eq!((level.edges[2].from, level.edges[2].to), (1, 0));
+    }
+
+    #[test]
+    fn test_aggregate_edges_keep_structural_flags_only() {
+        let nodes = vec![gpu_node([0.0; 3], 0); 4];
+        let mut bridge = gpu_edge(0, 2, 0, 0.5);
+        bridge.flags = (RenderFlags::BRIDGE | RenderFlags::FOCUS_PATH).bits();
+        let mut selected = gpu_edge(1, 3, 0, 0.5);
+        selected.flags = (RenderFlags::CROSS_DOMAIN | RenderFlags::SELECTED).bits();
+
+        let level = aggregate_level(&two_clusters(), &nodes, &[bridge, selected]);
+
+        assert_eq!(
+            level.edges[0].flags,
+            (RenderFlags::BRIDGE | RenderFlags::CROSS_DOMAIN).bits()
+        );
+    }
+
+    #[test]
+    fn test_build_lod_levels_per_clustering() {
+        let nodes = vec![gpu_node([0.0; 3], 0); 4];
+        let single = Clustering {
+            assignment: vec![0; 4],
+            labels: vec!["all".to_string()],
+        };
+
+        let levels = build_lod_levels(&[single, two_clusters()], &nodes, &[]);
+
+        assert_eq!(levels.len(), 2);
+        assert_eq!(levels[0].nodes.len(), 1);
+        assert_eq!(levels[0].nodes[0].size, 4.0);
+        assert_eq!(levels[1].nodes.len(), 2);
+    }
+}
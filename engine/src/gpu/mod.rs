@@ -1,6 +1,15 @@
 //! GPU buffer types
 
 pub mod buffers;
+pub mod cache;
+pub mod dirty;
+pub mod flags;
+pub mod format;
+pub mod geometry;
+pub mod interaction;
+pub mod lod;
+pub mod picking;
 pub mod types;
 
+pub use flags::RenderFlags;
 pub use types::{GpuEdge, GpuNode};
@@ -0,0 +1,188 @@
+//! GPU picking: colour encoding and index↔ID mapping
+//!
+//! Record `i` of the node buffer is `graph.nodes[i]`, but edge buffers skip
+//! edges with missing endpoints, so edge record `j` is not necessarily
+//! `graph.edges[j]`. This module makes both mappings explicit and encodes
+//! each record as a unique RGBA colour for an offscreen hit-test pass:
+//!
+//! - RGB holds `index + 1` as a 24-bit little-endian integer (0 = background)
+//! - A holds the record kind (1 = node, 2 = edge)
+
+use crate::graph::GraphStore;
+
+/// Alpha value marking a node pick colour
+pub const PICK_KIND_NODE: u8 = 1;
+
+/// Alpha value marking an edge pick colour
+pub const PICK_KIND_EDGE: u8 = 2;
+
+/// Largest record index representable in a pick colour
+pub const MAX_PICK_INDEX: usize = 0x00FF_FFFE;
+
+/// What a pick colour refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickTarget {
+    /// Node record index (same as index into `GraphStore::nodes`)
+    Node(usize),
+    /// Edge record index (index into the edge GPU buffer)
+    Edge(usize),
+}
+
+/// Encodes a record as an RGBA8 pick colour
+///
+/// Returns `None` if `index` exceeds [`MAX_PICK_INDEX`].
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub const fn encode_pick_color(target: PickTarget) -> Option<[u8; 4]> {
+    let (index, kind) = match target {
+        PickTarget::Node(index) => (index, PICK_KIND_NODE),
+        PickTarget::Edge(index) => (index, PICK_KIND_EDGE),
+    };
+    if index > MAX_PICK_INDEX {
+        return None;
+    }
+
+    let id = index + 1;
+    Some([id as u8, (id >> 8) as u8, (id >> 16) as u8, kind])
+}
+
+/// Decodes an RGBA8 pick colour read back from the hit-test target
+///
+/// Returns `None` for background pixels or unknown kinds.
+#[must_use]
+pub const fn decode_pick_color(rgba: [u8; 4]) -> Option<PickTarget> {
+    let id = rgba[0] as usize | (rgba[1] as usize) << 8 | (rgba[2] as usize) << 16;
+    if id == 0 {
+        return None;
+    }
+
+    match rgba[3] {
+        PICK_KIND_NODE => Some(PickTarget::Node(id - 1)),
+        PICK_KIND_EDGE => Some(PickTarget::Edge(id - 1)),
+        _ => None,
+    }
+}
+
+/// Returns the `GraphStore::edges` index of each edge GPU record
+///
+/// Mirrors the filtering done by `buffers::build_gpu_edges`.
+#[must_use]
+pub fn edge_record_indices(graph: &GraphStore) -> Vec<usize> {
+    graph
+        .edges
+        .iter()
+        .enumerate()
+        .filter(|(_, edge)| {
+            graph.id_to_idx.contains_key(&edge.from) && graph.id_to_idx.contains_key(&edge.to)
+        })
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Returns node IDs in node buffer order
+#[must_use]
+pub fn node_ids(graph: &GraphStore) -> Vec<&str> {
+    graph.nodes.iter().map(|node| node.id.as_str()).collect()
+}
+
+/// Generates the node pick-colour buffer (4 bytes per node record)
+#[must_use]
+pub fn generate_node_pick_buffer(graph: &GraphStore) -> Vec<u8> {
+    (0..graph.nodes.len())
+        .flat_map(|idx| encode_pick_color(PickTarget::Node(idx)).unwrap_or([0; 4]))
+        .collect()
+}
+
+/// Generates the edge pick-colour buffer (4 bytes per edge record)
+#[must_use]
+pub fn generate_edge_pick_buffer(graph: &GraphStore) -> Vec<u8> {
+    (0..edge_record_indices(graph).len())
+        .flat_map(|idx| encode_pick_color(PickTarget::Edge(idx)).unwrap_or([0; 4]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Edge, Node};
+
+    fn create_test_node(id: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            r#type: "proposition".to_string(),
+            domain: "philosophy".to_string(),
+            title: format!("Node {id}"),
+            content: None,
+            formal: None,
+            tags: vec![],
+            metadata: serde_json::Value::Null,
+            sources: vec![],
+            created: None,
+            updated: None,
+            status: None,
+        }
+    }
+
+    fn create_test_edge(from: &str, to: &str) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            relation: "supports".to_string(),
+            domain: "philosophy".to_string(),
+            weight: Some(0.9),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_pick_color_roundtrip() {
+        for target in [
+            PickTarget::Node(0),
+            PickTarget::Node(255),
+            PickTarget::Node(70_000),
+            PickTarget::Edge(0),
+            PickTarget::Edge(MAX_PICK_INDEX),
+        ] {
+            let color = encode_pick_color(target).unwrap();
+            assert_eq!(decode_pick_color(color), Some(target));
+        }
+    }
+
+    #[test]
+    fn test_pick_color_background_and_overflow() {
+        assert_eq!(decode_pick_color([0, 0, 0, 0]), None);
+        assert_eq!(decode_pick_color([0, 0, 0, PICK_KIND_NODE]), None);
+        assert_eq!(decode_pick_color([5, 0, 0, 99]), None);
+        assert_eq!(
+            encode_pick_color(PickTarget::Node(MAX_PICK_INDEX + 1)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_edge_record_indices_skip_dangling() {
+        let mut store = GraphStore::new();
+        store.add_node(create_test_node("a"));
+        store.add_node(create_test_node("b"));
+        store.add_edge(create_test_edge("a", "missing"));
+        store.add_edge(create_test_edge("a", "b"));
+
+        assert_eq!(edge_record_indices(&store), vec![1]);
+        assert_eq!(generate_edge_pick_buffer(&store).len(), 4);
+    }
+
+    #[test]
+    fn test_node_pick_buffer_matches_node_order() {
+        let mut store = GraphStore::new();
+        store.add_node(create_test_node("a"));
+        store.add_node(create_test_node("b"));
+
+        let buffer = generate_node_pick_buffer(&store);
+
+        assert_eq!(node_ids(&store), vec!["a", "b"]);
+        assert_eq!(
+            decode_pick_color([buffer[4], buffer[5], buffer[6], buffer[7]]),
+            Some(PickTarget::Node(1))
+        );
+    }
+}
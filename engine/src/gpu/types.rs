@@ -37,6 +37,7 @@ pub struct GpuNode {
     /// Status flags: bit 0=contested, bit 1=refuted, bit 2=draft, bit 3=archived (Gemini addition)
     pub status: u32,
     /// Padding for alignment (64-byte total)
+    #[allow(clippy::pub_underscore_fields)]
     pub _padding: u32,
 }
 
@@ -218,9 +219,11 @@ mod tests {
     fn test_gpu_types_are_copy() {
         // Verify both types implement Copy
         let node = GpuNode::zeroed();
-        let _node_copy = node; // Should compile (Copy trait)
+        let node_copy = node; // Should compile (Copy trait)
+        assert_eq!(node.domain_id, node_copy.domain_id);
 
         let edge = GpuEdge::zeroed();
-        let _edge_copy = edge; // Should compile (Copy trait)
+        let edge_copy = edge; // Should compile (Copy trait)
+        assert_eq!(edge.from, edge_copy.from);
     }
 }
@@ -1,6 +1,7 @@
-//! Domain filtering operations
+//! Filtering operations: domain filters and induced subgraphs
 
 use super::{GraphStore, Node};
+use std::collections::HashSet;
 
 impl GraphStore {
     /// Filters nodes by domain
@@ -29,6 +30,30 @@ impl GraphStore {
             .filter(|node| domains.contains(&node.domain.as_str()))
             .collect()
     }
+
+    /// Returns the subgraph induced by `ids`: those nodes, in graph order,
+    /// and the edges between them
+    ///
+    /// Unknown IDs are ignored.
+    #[must_use]
+    pub fn subgraph<'a>(&self, ids: impl IntoIterator<Item = &'a str>) -> Self {
+        let ids: HashSet<&str> = ids.into_iter().collect();
+        let mut subgraph = Self::new();
+        for node in self
+            .nodes
+            .iter()
+            .filter(|node| ids.contains(node.id.as_str()))
+        {
+            subgraph.add_node(node.clone());
+        }
+        for edge in &self.edges {
+            if ids.contains(edge.from.as_str()) && ids.contains(edge.to.as_str()) {
+                subgraph.add_edge(edge.clone());
+            }
+        }
+        subgraph.build_adjacency();
+        subgraph
+    }
 }
 
 #[cfg(test)]
@@ -49,6 +74,7 @@ mod tests {
             sources: vec![],
             created: None,
             updated: None,
+            status: None,
         }
     }
 
@@ -105,4 +131,22 @@ mod tests {
         assert_eq!(neighbors.len(), 1);
         assert_eq!(neighbors[0].id, "c");
     }
+
+    #[test]
+    fn test_subgraph_keeps_internal_edges() {
+        let mut store = GraphStore::new();
+        store.add_node(create_test_node("a", "philosophy"));
+        store.add_node(create_test_node("b", "mathematics"));
+        store.add_node(create_test_node("c", "physics"));
+        store.add_edge(create_edge("a", "b"));
+        store.add_edge(create_edge("b", "c"));
+        store.build_adjacency();
+
+        let subgraph = store.subgraph(["b", "a", "missing"]);
+
+        let ids: Vec<_> = subgraph.nodes().iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert_eq!(subgraph.edge_count(), 1);
+        assert_eq!(subgraph.neighbors("a", 1)[0].id, "b");
+    }
 }
@@ -0,0 +1,295 @@
+//! Node ID allocation
+//!
+//! IDs match `^[a-z0-9]{6}$`. New IDs are drawn from a 64-bit value, either
+//! a random seed or a hash of the node's content, and re-drawn until one is
+//! free in the graph. An optional prefix (see [`domain_prefix`]) fixes the
+//! leading characters.
+
+use super::GraphStore;
+use crate::error::{Error, Result};
+use std::collections::HashSet;
+
+/// Length of every node ID
+pub const ID_LENGTH: usize = 6;
+
+/// Characters node IDs are made of
+const ID_ALPHABET: &[u8; 36] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Longest allowed prefix, leaving at least one generated character
+const MAX_PREFIX_LENGTH: usize = ID_LENGTH - 1;
+
+/// Draws tried before giving up on a nearly full prefix
+const MAX_ATTEMPTS: u64 = 10_000;
+
+/// ID prefixes of the domain-prefix convention
+pub const DOMAIN_PREFIXES: &[(&str, &str)] = &[
+    ("philosophy", "ph"),
+    ("mathematics", "ma"),
+    ("physics", "py"),
+];
+
+/// Where the bits of a new ID come from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdStrategy {
+    /// Random, from a caller-supplied seed
+    Random(u64),
+    /// Derived from content (e.g. the node title): the same content gives
+    /// the same ID as long as it is free
+    Content(String),
+}
+
+impl IdStrategy {
+    /// Random strategy seeded from the process's hash keys and the clock
+    #[cfg(not(target_arch = "wasm32"))]
+    #[must_use]
+    pub fn random() -> Self {
+        use std::hash::{BuildHasher, Hasher};
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+        hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+        if let Ok(elapsed) = std::time::UNIX_EPOCH.elapsed() {
+            hasher.write_u128(elapsed.as_nanos());
+        }
+        Self::Random(hasher.finish())
+    }
+
+    fn seed(&self) -> u64 {
+        match self {
+            Self::Random(seed) => *seed,
+            Self::Content(content) => fnv1a(content.as_bytes()),
+        }
+    }
+}
+
+/// Options for [`GraphStore::generate_id`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdOptions {
+    /// Source of the generated characters
+    pub strategy: IdStrategy,
+    /// Leading characters every generated ID starts with
+    pub prefix: Option<String>,
+}
+
+impl IdOptions {
+    /// Options with no prefix
+    #[must_use]
+    pub const fn new(strategy: IdStrategy) -> Self {
+        Self {
+            strategy,
+            prefix: None,
+        }
+    }
+
+    /// Sets the prefix to the convention for `domain` (no prefix if unknown)
+    #[must_use]
+    pub fn with_domain(mut self, domain: &str) -> Self {
+        self.prefix = domain_prefix(domain).map(str::to_string);
+        self
+    }
+
+    /// Sets an explicit prefix
+    #[must_use]
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+}
+
+/// Returns the conventional ID prefix for a domain
+#[must_use]
+pub fn domain_prefix(domain: &str) -> Option<&'static str> {
+    DOMAIN_PREFIXES
+        .iter()
+        .find(|(name, _)| *name == domain)
+        .map(|(_, prefix)| *prefix)
+}
+
+/// Returns true if `id` matches `^[a-z0-9]{6}$`
+#[must_use]
+pub fn is_valid_id(id: &str) -> bool {
+    id.len() == ID_LENGTH && id.bytes().all(|b| ID_ALPHABET.contains(&b))
+}
+
+impl GraphStore {
+    /// Generates a node ID not used by any node in the graph
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Schema` error if the prefix is longer than five characters
+    /// or not `[a-z0-9]`, or an `Integrity` error if no free ID was found
+    /// (the prefix is nearly exhausted)
+    pub fn generate_id(&self, options: &IdOptions) -> Result<String> {
+        self.generate_ids(options, 1)
+            .map(|mut ids| ids.swap_remove(0))
+    }
+
+    /// Generates `count` distinct node IDs not used by any node in the graph
+    ///
+    /// # Errors
+    ///
+    /// Same as [`GraphStore::generate_id`]
+    pub fn generate_ids(&self, options: &IdOptions, count: usize) -> Result<Vec<String>> {
+        let prefix = options.prefix.as_deref().unwrap_or_default();
+        if prefix.len() > MAX_PREFIX_LENGTH || !prefix.bytes().all(|b| ID_ALPHABET.contains(&b)) {
+            return Err(Error::schema(format!(
+                "ID prefix '{prefix}' must be at most {MAX_PREFIX_LENGTH} characters of [a-z0-9]"
+            )));
+        }
+
+        let seed = options.strategy.seed();
+        let mut ids = Vec::with_capacity(count);
+        let mut taken = HashSet::new();
+        let mut attempt = 0;
+        while ids.len() < count {
+            if attempt == MAX_ATTEMPTS * count as u64 {
+                return Err(Error::integrity(format!(
+                    "No free node ID with prefix '{prefix}' after {attempt} attempts"
+                )));
+            }
+            let id = encode(prefix, splitmix64(seed.wrapping_add(attempt)));
+            attempt += 1;
+            if !self.id_to_idx.contains_key(&id) && taken.insert(id.clone()) {
+                ids.push(id);
+            }
+        }
+        Ok(ids)
+    }
+}
+
+/// Fills the characters after `prefix` from `bits`, base 36
+fn encode(prefix: &str, mut bits: u64) -> String {
+    let mut id = String::with_capacity(ID_LENGTH);
+    id.push_str(prefix);
+    while id.len() < ID_LENGTH {
+        id.push(char::from(ID_ALPHABET[(bits % 36) as usize]));
+        bits /= 36;
+    }
+    id
+}
+
+/// 64-bit FNV-1a: stable across platforms and Rust versions, unlike `DefaultHasher`
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// `SplitMix64` finaliser: spreads consecutive inputs over all 64 bits
+const fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Node;
+
+    fn create_test_node(id: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            r#type: "concept".to_string(),
+            domain: "philosophy".to_string(),
+            title: format!("Node {id}"),
+            content: None,
+            formal: None,
+            tags: vec![],
+            metadata: serde_json::Value::Null,
+            sources: vec![],
+            created: None,
+            updated: None,
+            status: None,
+        }
+    }
+
+    #[test]
+    fn test_generated_ids_are_valid() {
+        let graph = GraphStore::new();
+        let ids = graph
+            .generate_ids(&IdOptions::new(IdStrategy::Random(42)), 100)
+            .unwrap();
+
+        assert_eq!(ids.len(), 100);
+        assert!(ids.iter().all(|id| is_valid_id(id)));
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 100);
+    }
+
+    #[test]
+    fn test_content_ids_are_deterministic() {
+        let graph = GraphStore::new();
+        let options = IdOptions::new(IdStrategy::Content("Modus Ponens".to_string()));
+
+        let first = graph.generate_id(&options).unwrap();
+        assert_eq!(graph.generate_id(&options).unwrap(), first);
+
+        let other = IdOptions::new(IdStrategy::Content("Modus Tollens".to_string()));
+        assert_ne!(graph.generate_id(&other).unwrap(), first);
+    }
+
+    #[test]
+    fn test_generated_id_skips_taken_ids() {
+        let options = IdOptions::new(IdStrategy::Content("Modus Ponens".to_string()));
+        let mut graph = GraphStore::new();
+        let first = graph.generate_id(&options).unwrap();
+
+        graph.add_node(create_test_node(&first));
+        let second = graph.generate_id(&options).unwrap();
+        assert_ne!(second, first);
+        assert!(is_valid_id(&second));
+    }
+
+    #[test]
+    fn test_prefix_is_kept() {
+        let graph = GraphStore::new();
+        let options = IdOptions::new(IdStrategy::Random(7)).with_domain("physics");
+        assert!(graph.generate_id(&options).unwrap().starts_with("py"));
+
+        let options = IdOptions::new(IdStrategy::Random(7)).with_prefix("abcde");
+        assert!(graph.generate_id(&options).unwrap().starts_with("abcde"));
+    }
+
+    #[test]
+    fn test_exhausted_prefix_fails() {
+        let mut graph = GraphStore::new();
+        for &c in ID_ALPHABET {
+            graph.add_node(create_test_node(&format!("abcde{}", char::from(c))));
+        }
+
+        let options = IdOptions::new(IdStrategy::Random(1)).with_prefix("abcde");
+        let err = graph.generate_id(&options).unwrap_err();
+        assert!(err.message().contains("No free node ID"));
+    }
+
+    #[test]
+    fn test_invalid_prefix_rejected() {
+        let graph = GraphStore::new();
+        for prefix in ["abcdef", "AB", "a-b"] {
+            let options = IdOptions::new(IdStrategy::Random(1)).with_prefix(prefix);
+            assert!(graph.generate_id(&options).is_err(), "{prefix}");
+        }
+    }
+
+    #[test]
+    fn test_unknown_domain_has_no_prefix() {
+        assert_eq!(domain_prefix("biology"), None);
+        let options = IdOptions::new(IdStrategy::Random(1)).with_domain("biology");
+        assert_eq!(options.prefix, None);
+    }
+
+    #[test]
+    fn test_is_valid_id() {
+        assert!(is_valid_id("0lg001"));
+        assert!(!is_valid_id("0lg01"));
+        assert!(!is_valid_id("0LG001"));
+        assert!(!is_valid_id("0lg_01"));
+    }
+
+    #[test]
+    fn test_random_strategy_varies() {
+        assert_ne!(IdStrategy::random(), IdStrategy::random());
+    }
+}
@@ -2,8 +2,10 @@
 
 pub mod edge;
 pub mod filter;
+pub mod ids;
 pub mod node;
 pub mod query;
+pub mod refactor;
 pub mod store;
 
 pub use edge::Edge;
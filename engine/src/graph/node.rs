@@ -36,6 +36,9 @@ pub struct Node {
     /// ISO 8601 timestamp of last update
     #[serde(skip_serializing_if = "Option::is_none")]
     pub updated: Option<String>,
+    /// Lifecycle status (draft, stable, refuted, archived); absent means stable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
 }
 
 #[cfg(test)]
@@ -91,6 +94,7 @@ mod tests {
             sources: vec![],
             created: None,
             updated: None,
+            status: None,
         };
 
         let json = serde_json::to_string(&node).unwrap();
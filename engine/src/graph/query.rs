@@ -140,6 +140,7 @@ mod tests {
             sources: vec![],
             created: None,
             updated: None,
+            status: None,
         }
     }
 
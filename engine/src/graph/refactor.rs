@@ -0,0 +1,702 @@
+//! Refactorings that change node identity: rename, merge and split
+//!
+//! Each operation rewrites every incident edge, keeps the ID index and
+//! adjacency consistent and returns a [`RefactorReport`] describing exactly
+//! which nodes and edges changed, so that the change can be written back to
+//! the repository files (see [`crate::parsers::writer`]).
+
+use super::ids::is_valid_id;
+use super::{Edge, GraphStore, Node};
+use crate::error::{Error, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// How the weights of parallel edges are combined when they are deduplicated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WeightPolicy {
+    /// Strongest weight wins
+    #[default]
+    Max,
+    /// Weakest weight wins
+    Min,
+    /// Average of the weights
+    Mean,
+    /// Weight of the edge that was in the graph first
+    First,
+}
+
+impl WeightPolicy {
+    /// Combines the weights of parallel edges, ignoring unweighted ones
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // edge counts are tiny
+    pub fn combine(self, weights: &[Option<f32>]) -> Option<f32> {
+        let mut present = weights.iter().flatten().copied();
+        match self {
+            Self::Max => present.reduce(f32::max),
+            Self::Min => present.reduce(f32::min),
+            Self::Mean => {
+                let present: Vec<f32> = present.collect();
+                (!present.is_empty()).then(|| present.iter().sum::<f32>() / present.len() as f32)
+            }
+            Self::First => present.next(),
+        }
+    }
+}
+
+/// An edge as it was before and after a refactoring
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)] // Edge weight is f32
+pub struct EdgeChange {
+    /// Edge as it was in the graph
+    pub before: Edge,
+    /// Edge as it is now
+    pub after: Edge,
+}
+
+/// What a refactoring changed
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)] // Edge weight is f32
+pub struct RefactorReport {
+    /// Nodes whose ID changed, as `(old, new)`
+    pub renamed_nodes: Vec<(String, String)>,
+    /// Nodes that did not exist before
+    pub added_nodes: Vec<String>,
+    /// Nodes whose fields changed
+    pub updated_nodes: Vec<String>,
+    /// Nodes that no longer exist
+    pub removed_nodes: Vec<String>,
+    /// Edges that still exist but changed endpoints, weight or metadata
+    pub changed_edges: Vec<EdgeChange>,
+    /// Edges that no longer exist, as they were before
+    pub removed_edges: Vec<Edge>,
+}
+
+impl RefactorReport {
+    /// Returns true if nothing changed
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.renamed_nodes.is_empty()
+            && self.added_nodes.is_empty()
+            && self.updated_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.changed_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+/// One of the nodes a split produces
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitPart {
+    /// ID of the new node; may be the split node's own ID to keep it
+    pub id: String,
+    /// Title of the new node
+    pub title: String,
+    /// Content of the new node (default: the split node's content)
+    pub content: Option<String>,
+}
+
+impl SplitPart {
+    /// Part with the given ID and title, keeping the split node's content
+    #[must_use]
+    pub fn new(id: impl Into<String>, title: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            content: None,
+        }
+    }
+}
+
+impl GraphStore {
+    /// Renames a node and rewrites every edge that refers to it
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Schema` error if `new_id` is not a valid node ID, or an
+    /// `Integrity` error if `old_id` does not exist or `new_id` is taken
+    pub fn rename_node(&mut self, old_id: &str, new_id: &str) -> Result<RefactorReport> {
+        let idx = self.require(old_id)?;
+        if !is_valid_id(new_id) {
+            return Err(Error::schema(format!(
+                "Node ID '{new_id}' must match ^[a-z0-9]{{6}}$"
+            )));
+        }
+        if self.id_to_idx.contains_key(new_id) {
+            return Err(Error::integrity(format!("Node '{new_id}' already exists")));
+        }
+
+        self.nodes[idx].id = new_id.to_string();
+        let mut report = RefactorReport {
+            renamed_nodes: vec![(old_id.to_string(), new_id.to_string())],
+            ..RefactorReport::default()
+        };
+        for edge in &mut self.edges {
+            let before = edge.clone();
+            if rewrite_endpoints(edge, old_id, new_id) {
+                report.changed_edges.push(EdgeChange {
+                    before,
+                    after: edge.clone(),
+                });
+            }
+        }
+
+        self.reindex();
+        Ok(report)
+    }
+
+    /// Merges node `drop_id` into `keep_id`
+    ///
+    /// Edges of `drop_id` are moved to `keep_id`. Edges between the two
+    /// nodes are removed rather than becoming self-loops, and edges of
+    /// `keep_id` that end up with the same endpoints and relation are
+    /// collapsed into the first one, with weights combined by `policy`.
+    /// `tags` and `sources` are unioned; other fields of `keep_id` win, with
+    /// missing ones (including metadata keys) filled in from `drop_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Integrity` error if either node does not exist or both
+    /// IDs are the same
+    pub fn merge_nodes(
+        &mut self,
+        keep_id: &str,
+        drop_id: &str,
+        policy: WeightPolicy,
+    ) -> Result<RefactorReport> {
+        let keep_idx = self.require(keep_id)?;
+        let drop_idx = self.require(drop_id)?;
+        if keep_idx == drop_idx {
+            return Err(Error::integrity(format!(
+                "Cannot merge node '{keep_id}' into itself"
+            )));
+        }
+
+        let dropped = self.nodes.remove(drop_idx);
+        let keep_idx = if drop_idx < keep_idx {
+            keep_idx - 1
+        } else {
+            keep_idx
+        };
+        absorb(&mut self.nodes[keep_idx], dropped);
+
+        let mut report = RefactorReport {
+            updated_nodes: vec![keep_id.to_string()],
+            removed_nodes: vec![drop_id.to_string()],
+            ..RefactorReport::default()
+        };
+
+        let mut originals: Vec<Edge> = Vec::with_capacity(self.edges.len());
+        let mut merged: Vec<Edge> = Vec::with_capacity(self.edges.len());
+        let mut weights: Vec<Vec<Option<f32>>> = Vec::with_capacity(self.edges.len());
+        let mut incident: HashMap<(String, String, String), usize> = HashMap::new();
+
+        for mut edge in std::mem::take(&mut self.edges) {
+            let original = edge.clone();
+            let moved = rewrite_endpoints(&mut edge, drop_id, keep_id);
+            if moved && edge.from == edge.to {
+                report.removed_edges.push(original);
+                continue;
+            }
+
+            if edge.from == keep_id || edge.to == keep_id {
+                let key = (edge.from.clone(), edge.to.clone(), edge.relation.clone());
+                if let Some(&pos) = incident.get(&key) {
+                    weights[pos].push(edge.weight);
+                    if merged[pos].metadata.is_none() {
+                        merged[pos].metadata = edge.metadata;
+                    }
+                    report.removed_edges.push(original);
+                    continue;
+                }
+                incident.insert(key, merged.len());
+            }
+
+            weights.push(vec![edge.weight]);
+            originals.push(original);
+            merged.push(edge);
+        }
+
+        for ((edge, weights), before) in merged.iter_mut().zip(&weights).zip(originals) {
+            if weights.len() > 1 {
+                edge.weight = policy.combine(weights);
+            }
+            if *edge != before {
+                report.changed_edges.push(EdgeChange {
+                    before,
+                    after: edge.clone(),
+                });
+            }
+        }
+
+        self.edges = merged;
+        self.reindex();
+        Ok(report)
+    }
+
+    /// Splits node `id` into `parts`, reassigning each of its edges to one part
+    ///
+    /// Each part is a copy of the split node (the template) with its own
+    /// ID, title and, if given, content. `assign` is called once for every
+    /// edge incident to `id`, in graph order, and returns the index of the
+    /// part that takes it over; a self-loop moves as a whole. The node is
+    /// replaced by the parts at its position, unless one part keeps its ID,
+    /// in which case that part updates it in place.
+    ///
+    /// Nothing changes unless every edge was assigned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Integrity` error if `id` does not exist, fewer than two
+    /// parts are given, a part ID is duplicated or taken by another node,
+    /// or `assign` returns an index out of range; a `Schema` error if a
+    /// part ID is not a valid node ID; and any error `assign` returns
+    pub fn split_node<F>(
+        &mut self,
+        id: &str,
+        parts: &[SplitPart],
+        mut assign: F,
+    ) -> Result<RefactorReport>
+    where
+        F: FnMut(&Edge, &[SplitPart]) -> Result<usize>,
+    {
+        let idx = self.require(id)?;
+        if parts.len() < 2 {
+            return Err(Error::integrity(format!(
+                "Splitting node '{id}' needs at least two parts"
+            )));
+        }
+        for (pos, part) in parts.iter().enumerate() {
+            if !is_valid_id(&part.id) {
+                return Err(Error::schema(format!(
+                    "Node ID '{}' must match ^[a-z0-9]{{6}}$",
+                    part.id
+                )));
+            }
+            if parts[..pos].iter().any(|other| other.id == part.id) {
+                return Err(Error::integrity(format!(
+                    "Part ID '{}' is used twice",
+                    part.id
+                )));
+            }
+            if part.id != id && self.id_to_idx.contains_key(&part.id) {
+                return Err(Error::integrity(format!(
+                    "Node '{}' already exists",
+                    part.id
+                )));
+            }
+        }
+
+        let mut assignments = Vec::new();
+        for (edge_idx, edge) in self.edges.iter().enumerate() {
+            if edge.from != id && edge.to != id {
+                continue;
+            }
+            let part = assign(edge, parts)?;
+            if part >= parts.len() {
+                return Err(Error::integrity(format!(
+                    "Edge {} -[{}]-> {} was assigned to part {part} of {}",
+                    edge.from,
+                    edge.relation,
+                    edge.to,
+                    parts.len()
+                )));
+            }
+            assignments.push((edge_idx, part));
+        }
+
+        let mut report = RefactorReport::default();
+        for part in parts {
+            if part.id == id {
+                report.updated_nodes.push(part.id.clone());
+            } else {
+                report.added_nodes.push(part.id.clone());
+            }
+        }
+        if !parts.iter().any(|part| part.id == id) {
+            report.removed_nodes.push(id.to_string());
+        }
+
+        let template = &self.nodes[idx];
+        let new_nodes: Vec<Node> = parts
+            .iter()
+            .map(|part| Node {
+                id: part.id.clone(),
+                title: part.title.clone(),
+                content: part.content.clone().or_else(|| template.content.clone()),
+                ..template.clone()
+            })
+            .collect();
+        self.nodes.splice(idx..=idx, new_nodes);
+
+        for (edge_idx, part) in assignments {
+            let edge = &mut self.edges[edge_idx];
+            let before = edge.clone();
+            rewrite_endpoints(edge, id, &parts[part].id);
+            if *edge != before {
+                report.changed_edges.push(EdgeChange {
+                    before,
+                    after: edge.clone(),
+                });
+            }
+        }
+
+        self.reindex();
+        Ok(report)
+    }
+
+    /// Returns the index of `id`, or an error naming it
+    pub(crate) fn require(&self, id: &str) -> Result<usize> {
+        self.index_of(id)
+            .ok_or_else(|| Error::integrity(format!("Unknown node '{id}'")))
+    }
+}
+
+/// Points the ends of `edge` at `to` wherever they were `from`
+///
+/// Returns true if either end changed.
+pub(crate) fn rewrite_endpoints(edge: &mut Edge, from: &str, to: &str) -> bool {
+    let mut changed = false;
+    for end in [&mut edge.from, &mut edge.to] {
+        if end == from {
+            *end = to.to_string();
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Folds the fields of `drop` into `keep`
+fn absorb(keep: &mut Node, drop: Node) {
+    union(&mut keep.tags, drop.tags);
+    union(&mut keep.sources, drop.sources);
+    for (field, other) in [
+        (&mut keep.content, drop.content),
+        (&mut keep.formal, drop.formal),
+        (&mut keep.created, drop.created),
+        (&mut keep.updated, drop.updated),
+        (&mut keep.status, drop.status),
+    ] {
+        if field.is_none() {
+            *field = other;
+        }
+    }
+
+    match (&mut keep.metadata, drop.metadata) {
+        (Value::Object(keep), Value::Object(drop)) => {
+            for (key, value) in drop {
+                keep.entry(key).or_insert(value);
+            }
+        }
+        (keep @ Value::Null, drop) => *keep = drop,
+        _ => {}
+    }
+}
+
+/// Appends the items of `other` that `items` does not contain yet
+fn union(items: &mut Vec<String>, other: Vec<String>) {
+    for item in other {
+        if !items.contains(&item) {
+            items.push(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn create_test_node(id: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            r#type: "proposition".to_string(),
+            domain: "philosophy".to_string(),
+            title: format!("Node {id}"),
+            content: None,
+            formal: None,
+            tags: vec![],
+            metadata: Value::Null,
+            sources: vec![],
+            created: None,
+            updated: None,
+            status: None,
+        }
+    }
+
+    fn create_test_edge(from: &str, to: &str, relation: &str, weight: Option<f32>) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            relation: relation.to_string(),
+            domain: "philosophy".to_string(),
+            weight,
+            metadata: None,
+        }
+    }
+
+    fn create_graph(ids: &[&str], edges: Vec<Edge>) -> GraphStore {
+        let mut graph = GraphStore::new();
+        for id in ids {
+            graph.add_node(create_test_node(id));
+        }
+        for edge in edges {
+            graph.add_edge(edge);
+        }
+        graph.build_adjacency();
+        graph
+    }
+
+    #[test]
+    fn test_rename_rewrites_edges_and_index() {
+        let mut graph = create_graph(
+            &["aaa111", "bbb222", "ccc333"],
+            vec![
+                create_test_edge("aaa111", "bbb222", "supports", Some(0.5)),
+                create_test_edge("ccc333", "aaa111", "attacks", None),
+                create_test_edge("bbb222", "ccc333", "supports", None),
+            ],
+        );
+
+        let report = graph.rename_node("aaa111", "zzz999").unwrap();
+
+        assert_eq!(
+            report.renamed_nodes,
+            vec![("aaa111".to_string(), "zzz999".to_string())]
+        );
+        assert_eq!(report.changed_edges.len(), 2);
+        assert!(graph.get_node("aaa111").is_none());
+        assert_eq!(graph.get_node("zzz999").unwrap().title, "Node aaa111");
+        assert_eq!(graph.edges()[0].from, "zzz999");
+        assert_eq!(graph.edges()[1].to, "zzz999");
+
+        let idx = graph.index_of("zzz999").unwrap();
+        assert_eq!(
+            graph.out_edges[idx],
+            vec![graph.index_of("bbb222").unwrap()]
+        );
+        assert_eq!(graph.in_edges[idx], vec![graph.index_of("ccc333").unwrap()]);
+    }
+
+    #[test]
+    fn test_rename_rejects_bad_targets() {
+        let mut graph = create_graph(&["aaa111", "bbb222"], vec![]);
+
+        assert!(graph.rename_node("missing", "ccc333").is_err());
+        assert!(graph.rename_node("aaa111", "bbb222").is_err());
+        assert!(graph.rename_node("aaa111", "NOT_AN_ID").is_err());
+        assert!(graph.get_node("aaa111").is_some());
+    }
+
+    #[test]
+    fn test_merge_moves_edges_and_drops_loops() {
+        let mut graph = create_graph(
+            &["keep01", "drop01", "other1"],
+            vec![
+                create_test_edge("drop01", "other1", "supports", None),
+                create_test_edge("keep01", "drop01", "entails", None),
+                create_test_edge("other1", "drop01", "attacks", Some(0.4)),
+            ],
+        );
+
+        let report = graph
+            .merge_nodes("keep01", "drop01", WeightPolicy::Max)
+            .unwrap();
+
+        assert_eq!(report.removed_nodes, vec!["drop01"]);
+        assert_eq!(report.updated_nodes, vec!["keep01"]);
+        assert_eq!(report.removed_edges.len(), 1, "keep→drop would be a loop");
+        assert_eq!(report.changed_edges.len(), 2);
+        assert_eq!(graph.node_count(), 2);
+        assert!(graph
+            .edges()
+            .iter()
+            .all(|e| e.from != "drop01" && e.to != "drop01"));
+
+        let keep = graph.index_of("keep01").unwrap();
+        let other = graph.index_of("other1").unwrap();
+        assert_eq!(graph.out_edges[keep], vec![other]);
+        assert_eq!(graph.in_edges[keep], vec![other]);
+    }
+
+    #[test]
+    fn test_merge_deduplicates_parallel_edges() {
+        let edges = || {
+            vec![
+                create_test_edge("keep01", "other1", "supports", Some(0.9)),
+                create_test_edge("drop01", "other1", "supports", Some(0.5)),
+                create_test_edge("drop01", "other1", "attacks", Some(0.2)),
+            ]
+        };
+
+        for (policy, expected) in [
+            (WeightPolicy::Max, 0.9),
+            (WeightPolicy::Min, 0.5),
+            (WeightPolicy::Mean, 0.7),
+            (WeightPolicy::First, 0.9),
+        ] {
+            let mut graph = create_graph(&["keep01", "drop01", "other1"], edges());
+            let report = graph.merge_nodes("keep01", "drop01", policy).unwrap();
+
+            assert_eq!(graph.edge_count(), 2, "{policy:?}");
+            let supports = &graph.edges()[0];
+            assert!(
+                (supports.weight.unwrap() - expected).abs() < 1e-6,
+                "{policy:?}"
+            );
+            assert_eq!(report.removed_edges.len(), 1);
+            assert_eq!(report.removed_edges[0].weight, Some(0.5));
+        }
+    }
+
+    #[test]
+    fn test_merge_unions_fields() {
+        let mut keep = create_test_node("keep01");
+        keep.tags = vec!["a".to_string(), "b".to_string()];
+        keep.sources = vec!["s1".to_string()];
+        keep.metadata = json!({"importance": 5});
+        let mut drop = create_test_node("drop01");
+        drop.tags = vec!["b".to_string(), "c".to_string()];
+        drop.sources = vec!["s2".to_string()];
+        drop.content = Some("From drop".to_string());
+        drop.metadata = json!({"importance": 9, "certainty": 0.5});
+
+        let mut graph = GraphStore::new();
+        graph.add_node(drop);
+        graph.add_node(keep);
+        graph
+            .merge_nodes("keep01", "drop01", WeightPolicy::default())
+            .unwrap();
+
+        let merged = graph.get_node("keep01").unwrap();
+        assert_eq!(merged.tags, vec!["a", "b", "c"]);
+        assert_eq!(merged.sources, vec!["s1", "s2"]);
+        assert_eq!(merged.content.as_deref(), Some("From drop"));
+        assert_eq!(merged.metadata, json!({"importance": 5, "certainty": 0.5}));
+        assert_eq!(graph.index_of("keep01"), Some(0));
+    }
+
+    #[test]
+    fn test_merge_rejects_self_and_unknown() {
+        let mut graph = create_graph(&["keep01"], vec![]);
+        assert!(graph
+            .merge_nodes("keep01", "keep01", WeightPolicy::Max)
+            .is_err());
+        assert!(graph
+            .merge_nodes("keep01", "missing", WeightPolicy::Max)
+            .is_err());
+    }
+
+    #[test]
+    fn test_split_reassigns_edges() {
+        let mut graph = create_graph(
+            &["before", "split1", "after1"],
+            vec![
+                create_test_edge("before", "split1", "supports", None),
+                create_test_edge("split1", "after1", "entails", Some(0.7)),
+                create_test_edge("after1", "split1", "attacks", None),
+            ],
+        );
+        graph.nodes[1].tags = vec!["conflated".to_string()];
+        let parts = [
+            SplitPart::new("parta1", "Part A"),
+            SplitPart {
+                content: Some("Only B".to_string()),
+                ..SplitPart::new("partb2", "Part B")
+            },
+        ];
+
+        let report = graph
+            .split_node("split1", &parts, |edge, _| {
+                Ok(usize::from(edge.relation == "attacks"))
+            })
+            .unwrap();
+
+        assert_eq!(report.added_nodes, vec!["parta1", "partb2"]);
+        assert_eq!(report.removed_nodes, vec!["split1"]);
+        assert_eq!(report.changed_edges.len(), 3);
+        assert!(graph.get_node("split1").is_none());
+        assert_eq!(graph.index_of("parta1"), Some(1), "parts take its place");
+
+        let part_b = graph.get_node("partb2").unwrap();
+        assert_eq!(part_b.title, "Part B");
+        assert_eq!(part_b.content.as_deref(), Some("Only B"));
+        assert_eq!(part_b.tags, vec!["conflated"], "copied from the template");
+
+        let a = graph.index_of("parta1").unwrap();
+        let b = graph.index_of("partb2").unwrap();
+        let before = graph.index_of("before").unwrap();
+        let after = graph.index_of("after1").unwrap();
+        assert_eq!(graph.in_edges[a], vec![before]);
+        assert_eq!(graph.out_edges[a], vec![after]);
+        assert_eq!(graph.in_edges[b], vec![after]);
+        assert!(graph.out_edges[b].is_empty());
+    }
+
+    #[test]
+    fn test_split_can_keep_original_id() {
+        let mut graph = create_graph(
+            &["split1", "other1"],
+            vec![create_test_edge("split1", "other1", "supports", None)],
+        );
+        let parts = [
+            SplitPart::new("split1", "Narrower"),
+            SplitPart::new("newpt1", "Rest"),
+        ];
+
+        let report = graph.split_node("split1", &parts, |_, _| Ok(0)).unwrap();
+
+        assert_eq!(report.updated_nodes, vec!["split1"]);
+        assert_eq!(report.added_nodes, vec!["newpt1"]);
+        assert!(report.removed_nodes.is_empty());
+        assert!(report.changed_edges.is_empty());
+        assert_eq!(graph.get_node("split1").unwrap().title, "Narrower");
+        assert_eq!(graph.node_count(), 3);
+    }
+
+    #[test]
+    fn test_failed_split_changes_nothing() {
+        let mut graph = create_graph(
+            &["split1", "other1"],
+            vec![
+                create_test_edge("split1", "other1", "supports", None),
+                create_test_edge("other1", "split1", "attacks", None),
+            ],
+        );
+        let parts = [SplitPart::new("parta1", "A"), SplitPart::new("partb2", "B")];
+
+        assert!(graph.split_node("split1", &parts, |_, _| Ok(2)).is_err());
+        let mut calls = 0;
+        let result = graph.split_node("split1", &parts, |_, _| {
+            calls += 1;
+            if calls == 2 {
+                Err(Error::integrity("cancelled"))
+            } else {
+                Ok(0)
+            }
+        });
+        assert!(result.is_err());
+        assert!(graph.get_node("split1").is_some());
+        assert_eq!(graph.edges()[0].from, "split1");
+
+        for parts in [
+            vec![SplitPart::new("parta1", "A")],
+            vec![SplitPart::new("parta1", "A"), SplitPart::new("parta1", "B")],
+            vec![SplitPart::new("parta1", "A"), SplitPart::new("other1", "B")],
+            vec![SplitPart::new("parta1", "A"), SplitPart::new("BAD", "B")],
+        ] {
+            assert!(graph.split_node("split1", &parts, |_, _| Ok(0)).is_err());
+        }
+        assert_eq!(graph.node_count(), 2);
+    }
+
+    #[test]
+    fn test_weight_policy_ignores_missing_weights() {
+        assert_eq!(WeightPolicy::Max.combine(&[None, None]), None);
+        assert_eq!(WeightPolicy::First.combine(&[None, Some(0.3)]), Some(0.3));
+        assert_eq!(
+            WeightPolicy::Mean.combine(&[Some(0.2), None, Some(0.4)]),
+            Some(0.3)
+        );
+    }
+}
@@ -1,10 +1,13 @@
 //! `GraphStore` implementation
 
 use super::{Edge, Node};
+use crate::error::Result;
+use crate::parsers::loader::load_repository_lenient;
 use std::collections::HashMap;
+use std::path::Path;
 
 /// Main graph storage structure
-#[derive(Default)]
+#[derive(Debug, Clone, Default)]
 pub struct GraphStore {
     pub(crate) nodes: Vec<Node>,
     pub(crate) edges: Vec<Edge>,
@@ -26,9 +29,33 @@ impl GraphStore {
         }
     }
 
+    /// Loads a repository checkout rooted at `root`
+    ///
+    /// Reads `nodes/**/*.json`, `edges/**/*.jsonl` and any `edges/**/*.toon`,
+    /// and returns a store with adjacency built. Use
+    /// [`load_repository_lenient`] to collect every problem instead of
+    /// failing on the first.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first `Io`, `Json` or `Toon` error, or an `Integrity`
+    /// error if an edge's relation does not match its `<relation>.jsonl`
+    /// file name
+    pub fn load_repository(root: &Path) -> Result<Self> {
+        let mut report = load_repository_lenient(root);
+        if !report.diagnostics.is_empty() {
+            return Err(report.diagnostics.swap_remove(0));
+        }
+
+        Ok(report.into_graph_store())
+    }
+
     /// Adds a node to the graph
     ///
     /// If a node with the same ID already exists, it will be replaced.
+    /// To change a node's identity instead, see
+    /// [`rename_node`](Self::rename_node), [`merge_nodes`](Self::merge_nodes)
+    /// and [`split_node`](Self::split_node).
     pub fn add_node(&mut self, node: Node) {
         let node_id = node.id.clone();
 
@@ -84,6 +111,20 @@ impl GraphStore {
         }
     }
 
+    /// Rebuilds the ID index and adjacency after nodes were removed or
+    /// reordered, or edge endpoints rewritten
+    pub(crate) fn reindex(&mut self) {
+        self.id_to_idx = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| (node.id.clone(), idx))
+            .collect();
+        self.out_edges.resize_with(self.nodes.len(), Vec::new);
+        self.in_edges.resize_with(self.nodes.len(), Vec::new);
+        self.build_adjacency();
+    }
+
     /// Returns the number of nodes
     #[must_use]
     pub const fn node_count(&self) -> usize {
@@ -101,6 +142,24 @@ impl GraphStore {
     pub fn nodes(&self) -> &[Node] {
         &self.nodes
     }
+
+    /// Returns all edges
+    #[must_use]
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// Gets a node by its index (the same index used by GPU node buffers)
+    #[must_use]
+    pub fn node_at_index(&self, idx: usize) -> Option<&Node> {
+        self.nodes.get(idx)
+    }
+
+    /// Returns the index of a node by ID
+    #[must_use]
+    pub fn index_of(&self, id: &str) -> Option<usize> {
+        self.id_to_idx.get(id).copied()
+    }
 }
 
 #[cfg(test)]
@@ -120,6 +179,7 @@ mod tests {
             sources: vec![],
             created: None,
             updated: None,
+            status: None,
         }
     }
 
@@ -275,8 +335,7 @@ mod tests {
         }
 
         // Build adjacency - should complete quickly
-        use std::time::Instant;
-        let start = Instant::now();
+        let start = std::time::Instant::now();
         store.build_adjacency();
         let elapsed = start.elapsed();
 
@@ -309,9 +368,79 @@ mod tests {
         assert_eq!(store.get_node("same01"), Some(&node2));
     }
 
+    #[test]
+    fn test_graph_store_index_mapping() {
+        let mut store = GraphStore::new();
+        store.add_node(create_test_node("a"));
+        store.add_node(create_test_node("b"));
+
+        assert_eq!(store.index_of("b"), Some(1));
+        assert_eq!(store.node_at_index(1).map(|n| n.id.as_str()), Some("b"));
+        assert_eq!(store.index_of("missing"), None);
+        assert_eq!(store.node_at_index(2), None);
+    }
+
     #[test]
     fn test_graph_store_default_trait() {
-        let store: GraphStore = Default::default();
+        let store = GraphStore::default();
         assert_eq!(store.node_count(), 0);
     }
+
+    #[test]
+    fn test_load_repository_builds_adjacency() {
+        let root = std::env::temp_dir().join("truthmines_test_repository");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("nodes")).unwrap();
+        std::fs::create_dir_all(root.join("edges")).unwrap();
+        for id in ["aaa001", "bbb002"] {
+            let json = serde_json::to_string(&create_test_node(id)).unwrap();
+            std::fs::write(root.join("nodes").join(format!("{id}.json")), json).unwrap();
+        }
+        std::fs::write(
+            root.join("edges/supports.jsonl"),
+            r#"{"f":"aaa001","t":"bbb002","relation":"supports","domain":"philosophy"}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("edges/extra.toon"),
+            "nodes[1]{id,type,domain,title}:\nccc003,concept,philosophy,Third\n\n\
+             defines[1]{f,t}:\nccc003,aaa001\n",
+        )
+        .unwrap();
+
+        let store = GraphStore::load_repository(&root).unwrap();
+
+        assert_eq!(store.node_count(), 3);
+        assert_eq!(store.edge_count(), 2);
+        let aaa = store.index_of("aaa001").unwrap();
+        assert_eq!(store.out_edges[aaa], [store.index_of("bbb002").unwrap()]);
+        assert_eq!(store.in_edges[aaa], [store.index_of("ccc003").unwrap()]);
+
+        // Relation must match the file name
+        std::fs::write(
+            root.join("edges/attacks.jsonl"),
+            r#"{"f":"bbb002","t":"aaa001","relation":"supports","domain":"philosophy"}"#,
+        )
+        .unwrap();
+        let err = GraphStore::load_repository(&root).err().unwrap();
+        assert_eq!(err.kind(), "integrity");
+        assert_eq!(err.location().path, Some(root.join("edges/attacks.jsonl")));
+        assert_eq!(err.location().line, Some(1));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_load_repository_project_root() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+
+        let store = GraphStore::load_repository(root).unwrap();
+
+        assert!(store.node_count() > 0);
+        assert!(store.edge_count() > 0);
+        let connected = (0..store.node_count())
+            .filter(|&idx| !store.out_edges[idx].is_empty() || !store.in_edges[idx].is_empty())
+            .count();
+        assert!(connected > 0);
+    }
 }
@@ -0,0 +1,461 @@
+//! Web app index artefacts
+//!
+//! Builds `dist/manifest.json` and `dist/graph.json` from a `GraphStore`,
+//! replacing `scripts/build_index.py` so the web app and the engine share one
+//! view of the graph. Both files keep the shape the Python script produced;
+//! `graph.json` entries additionally carry the precomputed depth, truth mine
+//! position and analysis metrics so the web app can skip computing them.
+
+use crate::analysis::clustering::cluster_by_community;
+use crate::analysis::compute_all_load_bearing;
+use crate::error::{Error, Result};
+use crate::graph::GraphStore;
+use crate::layout::{depth::compute_depths, truth_mine::compute_truth_mine_layout};
+use crate::parsers::loader::{load_repository_lenient, NODES_DIR};
+use crate::style::StyleConfig;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Manifest format version
+pub const MANIFEST_VERSION: &str = "1.0.0";
+
+/// File name of the manifest inside the output directory
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// File name of the graph summary inside the output directory
+pub const GRAPH_FILE: &str = "graph.json";
+
+/// Contents of `manifest.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Manifest {
+    /// Manifest format version
+    pub version: String,
+    /// RFC 3339 build timestamp
+    pub generated: String,
+    /// Where each node lives, by node ID
+    pub nodes: BTreeMap<String, ManifestEntry>,
+    /// Aggregate counts
+    pub stats: ManifestStats,
+}
+
+/// Manifest record for one node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ManifestEntry {
+    /// Node file path relative to the repository root
+    pub file: String,
+    /// Node domain
+    pub domain: String,
+    /// Node type
+    #[serde(rename = "type")]
+    pub r#type: String,
+}
+
+/// Aggregate counts in the manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ManifestStats {
+    /// Number of nodes
+    pub total_nodes: usize,
+    /// Number of edges
+    pub total_edges: usize,
+    /// Node count per domain
+    pub by_domain: BTreeMap<String, usize>,
+    /// Node count per type
+    pub by_type: BTreeMap<String, usize>,
+    /// Edge count per relation
+    pub by_relation: BTreeMap<String, usize>,
+}
+
+/// One `graph.json` entry
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphEntry {
+    /// Node ID
+    pub id: String,
+    /// Node type
+    #[serde(rename = "type")]
+    pub r#type: String,
+    /// Node domain
+    pub domain: String,
+    /// Node title
+    pub title: String,
+    /// Epistemic depth (0 = foundation)
+    pub depth: u32,
+    /// Truth mine layout position
+    pub position: [f32; 3],
+    /// Analysis metrics
+    pub metrics: NodeMetrics,
+}
+
+/// Precomputed analysis metrics for one node
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeMetrics {
+    /// Fraction of the graph orphaned if the node were removed
+    pub load_bearing: f32,
+    /// Number of incoming edges
+    pub in_degree: usize,
+    /// Number of outgoing edges
+    pub out_degree: usize,
+    /// Label of the node's community cluster
+    pub community: String,
+}
+
+/// Both index artefacts
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Index {
+    /// `manifest.json`
+    pub manifest: Manifest,
+    /// `graph.json`
+    pub graph: Vec<GraphEntry>,
+}
+
+/// Settings for [`build_index`]
+#[derive(Debug, Clone)]
+pub struct IndexOptions {
+    /// Timestamp to record; `None` uses the current time
+    pub generated: Option<String>,
+    /// Y-axis spacing between depth layers of the layout
+    pub depth_spacing: f32,
+}
+
+impl Default for IndexOptions {
+    fn default() -> Self {
+        Self {
+            generated: None,
+            depth_spacing: StyleConfig::default().depth_spacing(),
+        }
+    }
+}
+
+/// Builds the manifest and graph summary for `graph`
+///
+/// # Arguments
+///
+/// * `graph` - Graph store with adjacency built
+/// * `node_files` - Node file path relative to the repository root, by ID;
+///   nodes without an entry are assumed to live at `nodes/<id>.json`
+/// * `options` - Timestamp and layout settings
+#[must_use]
+#[allow(clippy::implicit_hasher)]
+pub fn build_index(
+    graph: &GraphStore,
+    node_files: &HashMap<String, String>,
+    options: &IndexOptions,
+) -> Index {
+    let mut nodes = BTreeMap::new();
+    for node in graph.nodes() {
+        let file = node_files
+            .get(&node.id)
+            .cloned()
+            .unwrap_or_else(|| format!("{NODES_DIR}/{}.json", node.id));
+        nodes.insert(
+            node.id.clone(),
+            ManifestEntry {
+                file,
+                domain: node.domain.clone(),
+                r#type: node.r#type.clone(),
+            },
+        );
+    }
+
+    let manifest = Manifest {
+        version: MANIFEST_VERSION.to_string(),
+        generated: options
+            .generated
+            .clone()
+            .unwrap_or_else(|| format_timestamp(SystemTime::now())),
+        nodes,
+        stats: manifest_stats(graph),
+    };
+
+    let depths = compute_depths(graph);
+    let positions = compute_truth_mine_layout(graph, &depths, options.depth_spacing);
+    let communities = cluster_by_community(graph);
+    let load_bearing = compute_all_load_bearing(graph);
+    let summary = graph
+        .nodes()
+        .iter()
+        .enumerate()
+        .map(|(idx, node)| GraphEntry {
+            id: node.id.clone(),
+            r#type: node.r#type.clone(),
+            domain: node.domain.clone(),
+            title: node.title.clone(),
+            depth: depths.get(&node.id).copied().unwrap_or(0),
+            position: positions.get(&node.id).copied().unwrap_or_default(),
+            metrics: NodeMetrics {
+                load_bearing: load_bearing.get(&node.id).copied().unwrap_or(0.0),
+                in_degree: graph.in_edges[idx].len(),
+                out_degree: graph.out_edges[idx].len(),
+                community: communities.labels[communities.assignment[idx]].clone(),
+            },
+        })
+        .collect();
+
+    Index {
+        manifest,
+        graph: summary,
+    }
+}
+
+/// Counts nodes per domain and type, and edges per relation
+#[must_use]
+pub fn manifest_stats(graph: &GraphStore) -> ManifestStats {
+    let mut by_domain = BTreeMap::new();
+    let mut by_type = BTreeMap::new();
+    let mut by_relation = BTreeMap::new();
+
+    for node in graph.nodes() {
+        *by_domain.entry(node.domain.clone()).or_default() += 1;
+        *by_type.entry(node.r#type.clone()).or_default() += 1;
+    }
+    for edge in graph.edges() {
+        *by_relation.entry(edge.relation.clone()).or_default() += 1;
+    }
+
+    ManifestStats {
+        total_nodes: graph.node_count(),
+        total_edges: graph.edge_count(),
+        by_domain,
+        by_type,
+        by_relation,
+    }
+}
+
+/// Loads the repository at `root` leniently and builds its index
+///
+/// # Returns
+///
+/// The index of everything that loaded, plus the load diagnostics
+#[must_use]
+pub fn build_repository_index(root: &Path, options: &IndexOptions) -> (Index, Vec<Error>) {
+    let mut report = load_repository_lenient(root);
+    let node_files = report
+        .node_files()
+        .into_iter()
+        .map(|(id, path)| (id, relative_path(&path, root)))
+        .collect();
+    let diagnostics = std::mem::take(&mut report.diagnostics);
+    let graph = report.into_graph_store();
+
+    (build_index(&graph, &node_files, options), diagnostics)
+}
+
+/// Writes `manifest.json` and `graph.json` into `dist_dir`, creating it
+///
+/// # Returns
+///
+/// Paths of the written manifest and graph files
+///
+/// # Errors
+///
+/// Returns an `Io` error if the directory or files cannot be written
+pub fn write_index(index: &Index, dist_dir: &Path) -> Result<(PathBuf, PathBuf)> {
+    fs::create_dir_all(dist_dir)
+        .map_err(|e| Error::io(format!("Failed to create directory: {e}")).with_path(dist_dir))?;
+
+    let manifest_path = dist_dir.join(MANIFEST_FILE);
+    write_json(&manifest_path, &index.manifest)?;
+    let graph_path = dist_dir.join(GRAPH_FILE);
+    write_json(&graph_path, &index.graph)?;
+
+    Ok((manifest_path, graph_path))
+}
+
+fn write_json(path: &Path, value: &impl Serialize) -> Result<()> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| Error::from_json(&e))?;
+    fs::write(path, json + "\n")
+        .map_err(|e| Error::io(format!("Failed to write file: {e}")).with_path(path))
+}
+
+/// `path` relative to `root`, with `/` separators
+fn relative_path(path: &Path, root: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Formats a time as an RFC 3339 UTC timestamp (`2025-01-15T10:00:00Z`)
+fn format_timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Edge, Node};
+    use std::time::Duration;
+
+    fn node(id: &str, r#type: &str, domain: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            r#type: r#type.to_string(),
+            domain: domain.to_string(),
+            title: format!("Node {id}"),
+            content: None,
+            formal: None,
+            tags: vec![],
+            metadata: serde_json::Value::Null,
+            sources: vec![],
+            created: None,
+            updated: None,
+            status: None,
+        }
+    }
+
+    fn edge(from: &str, to: &str, relation: &str) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            relation: relation.to_string(),
+            domain: "philosophy".to_string(),
+            weight: None,
+            metadata: None,
+        }
+    }
+
+    fn sample_graph() -> GraphStore {
+        let mut graph = GraphStore::new();
+        graph.add_node(node("ax0001", "axiom", "mathematics"));
+        graph.add_node(node("th0001", "theorem", "mathematics"));
+        graph.add_node(node("pr0001", "proposition", "philosophy"));
+        graph.add_edge(edge("ax0001", "th0001", "proves"));
+        graph.add_edge(edge("th0001", "pr0001", "supports"));
+        graph.build_adjacency();
+        graph
+    }
+
+    fn options() -> IndexOptions {
+        IndexOptions {
+            generated: Some("2025-01-15T10:00:00Z".to_string()),
+            ..IndexOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_manifest_stats_and_files() {
+        let files = HashMap::from([("th0001".to_string(), "nodes/math/th0001.json".to_string())]);
+
+        let index = build_index(&sample_graph(), &files, &options());
+        let manifest = &index.manifest;
+
+        assert_eq!(manifest.version, MANIFEST_VERSION);
+        assert_eq!(manifest.generated, "2025-01-15T10:00:00Z");
+        assert_eq!(manifest.stats.total_nodes, 3);
+        assert_eq!(manifest.stats.total_edges, 2);
+        assert_eq!(manifest.stats.by_domain["mathematics"], 2);
+        assert_eq!(manifest.stats.by_type["axiom"], 1);
+        assert_eq!(manifest.stats.by_relation["proves"], 1);
+        assert_eq!(manifest.nodes["th0001"].file, "nodes/math/th0001.json");
+        assert_eq!(manifest.nodes["ax0001"].file, "nodes/ax0001.json");
+    }
+
+    #[test]
+    fn test_graph_entries_carry_depth_position_and_metrics() {
+        let index = build_index(&sample_graph(), &HashMap::new(), &options());
+        let entries = &index.graph;
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].id, "ax0001");
+        assert_eq!(
+            entries.iter().map(|entry| entry.depth).collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+        assert_eq!(entries[2].position[1], 2.0 * options().depth_spacing);
+        assert_eq!(entries[0].metrics.out_degree, 1);
+        assert_eq!(entries[0].metrics.in_degree, 0);
+        assert!(entries[0].metrics.load_bearing > 0.0);
+
+        let json = serde_json::to_value(entries).unwrap();
+        assert_eq!(json[0]["type"], "axiom");
+        assert!(json[0]["metrics"]["community"].is_string());
+    }
+
+    #[test]
+    fn test_build_index_scales_to_long_chains() {
+        let count = 20_000;
+        let mut graph = GraphStore::new();
+        for i in 0..count {
+            graph.add_node(node(&format!("n{i}"), "proposition", "philosophy"));
+        }
+        for i in 1..count {
+            graph.add_edge(edge(&format!("n{}", i - 1), &format!("n{i}"), "supports"));
+        }
+        graph.build_adjacency();
+
+        let start = std::time::Instant::now();
+        let index = build_index(&graph, &HashMap::new(), &options());
+        let elapsed = start.elapsed();
+
+        assert_eq!(index.graph.len(), count);
+        assert!(index.graph[0].metrics.load_bearing > 0.99);
+        assert!(elapsed < Duration::from_secs(10), "took {elapsed:?}");
+    }
+
+    #[test]
+    fn test_write_index_files() {
+        let dist = std::env::temp_dir().join("truthmines_test_dist");
+        let _ = fs::remove_dir_all(&dist);
+        let index = build_index(&sample_graph(), &HashMap::new(), &options());
+
+        let (manifest_path, graph_path) = write_index(&index, &dist).unwrap();
+
+        let manifest: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(manifest_path).unwrap()).unwrap();
+        assert_eq!(manifest["stats"]["total_nodes"], 3);
+        assert_eq!(manifest["nodes"]["pr0001"]["type"], "proposition");
+        let graph: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(graph_path).unwrap()).unwrap();
+        assert_eq!(graph.as_array().unwrap().len(), 3);
+
+        fs::remove_dir_all(&dist).unwrap();
+    }
+
+    #[test]
+    fn test_build_repository_index_project_data() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+
+        let (index, diagnostics) = build_repository_index(root, &options());
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        assert_eq!(index.graph.len(), index.manifest.stats.total_nodes);
+        for entry in index.manifest.nodes.values() {
+            assert!(entry.file.starts_with("nodes/"), "{}", entry.file);
+            assert!(root.join(&entry.file).exists(), "{}", entry.file);
+        }
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+        let time = UNIX_EPOCH + Duration::from_secs(1_736_935_205);
+        assert_eq!(format_timestamp(time), "2025-01-15T10:00:05Z");
+        let leap = UNIX_EPOCH + Duration::from_secs(951_825_599);
+        assert_eq!(format_timestamp(leap), "2000-02-29T11:59:59Z");
+    }
+}
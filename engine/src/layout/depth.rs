@@ -22,56 +22,53 @@ pub fn compute_depths(graph: &GraphStore) -> HashMap<String, u32> {
     let mut depths = HashMap::new();
     let mut in_degree = vec![0_usize; graph.nodes.len()];
 
-    // Count incoming edges for each node, but only for epistemic relations
+    // Count incoming edges for each node, but only for epistemic relations,
+    // and collect each node's outgoing epistemic targets
     let epistemic_relations = ["supports", "proves", "entails"];
+    let mut outgoing = vec![Vec::new(); graph.nodes.len()];
 
     for edge in &graph.edges {
         if epistemic_relations.contains(&edge.relation.as_str()) {
             if let Some(&to_idx) = graph.id_to_idx.get(&edge.to) {
                 in_degree[to_idx] += 1;
+                if let Some(&from_idx) = graph.id_to_idx.get(&edge.from) {
+                    outgoing[from_idx].push(to_idx);
+                }
             }
         }
     }
 
     // Find foundation nodes (in-degree 0)
     let mut queue = VecDeque::new();
+    let mut layer = vec![None; graph.nodes.len()];
     for (idx, &degree) in in_degree.iter().enumerate() {
         if degree == 0 {
-            let node_id = &graph.nodes[idx].id;
-            depths.insert(node_id.clone(), 0);
+            layer[idx] = Some(0);
             queue.push_back(idx);
         }
     }
 
-    // BFS topological layering
+    // BFS topological layering; a node is queued once, when its last
+    // incoming epistemic edge has been seen
     while let Some(current_idx) = queue.pop_front() {
-        let current_depth = depths[&graph.nodes[current_idx].id];
+        let new_depth = layer[current_idx].unwrap_or(0) + 1;
 
-        // Process outgoing epistemic edges
-        for edge in &graph.edges {
-            if epistemic_relations.contains(&edge.relation.as_str()) {
-                if let Some(&from_idx) = graph.id_to_idx.get(&edge.from) {
-                    if from_idx == current_idx {
-                        if let Some(&to_idx) = graph.id_to_idx.get(&edge.to) {
-                            // Update depth for target node
-                            let new_depth = current_depth + 1;
-                            depths
-                                .entry(graph.nodes[to_idx].id.clone())
-                                .and_modify(|d| *d = (*d).max(new_depth))
-                                .or_insert(new_depth);
-
-                            // Decrement in-degree and add to queue if it reaches 0
-                            in_degree[to_idx] = in_degree[to_idx].saturating_sub(1);
-                            if in_degree[to_idx] == 0 && !queue.iter().any(|&idx| idx == to_idx) {
-                                queue.push_back(to_idx);
-                            }
-                        }
-                    }
-                }
+        for &to_idx in &outgoing[current_idx] {
+            layer[to_idx] = Some(layer[to_idx].map_or(new_depth, |d: u32| d.max(new_depth)));
+
+            in_degree[to_idx] -= 1;
+            if in_degree[to_idx] == 0 {
+                queue.push_back(to_idx);
             }
         }
     }
 
+    for (node, depth) in graph.nodes.iter().zip(layer) {
+        if let Some(depth) = depth {
+            depths.insert(node.id.clone(), depth);
+        }
+    }
+
     // Handle any remaining nodes (in cycles) - assign to deepest reachable
     for node in &graph.nodes {
         if !depths.contains_key(&node.id) {
@@ -101,6 +98,7 @@ mod tests {
             sources: vec![],
             created: None,
             updated: None,
+            status: None,
         }
     }
 
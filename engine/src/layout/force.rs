@@ -36,7 +36,7 @@ pub fn compute_layout_2d(graph: &GraphStore, iterations: usize) -> HashMap<Strin
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::graph::{Edge, GraphStore, Node};
+    use crate::graph::{GraphStore, Node};
 
     fn create_test_node(id: &str) -> Node {
         Node {
@@ -51,6 +51,7 @@ mod tests {
             sources: vec![],
             created: None,
             updated: None,
+            status: None,
         }
     }
 
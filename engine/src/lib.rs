@@ -10,21 +10,41 @@
 //! - `layout`: Layout algorithms (force-directed, depth-based, truth mine)
 //! - `gpu`: GPU-friendly buffer types (`GpuNode`, `GpuEdge`)
 //! - `style`: Visual style mapping
-//! - `parsers`: Data parsers (JSON, TOON)
+//! - `parsers`: Data parsers (JSON, TOON) and write-back of refactorings
 //! - `analysis`: Graph analysis algorithms (Gemini 3 Pro additions)
+//! - `error`: Crate-wide error type with file/line/column locations
+//! - `validate`: Schema validation of nodes and edges
+//! - `lint`: Configurable epistemic rules over a loaded graph
+//! - `format`: Canonical formatting of node JSON and edge JSONL files
+//! - `diff`: Field-level and derived-impact diff between two graph versions
+//! - `merge`: Semantic three-way merge of graph versions and repository files
+//! - `export`: `GraphML`, GEXF and DOT export of a graph or subgraph
+//! - `index`: `dist/manifest.json` and `dist/graph.json` generation
 //! - `wasm`: WebAssembly bindings (when compiled to WASM)
 
+// Exact float comparisons are intentional in unit tests
+#![cfg_attr(test, allow(clippy::float_cmp))]
+
 pub mod graph;
 pub mod layout;
 pub mod gpu;
 pub mod style;
 pub mod parsers;
 pub mod analysis;
+pub mod error;
+pub mod validate;
+pub mod lint;
+pub mod format;
+pub mod diff;
+pub mod merge;
+pub mod export;
+pub mod index;
 
 #[cfg(target_arch = "wasm32")]
 pub mod wasm;
 
 // Re-export main types (more will be added as implemented)
+pub use error::Error;
 pub use graph::{Node, Edge, GraphStore};
 
 #[cfg(test)]
@@ -0,0 +1,511 @@
+//! Epistemic lint rules over a loaded graph
+//!
+//! Schema validation ([`crate::validate`]) checks each node and edge on its
+//! own; lint rules check how they fit together, e.g. that a `proves` edge
+//! starts at something that can prove. Every rule in [`RULES`] has a name and
+//! a default [`Severity`], which a TOML file can override per rule:
+//!
+//! ```toml
+//! [rules]
+//! cross-domain-bridge = "error"
+//! refuted-needs-refutation = "off"
+//! ```
+
+use crate::error::{Error, Result};
+use crate::graph::{Edge, GraphStore, Node};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Node types that may be the source of a `proves` edge
+const PROVING_TYPES: &[&str] = &["axiom", "theorem", "lemma"];
+
+/// How seriously a lint rule's findings are reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Rule is disabled
+    Off,
+    /// Worth knowing, nothing to fix
+    Info,
+    /// Probably wrong
+    Warning,
+    /// Must be fixed
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Off => "off",
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+/// A named lint rule
+#[derive(Debug, Clone, Copy)]
+pub struct Rule {
+    /// Name used in the config file and in diagnostics
+    pub name: &'static str,
+    /// One-line summary of what the rule enforces
+    pub description: &'static str,
+    /// Severity when the config does not mention the rule
+    pub default_severity: Severity,
+    check: fn(&GraphStore) -> Vec<Finding>,
+}
+
+/// All lint rules, in the order they run
+pub const RULES: &[Rule] = &[
+    Rule {
+        name: "cross-domain-bridge",
+        description: "Edges between nodes of different domains use a `bridge:` domain",
+        default_severity: Severity::Error,
+        check: check_cross_domain_bridge,
+    },
+    Rule {
+        name: "proves-source",
+        description: "`proves` edges start at an axiom, theorem or lemma",
+        default_severity: Severity::Warning,
+        check: check_proves_source,
+    },
+    Rule {
+        name: "axiom-not-supported",
+        description: "Axioms have no incoming `supports` edges",
+        default_severity: Severity::Warning,
+        check: check_axiom_not_supported,
+    },
+    Rule {
+        name: "contested-needs-attack",
+        description: "Nodes with `metadata.contested = true` have an incoming `attacks` edge",
+        default_severity: Severity::Warning,
+        check: check_contested_needs_attack,
+    },
+    Rule {
+        name: "refuted-needs-refutation",
+        description: "Nodes with status `refuted` have an incoming `refutes` edge",
+        default_severity: Severity::Warning,
+        check: check_refuted_needs_refutation,
+    },
+];
+
+/// A rule broken by a node or edge
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)] // Edge weight is f32
+pub struct LintDiagnostic {
+    /// Name of the rule that fired
+    pub rule: &'static str,
+    /// Configured severity of the rule
+    pub severity: Severity,
+    /// Node the finding is about
+    pub node: String,
+    /// Edge the finding is about, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edge: Option<Edge>,
+    /// What is wrong
+    pub message: String,
+}
+
+impl fmt::Display for LintDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}] {}: {}",
+            self.severity, self.rule, self.node, self.message
+        )
+    }
+}
+
+/// What a rule reports, before its severity is known
+struct Finding {
+    node: String,
+    edge: Option<Edge>,
+    message: String,
+}
+
+impl Finding {
+    fn node(node: &Node, message: String) -> Self {
+        Self {
+            node: node.id.clone(),
+            edge: None,
+            message,
+        }
+    }
+
+    fn edge(node: &str, edge: &Edge, message: String) -> Self {
+        Self {
+            node: node.to_string(),
+            edge: Some(edge.clone()),
+            message,
+        }
+    }
+}
+
+/// Per-rule severities loaded from TOML
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintConfig {
+    #[serde(default)]
+    rules: HashMap<String, Severity>,
+}
+
+impl LintConfig {
+    /// Loads lint config from TOML string
+    ///
+    /// An empty string gives every rule its default severity.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if TOML is malformed, a severity is not one of `off`,
+    /// `info`, `warning` or `error`, or a rule name is unknown
+    pub fn from_toml(toml_str: &str) -> Result<Self> {
+        let config: Self = toml::from_str(toml_str).map_err(|e| Error::from_toml(&e, toml_str))?;
+        if let Some(name) = config
+            .rules
+            .keys()
+            .find(|name| !RULES.iter().any(|rule| rule.name == name.as_str()))
+        {
+            return Err(Error::toml(format!("Unknown lint rule: {name}")));
+        }
+        Ok(config)
+    }
+
+    /// Returns the severity configured for `rule`, or its default
+    #[must_use]
+    pub fn severity(&self, rule: &Rule) -> Severity {
+        self.rules
+            .get(rule.name)
+            .copied()
+            .unwrap_or(rule.default_severity)
+    }
+}
+
+/// Runs every enabled rule over the graph
+///
+/// Diagnostics are grouped by rule, in [`RULES`] order. Edges whose
+/// endpoints are not loaded are skipped; [`crate::validate`] reports those.
+#[must_use]
+pub fn lint(graph: &GraphStore, config: &LintConfig) -> Vec<LintDiagnostic> {
+    let mut diagnostics = Vec::new();
+    for rule in RULES {
+        let severity = config.severity(rule);
+        if severity == Severity::Off {
+            continue;
+        }
+        diagnostics.extend(
+            (rule.check)(graph)
+                .into_iter()
+                .map(|finding| LintDiagnostic {
+                    rule: rule.name,
+                    severity,
+                    node: finding.node,
+                    edge: finding.edge,
+                    message: finding.message,
+                }),
+        );
+    }
+    diagnostics
+}
+
+/// Returns the edges whose endpoints are both loaded, with those endpoints
+fn resolved_edges(graph: &GraphStore) -> impl Iterator<Item = (&Edge, &Node, &Node)> {
+    graph.edges().iter().filter_map(|edge| {
+        let from = graph.get_node(&edge.from)?;
+        let to = graph.get_node(&edge.to)?;
+        Some((edge, from, to))
+    })
+}
+
+/// Returns the IDs of nodes with at least one incoming `relation` edge
+fn targets_of<'a>(graph: &'a GraphStore, relation: &str) -> HashSet<&'a str> {
+    graph
+        .edges()
+        .iter()
+        .filter(|edge| edge.relation == relation)
+        .map(|edge| edge.to.as_str())
+        .collect()
+}
+
+fn check_cross_domain_bridge(graph: &GraphStore) -> Vec<Finding> {
+    resolved_edges(graph)
+        .filter(|(edge, from, to)| {
+            from.domain != to.domain && !edge.domain.starts_with("bridge:")
+        })
+        .map(|(edge, from, to)| {
+            Finding::edge(
+                &from.id,
+                edge,
+                format!(
+                    "`{}` edge to {} crosses from {} to {} but has domain '{}' instead of a `bridge:` domain",
+                    edge.relation, to.id, from.domain, to.domain, edge.domain
+                ),
+            )
+        })
+        .collect()
+}
+
+fn check_proves_source(graph: &GraphStore) -> Vec<Finding> {
+    resolved_edges(graph)
+        .filter(|(edge, from, _)| {
+            edge.relation == "proves" && !PROVING_TYPES.contains(&from.r#type.as_str())
+        })
+        .map(|(edge, from, to)| {
+            Finding::edge(
+                &from.id,
+                edge,
+                format!(
+                    "`proves` edge to {} starts at a {}, not an axiom, theorem or lemma",
+                    to.id, from.r#type
+                ),
+            )
+        })
+        .collect()
+}
+
+fn check_axiom_not_supported(graph: &GraphStore) -> Vec<Finding> {
+    resolved_edges(graph)
+        .filter(|(edge, _, to)| edge.relation == "supports" && to.r#type == "axiom")
+        .map(|(edge, from, to)| {
+            Finding::edge(
+                &to.id,
+                edge,
+                format!(
+                    "axiom is supported by {}; axioms are assumed, not supported",
+                    from.id
+                ),
+            )
+        })
+        .collect()
+}
+
+fn check_contested_needs_attack(graph: &GraphStore) -> Vec<Finding> {
+    let attacked = targets_of(graph, "attacks");
+    graph
+        .nodes()
+        .iter()
+        .filter(|node| node.metadata.get("contested") == Some(&serde_json::Value::Bool(true)))
+        .filter(|node| !attacked.contains(node.id.as_str()))
+        .map(|node| {
+            Finding::node(
+                node,
+                "marked contested but has no incoming `attacks` edge".to_string(),
+            )
+        })
+        .collect()
+}
+
+fn check_refuted_needs_refutation(graph: &GraphStore) -> Vec<Finding> {
+    let refuted = targets_of(graph, "refutes");
+    graph
+        .nodes()
+        .iter()
+        .filter(|node| node.status.as_deref() == Some("refuted"))
+        .filter(|node| !refuted.contains(node.id.as_str()))
+        .map(|node| {
+            Finding::node(
+                node,
+                "has status refuted but no incoming `refutes` edge".to_string(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn node(id: &str, node_type: &str, domain: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            r#type: node_type.to_string(),
+            domain: domain.to_string(),
+            title: format!("Node {id}"),
+            content: None,
+            formal: None,
+            tags: vec![],
+            metadata: serde_json::Value::Null,
+            sources: vec![],
+            created: None,
+            updated: None,
+            status: None,
+        }
+    }
+
+    fn edge(from: &str, to: &str, relation: &str, domain: &str) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            relation: relation.to_string(),
+            domain: domain.to_string(),
+            weight: None,
+            metadata: None,
+        }
+    }
+
+    fn graph(nodes: Vec<Node>, edges: Vec<Edge>) -> GraphStore {
+        let mut graph = GraphStore::new();
+        for node in nodes {
+            graph.add_node(node);
+        }
+        for edge in edges {
+            graph.add_edge(edge);
+        }
+        graph
+    }
+
+    fn rules_fired(diagnostics: &[LintDiagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|d| d.rule).collect()
+    }
+
+    #[test]
+    fn test_rule_names_are_unique() {
+        let names: HashSet<_> = RULES.iter().map(|rule| rule.name).collect();
+        assert_eq!(names.len(), RULES.len());
+    }
+
+    #[test]
+    fn test_cross_domain_edge_needs_bridge_domain() {
+        let graph = graph(
+            vec![
+                node("phil01", "concept", "philosophy"),
+                node("math01", "theorem", "mathematics"),
+            ],
+            vec![
+                edge("phil01", "math01", "formalizes", "philosophy"),
+                edge("phil01", "math01", "explicates", "bridge:phil→math"),
+            ],
+        );
+
+        let diagnostics = lint(&graph, &LintConfig::default());
+        assert_eq!(rules_fired(&diagnostics), ["cross-domain-bridge"]);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].node, "phil01");
+        assert_eq!(diagnostics[0].edge.as_ref().unwrap().relation, "formalizes");
+    }
+
+    #[test]
+    fn test_proves_must_start_at_proving_type() {
+        let graph = graph(
+            vec![
+                node("lem001", "lemma", "mathematics"),
+                node("con001", "concept", "mathematics"),
+                node("thm001", "theorem", "mathematics"),
+            ],
+            vec![
+                edge("lem001", "thm001", "proves", "mathematics"),
+                edge("con001", "thm001", "proves", "mathematics"),
+            ],
+        );
+
+        let diagnostics = lint(&graph, &LintConfig::default());
+        assert_eq!(rules_fired(&diagnostics), ["proves-source"]);
+        assert_eq!(diagnostics[0].node, "con001");
+    }
+
+    #[test]
+    fn test_axiom_with_incoming_supports() {
+        let graph = graph(
+            vec![
+                node("ax0001", "axiom", "mathematics"),
+                node("obs001", "observation", "mathematics"),
+            ],
+            vec![
+                edge("obs001", "ax0001", "supports", "mathematics"),
+                edge("ax0001", "obs001", "supports", "mathematics"),
+            ],
+        );
+
+        let diagnostics = lint(&graph, &LintConfig::default());
+        assert_eq!(rules_fired(&diagnostics), ["axiom-not-supported"]);
+        assert_eq!(diagnostics[0].node, "ax0001");
+    }
+
+    #[test]
+    fn test_contested_node_needs_attack() {
+        let mut contested = node("con001", "proposition", "philosophy");
+        contested.metadata = json!({"contested": true});
+        let mut attacked = node("con002", "proposition", "philosophy");
+        attacked.metadata = json!({"contested": true});
+        let mut settled = node("set001", "proposition", "philosophy");
+        settled.metadata = json!({"contested": false});
+
+        let graph = graph(
+            vec![contested, attacked, settled],
+            vec![edge("set001", "con002", "attacks", "philosophy")],
+        );
+
+        let diagnostics = lint(&graph, &LintConfig::default());
+        assert_eq!(rules_fired(&diagnostics), ["contested-needs-attack"]);
+        assert_eq!(diagnostics[0].node, "con001");
+        assert!(diagnostics[0].edge.is_none());
+    }
+
+    #[test]
+    fn test_refuted_node_needs_refutation() {
+        let mut refuted = node("phl001", "theory", "physics");
+        refuted.status = Some("refuted".to_string());
+        let mut answered = node("phl002", "theory", "physics");
+        answered.status = Some("refuted".to_string());
+
+        let graph = graph(
+            vec![refuted, answered, node("exp001", "experiment", "physics")],
+            vec![edge("exp001", "phl002", "refutes", "physics")],
+        );
+
+        let diagnostics = lint(&graph, &LintConfig::default());
+        assert_eq!(rules_fired(&diagnostics), ["refuted-needs-refutation"]);
+        assert_eq!(diagnostics[0].node, "phl001");
+    }
+
+    #[test]
+    fn test_config_overrides_severity() {
+        let graph = graph(
+            vec![
+                node("con001", "concept", "mathematics"),
+                node("thm001", "theorem", "mathematics"),
+            ],
+            vec![edge("con001", "thm001", "proves", "mathematics")],
+        );
+
+        let config = LintConfig::from_toml("[rules]\nproves-source = \"error\"\n").unwrap();
+        assert_eq!(lint(&graph, &config)[0].severity, Severity::Error);
+
+        let config = LintConfig::from_toml("[rules]\nproves-source = \"off\"\n").unwrap();
+        assert!(lint(&graph, &config).is_empty());
+    }
+
+    #[test]
+    fn test_config_empty_uses_defaults() {
+        let config = LintConfig::from_toml("").unwrap();
+        for rule in RULES {
+            assert_eq!(config.severity(rule), rule.default_severity);
+        }
+    }
+
+    #[test]
+    fn test_config_rejects_unknown_rule() {
+        let err = LintConfig::from_toml("[rules]\nno-such-rule = \"error\"\n").unwrap_err();
+        assert!(err.message().contains("no-such-rule"));
+    }
+
+    #[test]
+    fn test_config_rejects_unknown_severity() {
+        let err = LintConfig::from_toml("[rules]\nproves-source = \"fatal\"\n").unwrap_err();
+        assert_eq!(err.location().line, Some(2));
+    }
+
+    #[test]
+    fn test_diagnostic_display() {
+        let diagnostic = LintDiagnostic {
+            rule: "proves-source",
+            severity: Severity::Warning,
+            node: "con001".to_string(),
+            edge: None,
+            message: "bad".to_string(),
+        };
+        assert_eq!(diagnostic.to_string(), "warning[proves-source] con001: bad");
+    }
+}
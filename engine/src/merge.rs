@@ -0,0 +1,1058 @@
+//! Semantic three-way merge of graph versions
+//!
+//! [`merge`] combines two versions of a graph (ours and theirs) that were
+//! both edited from a common base. Nodes are matched by ID and edges by
+//! `(from, to, relation)`, and each field (metadata key by key) is merged on
+//! its own: a field changed on one side only takes that side's value, so
+//! edits to different fields of the same node never conflict. Nodes and
+//! edges added on either side are kept, and ones deleted on one side and
+//! untouched on the other are deleted.
+//!
+//! A [`Conflict`] is reported when both sides changed the same field to
+//! different values (e.g. an edge weight), one side deleted what the other
+//! changed, or an edge kept by the merge points at a node the merge deleted.
+//! Conflicts still produce a complete graph: ours wins a field conflict, a
+//! changed node or edge wins over its deletion, and a dangling edge is
+//! dropped.
+//!
+//! [`merge_node_files`] and [`merge_edge_files`] apply the same merge to
+//! single repository files, for use as a git merge driver (see the
+//! `truth-mines-merge` binary).
+
+use crate::diff::{edge_fields, node_fields};
+use crate::error::{Error, Result};
+use crate::format::{format_edges_jsonl_as, format_node_json_as};
+use crate::graph::{Edge, GraphStore, Node};
+use crate::parsers::writer::{overlay_edge, overlay_node, EDGE_FIELDS, NODE_FIELDS};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+/// Which side of a merge did something
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    /// The version being merged into
+    Ours,
+    /// The version being merged in
+    Theirs,
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Ours => "ours",
+            Self::Theirs => "theirs",
+        })
+    }
+}
+
+/// A change the merge could not reconcile
+///
+/// Field values are `None` where the field is absent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Conflict {
+    /// Both sides changed a node field to different values
+    NodeField {
+        /// Node ID
+        id: String,
+        /// Field name as in the data files, `metadata.<key>` for metadata
+        field: String,
+        /// Value in the base version
+        base: Option<Value>,
+        /// Value in our version (kept in the merged graph)
+        ours: Option<Value>,
+        /// Value in their version
+        theirs: Option<Value>,
+    },
+    /// Both sides changed an edge field, such as its weight `w`, to
+    /// different values
+    EdgeField {
+        /// Source node ID
+        from: String,
+        /// Target node ID
+        to: String,
+        /// Relation type
+        relation: String,
+        /// Field name as in the data files, `w` for the weight
+        field: String,
+        /// Value in the base version
+        base: Option<Value>,
+        /// Value in our version (kept in the merged graph)
+        ours: Option<Value>,
+        /// Value in their version
+        theirs: Option<Value>,
+    },
+    /// One side deleted a node the other side changed (the node is kept)
+    NodeDeleted {
+        /// Node ID
+        id: String,
+        /// Side that deleted it
+        deleted_by: Side,
+    },
+    /// One side deleted an edge the other side changed (the edge is kept)
+    EdgeDeleted {
+        /// Source node ID
+        from: String,
+        /// Target node ID
+        to: String,
+        /// Relation type
+        relation: String,
+        /// Side that deleted it
+        deleted_by: Side,
+    },
+    /// An edge kept by the merge points at a node the merge deleted (the
+    /// edge is dropped)
+    DanglingEdge {
+        /// Source node ID
+        from: String,
+        /// Target node ID
+        to: String,
+        /// Relation type
+        relation: String,
+        /// The deleted endpoint
+        missing: String,
+    },
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |value: &Option<Value>| {
+            value
+                .as_ref()
+                .map_or_else(|| "(none)".to_string(), Value::to_string)
+        };
+        match self {
+            Self::NodeField {
+                id,
+                field,
+                base,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "node {id}: {field} changed on both sides (base {}, ours {}, theirs {})",
+                value(base),
+                value(ours),
+                value(theirs)
+            ),
+            Self::EdgeField {
+                from,
+                to,
+                relation,
+                field,
+                base,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "edge {from} -[{relation}]-> {to}: {field} changed on both sides (base {}, ours {}, theirs {})",
+                value(base),
+                value(ours),
+                value(theirs)
+            ),
+            Self::NodeDeleted { id, deleted_by } => {
+                write!(f, "node {id}: deleted by {deleted_by} but changed by the other side")
+            }
+            Self::EdgeDeleted {
+                from,
+                to,
+                relation,
+                deleted_by,
+            } => write!(
+                f,
+                "edge {from} -[{relation}]-> {to}: deleted by {deleted_by} but changed by the other side"
+            ),
+            Self::DanglingEdge {
+                from,
+                to,
+                relation,
+                missing,
+            } => write!(
+                f,
+                "edge {from} -[{relation}]-> {to}: node {missing} was deleted, so the edge was dropped"
+            ),
+        }
+    }
+}
+
+/// Merged graph and the conflicts found while merging
+#[derive(Debug, Clone)]
+pub struct MergeResult {
+    /// Merged graph; conflicting fields hold our value
+    pub graph: GraphStore,
+    /// Conflicts, nodes first, in merged order
+    pub conflicts: Vec<Conflict>,
+}
+
+impl MergeResult {
+    /// Returns true if the merge had no conflicts
+    #[must_use]
+    pub const fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Merges `ours` and `theirs`, both derived from `base`
+///
+/// The merged graph lists our nodes and edges in our order, followed by
+/// the ones only they have.
+#[must_use]
+pub fn merge(base: &GraphStore, ours: &GraphStore, theirs: &GraphStore) -> MergeResult {
+    let mut graph = GraphStore::new();
+    let mut conflicts = Vec::new();
+    merge_nodes(base, ours, theirs, &mut graph, &mut conflicts);
+    merge_edges(base, ours, theirs, &mut graph, &mut conflicts);
+    graph.build_adjacency();
+    MergeResult { graph, conflicts }
+}
+
+fn merge_nodes(
+    base: &GraphStore,
+    ours: &GraphStore,
+    theirs: &GraphStore,
+    graph: &mut GraphStore,
+    conflicts: &mut Vec<Conflict>,
+) {
+    let ids = ours.nodes.iter().chain(
+        theirs
+            .nodes
+            .iter()
+            .filter(|node| ours.get_node(&node.id).is_none()),
+    );
+    for node in ids {
+        let id = node.id.as_str();
+        let merged = match (base.get_node(id), ours.get_node(id), theirs.get_node(id)) {
+            (base_node, Some(our_node), Some(their_node)) => {
+                let empty = Map::new();
+                let base_fields = base_node.map(node_fields);
+                let fields = merge_fields(
+                    base_fields.as_ref().unwrap_or(&empty),
+                    &node_fields(our_node),
+                    &node_fields(their_node),
+                    |field, base, ours, theirs| {
+                        conflicts.push(Conflict::NodeField {
+                            id: id.to_string(),
+                            field,
+                            base,
+                            ours,
+                            theirs,
+                        });
+                    },
+                );
+                Some(node_from_fields(id, fields).unwrap_or_else(|| our_node.clone()))
+            }
+            (Some(base_node), Some(kept), None) | (Some(base_node), None, Some(kept)) => {
+                if kept == base_node {
+                    None
+                } else {
+                    let deleted_by = if ours.get_node(id).is_none() {
+                        Side::Ours
+                    } else {
+                        Side::Theirs
+                    };
+                    conflicts.push(Conflict::NodeDeleted {
+                        id: id.to_string(),
+                        deleted_by,
+                    });
+                    Some(kept.clone())
+                }
+            }
+            (None, Some(added), None) | (None, None, Some(added)) => Some(added.clone()),
+            (_, None, None) => None,
+        };
+        if let Some(merged) = merged {
+            graph.add_node(merged);
+        }
+    }
+}
+
+fn merge_edges<'a>(
+    base: &'a GraphStore,
+    ours: &'a GraphStore,
+    theirs: &'a GraphStore,
+    graph: &mut GraphStore,
+    conflicts: &mut Vec<Conflict>,
+) {
+    let (base_edges, our_edges, their_edges) =
+        (keyed_edges(base), keyed_edges(ours), keyed_edges(theirs));
+    let lookup = |edges: &[(EdgeKey, &'a Edge)]| -> HashMap<EdgeKey, &'a Edge> {
+        edges.iter().cloned().collect()
+    };
+    let (base_map, our_map, their_map) = (
+        lookup(&base_edges),
+        lookup(&our_edges),
+        lookup(&their_edges),
+    );
+    let keys = our_edges.iter().map(|(key, _)| key).chain(
+        their_edges
+            .iter()
+            .map(|(key, _)| key)
+            .filter(|key| !our_map.contains_key(*key)),
+    );
+    for key in keys {
+        let merged = match (
+            base_map.get(key).copied(),
+            our_map.get(key).copied(),
+            their_map.get(key).copied(),
+        ) {
+            (base_edge, Some(our_edge), Some(their_edge)) => {
+                let empty = Map::new();
+                let base_fields = base_edge.map(edge_fields);
+                let fields = merge_fields(
+                    base_fields.as_ref().unwrap_or(&empty),
+                    &edge_fields(our_edge),
+                    &edge_fields(their_edge),
+                    |field, base, ours, theirs| {
+                        conflicts.push(Conflict::EdgeField {
+                            from: our_edge.from.clone(),
+                            to: our_edge.to.clone(),
+                            relation: our_edge.relation.clone(),
+                            field,
+                            base,
+                            ours,
+                            theirs,
+                        });
+                    },
+                );
+                Some(edge_from_fields(our_edge, fields))
+            }
+            (Some(base_edge), Some(kept), None) | (Some(base_edge), None, Some(kept)) => {
+                if kept == base_edge {
+                    None
+                } else {
+                    let deleted_by = if our_map.contains_key(key) {
+                        Side::Theirs
+                    } else {
+                        Side::Ours
+                    };
+                    conflicts.push(Conflict::EdgeDeleted {
+                        from: kept.from.clone(),
+                        to: kept.to.clone(),
+                        relation: kept.relation.clone(),
+                        deleted_by,
+                    });
+                    Some(kept.clone())
+                }
+            }
+            (None, Some(added), None) | (None, None, Some(added)) => Some(added.clone()),
+            (_, None, None) => None,
+        };
+        let Some(merged) = merged else {
+            continue;
+        };
+        // Endpoints no version has (e.g. in a lone edge file) are not the merge's doing
+        let deleted = |id: &String| {
+            graph.get_node(id).is_none()
+                && [base, ours, theirs]
+                    .iter()
+                    .any(|version| version.get_node(id).is_some())
+        };
+        if let Some(missing) = [&merged.from, &merged.to]
+            .into_iter()
+            .find(|id| deleted(id))
+        {
+            conflicts.push(Conflict::DanglingEdge {
+                from: merged.from.clone(),
+                to: merged.to.clone(),
+                relation: merged.relation.clone(),
+                missing: missing.clone(),
+            });
+            continue;
+        }
+        graph.add_edge(merged);
+    }
+}
+
+/// Merged file content and the conflicts found
+type MergedFile = (String, Vec<Conflict>);
+
+/// Raw JSON object of the first line for each `(from, to, relation)`
+type RawLines = HashMap<(String, String, String), Map<String, Value>>;
+
+/// Edge identity: endpoints, relation and position among parallel edges
+type EdgeKey = (String, String, String, usize);
+
+fn keyed_edges(graph: &GraphStore) -> Vec<(EdgeKey, &Edge)> {
+    let mut seen: HashMap<(&str, &str, &str), usize> = HashMap::new();
+    graph
+        .edges
+        .iter()
+        .map(|edge| {
+            let count = seen
+                .entry((&edge.from, &edge.to, &edge.relation))
+                .or_default();
+            *count += 1;
+            let key = (
+                edge.from.clone(),
+                edge.to.clone(),
+                edge.relation.clone(),
+                *count,
+            );
+            (key, edge)
+        })
+        .collect()
+}
+
+/// Merges field maps key by key, calling `conflict` where both sides differ
+fn merge_fields(
+    base: &Map<String, Value>,
+    ours: &Map<String, Value>,
+    theirs: &Map<String, Value>,
+    mut conflict: impl FnMut(String, Option<Value>, Option<Value>, Option<Value>),
+) -> Map<String, Value> {
+    let present = |fields: &Map<String, Value>, key: &str| {
+        fields.get(key).filter(|value| !value.is_null()).cloned()
+    };
+    let keys: BTreeSet<&String> = base
+        .keys()
+        .chain(ours.keys())
+        .chain(theirs.keys())
+        .collect();
+
+    let mut merged = Map::new();
+    for key in keys {
+        let (b, o, t) = (present(base, key), present(ours, key), present(theirs, key));
+        let value = if o == t || t == b {
+            o
+        } else if o == b {
+            t
+        } else {
+            conflict(key.clone(), b, o.clone(), t);
+            o
+        };
+        if let Some(value) = value {
+            merged.insert(key.clone(), value);
+        }
+    }
+    merged
+}
+
+/// Folds `metadata.<key>` fields back into a `metadata` object
+fn unflatten_metadata(fields: Map<String, Value>) -> Map<String, Value> {
+    let mut unflattened = Map::new();
+    let mut metadata = Map::new();
+    for (key, value) in fields {
+        match key.strip_prefix("metadata.") {
+            Some(name) => {
+                metadata.insert(name.to_string(), value);
+            }
+            None => {
+                unflattened.insert(key, value);
+            }
+        }
+    }
+    if !metadata.is_empty() {
+        unflattened.insert("metadata".to_string(), Value::Object(metadata));
+    }
+    unflattened
+}
+
+fn node_from_fields(id: &str, fields: Map<String, Value>) -> Option<Node> {
+    let mut fields = unflatten_metadata(fields);
+    fields.insert("id".to_string(), Value::from(id));
+    serde_json::from_value(Value::Object(fields)).ok()
+}
+
+#[allow(clippy::cast_possible_truncation)] // weights are f32 to begin with
+fn edge_from_fields(edge: &Edge, fields: Map<String, Value>) -> Edge {
+    let mut fields = unflatten_metadata(fields);
+    Edge {
+        from: edge.from.clone(),
+        to: edge.to.clone(),
+        relation: edge.relation.clone(),
+        domain: fields
+            .remove("domain")
+            .and_then(|domain| domain.as_str().map(str::to_string))
+            .unwrap_or_else(|| edge.domain.clone()),
+        weight: fields
+            .remove("w")
+            .and_then(|weight| weight.as_f64())
+            .map(|weight| weight as f32),
+        metadata: fields.remove("metadata"),
+    }
+}
+
+/// Result of merging one repository file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileMerge {
+    /// Merged file content in canonical form (empty if the file was deleted)
+    pub content: String,
+    /// The same merge with their value in each conflicting field
+    pub their_content: String,
+    /// Conflicts; conflicting fields hold our value in `content`
+    pub conflicts: Vec<Conflict>,
+}
+
+impl FileMerge {
+    /// Returns `content` with the lines that differ from `their_content`
+    /// between git-style conflict markers (`<<<<<<< ours`, `=======`,
+    /// `>>>>>>> theirs`)
+    ///
+    /// Without conflicts this is just `content`.
+    #[must_use]
+    pub fn with_conflict_markers(&self) -> String {
+        let ours: Vec<&str> = self.content.lines().collect();
+        let theirs: Vec<&str> = self.their_content.lines().collect();
+        let mut out = String::new();
+        let mut push = |lines: &[&str]| {
+            for line in lines {
+                out.push_str(line);
+                out.push('\n');
+            }
+        };
+
+        let (mut i, mut j) = (0, 0);
+        let end = (ours.len(), theirs.len());
+        for (our_index, their_index) in common_lines(&ours, &theirs).into_iter().chain([end]) {
+            let (our_lines, their_lines) = (&ours[i..our_index], &theirs[j..their_index]);
+            if !our_lines.is_empty() || !their_lines.is_empty() {
+                push(&["<<<<<<< ours"]);
+                push(our_lines);
+                push(&["======="]);
+                push(their_lines);
+                push(&[">>>>>>> theirs"]);
+            }
+            push(ours.get(our_index..=our_index).unwrap_or_default());
+            (i, j) = (our_index + 1, their_index + 1);
+        }
+        out
+    }
+}
+
+/// Index pairs of the lines both texts keep, in order
+///
+/// Texts of equal length are compared line by line (an edge file has one
+/// line per edge on both sides); others by longest common subsequence.
+fn common_lines(ours: &[&str], theirs: &[&str]) -> Vec<(usize, usize)> {
+    if ours.len() == theirs.len() {
+        return (0..ours.len())
+            .filter(|&i| ours[i] == theirs[i])
+            .map(|i| (i, i))
+            .collect();
+    }
+
+    let mut lcs = vec![vec![0_usize; theirs.len() + 1]; ours.len() + 1];
+    for i in (0..ours.len()).rev() {
+        for j in (0..theirs.len()).rev() {
+            lcs[i][j] = if ours[i] == theirs[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j, mut pairs) = (0, 0, Vec::new());
+    while i < ours.len() && j < theirs.len() {
+        if ours[i] == theirs[j] {
+            pairs.push((i, j));
+            (i, j) = (i + 1, j + 1);
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+/// Merges three versions of a node file (`nodes/*.json`)
+///
+/// An empty version stands for a missing file. Keys the `Node` struct does
+/// not carry are merged three-way like fields, whole values at a time.
+///
+/// # Errors
+///
+/// Returns a `Json` error if a version does not parse, or an `Integrity`
+/// error if the merge leaves more than one node in the file (both sides
+/// changed its ID)
+pub fn merge_node_files(base: &str, ours: &str, theirs: &str) -> Result<FileMerge> {
+    merge_file(base, ours, theirs, merge_node_file)
+}
+
+fn merge_node_file(base: &str, ours: &str, theirs: &str) -> Result<MergedFile> {
+    let (base_graph, base_raw) = node_file_graph(base)?;
+    let (our_graph, our_raw) = node_file_graph(ours)?;
+    let (their_graph, their_raw) = node_file_graph(theirs)?;
+    let MergeResult {
+        graph,
+        mut conflicts,
+    } = merge(&base_graph, &our_graph, &their_graph);
+
+    let content = match graph.nodes() {
+        [] => String::new(),
+        [node] => {
+            let mut raw = merge_raw(
+                base_raw.as_ref(),
+                our_raw.as_ref(),
+                their_raw.as_ref(),
+                NODE_FIELDS,
+                |field, base, ours, theirs| {
+                    conflicts.push(Conflict::NodeField {
+                        id: node.id.clone(),
+                        field,
+                        base,
+                        ours,
+                        theirs,
+                    });
+                },
+            );
+            overlay_node(&mut raw, node)?;
+            format_node_json_as(
+                &Value::Object(raw).to_string(),
+                &[base, ours, theirs].join("\n"),
+            )?
+        }
+        nodes => {
+            return Err(Error::integrity(format!(
+                "Merged node file would hold {} nodes: {}",
+                nodes.len(),
+                nodes
+                    .iter()
+                    .map(|node| node.id.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )))
+        }
+    };
+    Ok((content, conflicts))
+}
+
+/// Merges three versions of an edge file (`edges/*.jsonl`)
+///
+/// An empty version stands for a missing file. Keys the `Edge` struct does
+/// not carry are merged three-way like fields, whole values at a time.
+///
+/// # Errors
+///
+/// Returns a `Json` error, located at its line, if a version does not parse
+pub fn merge_edge_files(base: &str, ours: &str, theirs: &str) -> Result<FileMerge> {
+    merge_file(base, ours, theirs, merge_edge_file)
+}
+
+fn merge_edge_file(base: &str, ours: &str, theirs: &str) -> Result<MergedFile> {
+    let (base_graph, base_raw) = edge_file_graph(base)?;
+    let (our_graph, our_raw) = edge_file_graph(ours)?;
+    let (their_graph, their_raw) = edge_file_graph(theirs)?;
+    let MergeResult {
+        graph,
+        mut conflicts,
+    } = merge(&base_graph, &our_graph, &their_graph);
+
+    let mut merged_raw: RawLines = HashMap::new();
+    let mut lines = String::new();
+    for edge in graph.edges() {
+        let key = (edge.from.clone(), edge.to.clone(), edge.relation.clone());
+        let mut raw = merged_raw
+            .entry(key)
+            .or_insert_with_key(|key| {
+                merge_raw(
+                    base_raw.get(key),
+                    our_raw.get(key),
+                    their_raw.get(key),
+                    EDGE_FIELDS,
+                    |field, base, ours, theirs| {
+                        conflicts.push(Conflict::EdgeField {
+                            from: edge.from.clone(),
+                            to: edge.to.clone(),
+                            relation: edge.relation.clone(),
+                            field,
+                            base,
+                            ours,
+                            theirs,
+                        });
+                    },
+                )
+            })
+            .clone();
+        overlay_edge(&mut raw, edge);
+        lines += &Value::Object(raw).to_string();
+        lines.push('\n');
+    }
+    let content = format_edges_jsonl_as(&lines, &[base, ours, theirs].join("\n"))?;
+    Ok((content, conflicts))
+}
+
+/// Runs a file merge, and again with the sides swapped if it conflicts
+fn merge_file(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+    merge: fn(&str, &str, &str) -> Result<MergedFile>,
+) -> Result<FileMerge> {
+    let (content, conflicts) = merge(base, ours, theirs)?;
+    let their_content = if conflicts.is_empty() {
+        content.clone()
+    } else {
+        merge(base, theirs, ours)?.0
+    };
+    Ok(FileMerge {
+        content,
+        their_content,
+        conflicts,
+    })
+}
+
+/// Merges the raw keys of one node or edge that its struct does not carry
+///
+/// A side without the item (deleted, or the item is kept only because of a
+/// delete conflict) leaves the other side's keys as they are. Conflicts on
+/// `known` keys are left out: those come from the merged struct.
+fn merge_raw(
+    base: Option<&Map<String, Value>>,
+    ours: Option<&Map<String, Value>>,
+    theirs: Option<&Map<String, Value>>,
+    known: &[&str],
+    mut conflict: impl FnMut(String, Option<Value>, Option<Value>, Option<Value>),
+) -> Map<String, Value> {
+    match (ours, theirs) {
+        (Some(ours), Some(theirs)) => {
+            let empty = Map::new();
+            merge_fields(base.unwrap_or(&empty), ours, theirs, |field, b, o, t| {
+                if !known.contains(&field.as_str()) {
+                    conflict(field, b, o, t);
+                }
+            })
+        }
+        (Some(kept), None) | (None, Some(kept)) => kept.clone(),
+        (None, None) => Map::new(),
+    }
+}
+
+/// Parses a node file into a graph of (at most) one node and its raw keys
+fn node_file_graph(source: &str) -> Result<(GraphStore, Option<Map<String, Value>>)> {
+    let mut graph = GraphStore::new();
+    if source.trim().is_empty() {
+        return Ok((graph, None));
+    }
+    let raw: Map<String, Value> = serde_json::from_str(source).map_err(|e| Error::from_json(&e))?;
+    let node: Node =
+        serde_json::from_value(Value::Object(raw.clone())).map_err(|e| Error::from_json(&e))?;
+    graph.add_node(node);
+    Ok((graph, Some(raw)))
+}
+
+/// Parses an edge file into a graph of edges and the raw line of each edge
+fn edge_file_graph(source: &str) -> Result<(GraphStore, RawLines)> {
+    let mut graph = GraphStore::new();
+    let mut raw_lines = HashMap::new();
+    for (idx, line) in source.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let located = |e: &serde_json::Error| Error::from_json(e).at(idx + 1, e.column());
+        let raw: Map<String, Value> = serde_json::from_str(line).map_err(|e| located(&e))?;
+        let edge: Edge =
+            serde_json::from_value(Value::Object(raw.clone())).map_err(|e| located(&e))?;
+        raw_lines
+            .entry((edge.from.clone(), edge.to.clone(), edge.relation.clone()))
+            .or_insert(raw);
+        graph.add_edge(edge);
+    }
+    Ok((graph, raw_lines))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn create_test_node(id: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            r#type: "proposition".to_string(),
+            domain: "philosophy".to_string(),
+            title: format!("Node {id}"),
+            content: None,
+            formal: None,
+            tags: vec![],
+            metadata: Value::Null,
+            sources: vec![],
+            created: None,
+            updated: None,
+            status: None,
+        }
+    }
+
+    fn create_test_edge(from: &str, to: &str, relation: &str, weight: Option<f32>) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            relation: relation.to_string(),
+            domain: "philosophy".to_string(),
+            weight,
+            metadata: None,
+        }
+    }
+
+    fn create_graph(nodes: Vec<Node>, edges: Vec<Edge>) -> GraphStore {
+        let mut graph = GraphStore::new();
+        for node in nodes {
+            graph.add_node(node);
+        }
+        for edge in edges {
+            graph.add_edge(edge);
+        }
+        graph.build_adjacency();
+        graph
+    }
+
+    fn base() -> GraphStore {
+        create_graph(
+            vec![create_test_node("a"), create_test_node("b")],
+            vec![create_test_edge("a", "b", "supports", Some(0.5))],
+        )
+    }
+
+    #[test]
+    fn test_merges_edits_to_different_fields() {
+        let base = base();
+        let mut ours = base.clone();
+        let mut node = create_test_node("a");
+        node.title = "Ours".to_string();
+        node.metadata = json!({"certainty": 0.9});
+        ours.add_node(node);
+        let mut theirs = base.clone();
+        let mut node = create_test_node("a");
+        node.tags = vec!["theirs".to_string()];
+        node.metadata = json!({"importance": 3});
+        theirs.add_node(node);
+
+        let result = merge(&base, &ours, &theirs);
+
+        assert!(result.is_clean(), "{:?}", result.conflicts);
+        let merged = result.graph.get_node("a").unwrap();
+        assert_eq!(merged.title, "Ours");
+        assert_eq!(merged.tags, vec!["theirs"]);
+        assert_eq!(merged.metadata, json!({"certainty": 0.9, "importance": 3}));
+    }
+
+    #[test]
+    fn test_same_field_changed_differently_conflicts() {
+        let base = base();
+        let mut ours = base.clone();
+        let mut node = create_test_node("a");
+        node.title = "Ours".to_string();
+        ours.add_node(node);
+        let mut theirs = base.clone();
+        let mut node = create_test_node("a");
+        node.title = "Theirs".to_string();
+        theirs.add_node(node);
+
+        let result = merge(&base, &ours, &theirs);
+
+        assert_eq!(
+            result.conflicts,
+            vec![Conflict::NodeField {
+                id: "a".to_string(),
+                field: "title".to_string(),
+                base: Some(json!("Node a")),
+                ours: Some(json!("Ours")),
+                theirs: Some(json!("Theirs")),
+            }]
+        );
+        assert_eq!(result.graph.get_node("a").unwrap().title, "Ours");
+    }
+
+    #[test]
+    fn test_edge_weights() {
+        let base = base();
+        let with_weight = |weight| {
+            create_graph(
+                vec![create_test_node("a"), create_test_node("b")],
+                vec![create_test_edge("a", "b", "supports", weight)],
+            )
+        };
+
+        let result = merge(&base, &base, &with_weight(Some(0.8)));
+        assert!(result.is_clean());
+        assert_eq!(result.graph.edges()[0].weight, Some(0.8));
+
+        let result = merge(&base, &with_weight(Some(0.8)), &with_weight(Some(0.8)));
+        assert!(result.is_clean(), "same change on both sides");
+
+        let result = merge(&base, &with_weight(Some(0.7)), &with_weight(None));
+        assert_eq!(result.conflicts.len(), 1);
+        assert!(matches!(
+            &result.conflicts[0],
+            Conflict::EdgeField { field, ours: Some(_), theirs: None, .. } if field == "w"
+        ));
+        assert_eq!(result.graph.edges()[0].weight, Some(0.7));
+    }
+
+    #[test]
+    fn test_unions_additions_and_applies_deletions() {
+        let base = base();
+        let mut ours = base.clone();
+        ours.add_node(create_test_node("c"));
+        ours.add_edge(create_test_edge("c", "a", "supports", None));
+        let theirs = create_graph(
+            vec![create_test_node("a"), create_test_node("d")],
+            vec![create_test_edge("d", "a", "attacks", None)],
+        );
+
+        let result = merge(&base, &ours, &theirs);
+
+        assert!(result.is_clean(), "{:?}", result.conflicts);
+        let ids: Vec<_> = result.graph.nodes().iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c", "d"], "b and a→b deleted by theirs");
+        let edges: Vec<_> = result
+            .graph
+            .edges()
+            .iter()
+            .map(|e| (e.from.as_str(), e.relation.as_str()))
+            .collect();
+        assert_eq!(edges, vec![("c", "supports"), ("d", "attacks")]);
+        assert!(result.graph.index_of("d").is_some());
+    }
+
+    #[test]
+    fn test_delete_of_changed_item_conflicts() {
+        let base = base();
+        let mut ours = base.clone();
+        let mut node = create_test_node("b");
+        node.content = Some("Changed".to_string());
+        ours.add_node(node);
+        let theirs = create_graph(vec![create_test_node("a")], vec![]);
+
+        let result = merge(&base, &ours, &theirs);
+
+        assert_eq!(
+            result.conflicts,
+            vec![Conflict::NodeDeleted {
+                id: "b".to_string(),
+                deleted_by: Side::Theirs
+            }]
+        );
+        assert!(result.graph.get_node("b").is_some());
+        assert_eq!(result.graph.edge_count(), 0, "unchanged edge deleted");
+    }
+
+    #[test]
+    fn test_edge_to_deleted_node_is_dropped() {
+        let base = base();
+        let mut ours = base.clone();
+        ours.add_node(create_test_node("c"));
+        ours.add_edge(create_test_edge("c", "b", "supports", None));
+        let theirs = create_graph(vec![create_test_node("a")], vec![]);
+
+        let result = merge(&base, &ours, &theirs);
+
+        assert_eq!(
+            result.conflicts,
+            vec![Conflict::DanglingEdge {
+                from: "c".to_string(),
+                to: "b".to_string(),
+                relation: "supports".to_string(),
+                missing: "b".to_string(),
+            }]
+        );
+        assert!(result.graph.get_node("b").is_none());
+        assert_eq!(result.graph.edge_count(), 0);
+    }
+
+    #[test]
+    fn test_merge_node_files_keeps_unknown_keys() {
+        let base = r#"{"id":"aaa111","type":"concept","domain":"philosophy","title":"T"}"#;
+        let ours = r#"{"id":"aaa111","type":"concept","domain":"philosophy","title":"T","namespace":"x","tags":["a"]}"#;
+        let theirs = r#"{"id":"aaa111","type":"concept","domain":"philosophy","title":"New","metadata":{"importance":1e1}}"#;
+
+        let merged = merge_node_files(base, ours, theirs).unwrap();
+
+        assert!(merged.conflicts.is_empty());
+        assert!(merged.content.contains("\"title\": \"New\""));
+        assert!(merged.content.contains("\"importance\": 1e1"));
+        assert!(merged.content.contains("\"tags\": [\"a\"]"));
+        assert!(merged.content.contains("\"namespace\": \"x\""));
+        assert!(merge_node_files(base, "{", theirs).is_err());
+    }
+
+    #[test]
+    fn test_merge_node_files_merges_unknown_keys_three_way() {
+        let base = r#"{"id":"aaa111","type":"concept","domain":"philosophy","title":"T","namespace":"x","x_old":1,"x_both":"b"}"#;
+        let ours = r#"{"id":"aaa111","type":"concept","domain":"philosophy","title":"T","namespace":"x","x_old":1,"x_both":"o"}"#;
+        let theirs = r#"{"id":"aaa111","type":"concept","domain":"philosophy","title":"T","namespace":"y","x_both":"t"}"#;
+
+        let merged = merge_node_files(base, ours, theirs).unwrap();
+
+        assert!(
+            merged.content.contains("\"namespace\": \"y\""),
+            "their edit"
+        );
+        assert!(!merged.content.contains("x_old"), "their deletion");
+        assert!(merged.content.contains("\"x_both\": \"o\""), "ours wins");
+        assert_eq!(
+            merged.conflicts,
+            vec![Conflict::NodeField {
+                id: "aaa111".to_string(),
+                field: "x_both".to_string(),
+                base: Some(json!("b")),
+                ours: Some(json!("o")),
+                theirs: Some(json!("t")),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_merge_edge_files_merges_unknown_keys_three_way() {
+        let base = "{\"f\":\"a\",\"t\":\"b\",\"relation\":\"supports\",\"domain\":\"philosophy\",\"note\":\"old\",\"gone\":true}\n";
+        let ours = base;
+        let theirs = "{\"f\":\"a\",\"t\":\"b\",\"relation\":\"supports\",\"domain\":\"philosophy\",\"note\":\"new\"}\n";
+
+        let merged = merge_edge_files(base, ours, theirs).unwrap();
+
+        assert!(merged.conflicts.is_empty());
+        assert_eq!(merged.content, theirs);
+    }
+
+    #[test]
+    fn test_conflict_markers_hold_both_sides() {
+        let base = r#"{"id":"aaa111","type":"concept","domain":"philosophy","title":"T","content":"Base","x_note":"b"}"#;
+        let ours = r#"{"id":"aaa111","type":"concept","domain":"philosophy","title":"Ours","content":"Base","x_note":"o"}"#;
+        let theirs = r#"{"id":"aaa111","type":"concept","domain":"philosophy","title":"Theirs"}"#;
+
+        let merged = merge_node_files(base, ours, theirs).unwrap();
+
+        assert_eq!(merged.conflicts.len(), 2, "{:?}", merged.conflicts);
+        assert_eq!(
+            merged.with_conflict_markers(),
+            concat!(
+                "{\n",
+                "  \"id\": \"aaa111\",\n",
+                "  \"type\": \"concept\",\n",
+                "  \"domain\": \"philosophy\",\n",
+                "<<<<<<< ours\n",
+                "  \"title\": \"Ours\",\n",
+                "  \"x_note\": \"o\"\n",
+                "=======\n",
+                "  \"title\": \"Theirs\"\n",
+                ">>>>>>> theirs\n",
+                "}\n",
+            )
+        );
+        let clean = merge_node_files(base, ours, base).unwrap();
+        assert_eq!(clean.with_conflict_markers(), clean.content);
+    }
+
+    #[test]
+    fn test_merge_edge_files() {
+        let base = "{\"f\":\"a\",\"t\":\"b\",\"relation\":\"supports\",\"w\":0.5,\"domain\":\"philosophy\",\"note\":\"kept\"}\n";
+        let ours = "{\"f\":\"a\",\"t\":\"b\",\"relation\":\"supports\",\"w\":0.6,\"domain\":\"philosophy\",\"note\":\"kept\"}\n";
+        let theirs = "{\"f\":\"a\",\"t\":\"b\",\"relation\":\"supports\",\"w\":0.5,\"domain\":\"philosophy\",\"note\":\"kept\"}\n\
+                      {\"f\":\"a\",\"t\":\"c\",\"relation\":\"supports\",\"domain\":\"philosophy\"}\n";
+
+        let merged = merge_edge_files(base, ours, theirs).unwrap();
+
+        assert!(merged.conflicts.is_empty());
+        assert_eq!(
+            merged.content,
+            "{\"f\":\"a\",\"t\":\"b\",\"relation\":\"supports\",\"w\":0.6,\"domain\":\"philosophy\",\"note\":\"kept\"}\n\
+             {\"f\":\"a\",\"t\":\"c\",\"relation\":\"supports\",\"domain\":\"philosophy\"}\n"
+        );
+
+        let err = merge_edge_files(base, "{\"f\":\"a\"}\nnot json", theirs).unwrap_err();
+        assert_eq!(err.location().line, Some(1));
+    }
+}
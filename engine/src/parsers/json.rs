@@ -1,58 +1,32 @@
 //! JSON node parser
 
+use super::loader::load_nodes_lenient;
+use crate::error::{Error, Result};
 use crate::graph::Node;
 use std::path::Path;
 
 /// Loads nodes from a directory containing JSON files
 ///
+/// Subdirectories are searched recursively. Use
+/// [`load_nodes_lenient`](super::loader::load_nodes_lenient) to collect
+/// every error instead of failing on the first.
+///
 /// # Arguments
 ///
 /// * `path` - Path to directory containing `*.json` node files
 ///
 /// # Returns
 ///
-/// Result containing vector of nodes or error message
+/// Result containing vector of nodes or error
 ///
 /// # Errors
 ///
-/// Returns error if directory doesn't exist, files can't be read, or JSON is malformed
-pub fn load_nodes_from_dir(path: &Path) -> Result<Vec<Node>, String> {
-    use std::fs;
-
-    if !path.exists() {
-        return Err(format!("Directory not found: {}", path.display()));
-    }
-
-    if !path.is_dir() {
-        return Err(format!("Path is not a directory: {}", path.display()));
-    }
-
-    let mut nodes = Vec::new();
-
-    // Read all .json files in directory
-    let entries = fs::read_dir(path)
-        .map_err(|e| format!("Failed to read directory {}: {}", path.display(), e))?;
-
-    for entry in entries {
-        let entry = entry
-            .map_err(|e| format!("Failed to read directory entry: {e}"))?;
-        let file_path = entry.path();
-
-        // Only process .json files
-        if let Some(ext) = file_path.extension() {
-            if ext == "json" {
-                let json_content = fs::read_to_string(&file_path)
-                    .map_err(|e| format!("Failed to read {}: {}", file_path.display(), e))?;
-
-                let node = load_node_from_json(&json_content)
-                    .map_err(|e| format!("{}: {}", file_path.display(), e))?;
-
-                nodes.push(node);
-            }
-        }
-    }
+/// Returns an `Io` error if the directory doesn't exist or files can't be
+/// read, or a `Json` error (with file, line and column) if JSON is malformed
+pub fn load_nodes_from_dir(path: &Path) -> Result<Vec<Node>> {
+    let report = load_nodes_lenient(path);
 
-    Ok(nodes)
+    report.diagnostics.into_iter().next().map_or(Ok(report.nodes), Err)
 }
 
 /// Loads nodes from a JSON string
@@ -63,14 +37,14 @@ pub fn load_nodes_from_dir(path: &Path) -> Result<Vec<Node>, String> {
 ///
 /// # Returns
 ///
-/// Result containing node or error message
+/// Result containing node or error
 ///
 /// # Errors
 ///
-/// Returns error if JSON is malformed or missing required fields
-pub fn load_node_from_json(json: &str) -> Result<Node, String> {
-    serde_json::from_str(json)
-        .map_err(|e| format!("JSON parsing error: {e}"))
+/// Returns a `Json` error with line and column if JSON is malformed or
+/// missing required fields
+pub fn load_node_from_json(json: &str) -> Result<Node> {
+    serde_json::from_str(json).map_err(|e| Error::from_json(&e))
 }
 
 #[cfg(test)]
@@ -122,6 +96,8 @@ mod tests {
 
         assert!(result.is_err());
         let err = result.unwrap_err();
+        assert_eq!(err.kind(), "json");
+        let err = err.to_string();
         assert!(err.contains("missing") || err.contains("required") || err.contains("field"));
     }
 
@@ -143,8 +119,7 @@ mod tests {
         for i in 1..=3 {
             let id = format!("test{i:02}");
             let json = format!(
-                r#"{{"id":"{}","type":"proposition","domain":"philosophy","title":"Node {}"}}"#,
-                id, i
+                r#"{{"id":"{id}","type":"proposition","domain":"philosophy","title":"Node {i}"}}"#
             );
             fs::write(temp_dir.join(format!("{id}.json")), json).unwrap();
         }
@@ -180,6 +155,9 @@ mod tests {
 
         assert!(result.is_err());
         let err = result.unwrap_err();
+        assert_eq!(err.kind(), "io");
+        assert_eq!(err.location().path.as_deref(), Some(nonexistent));
+        let err = err.to_string();
         assert!(err.contains("not found") || err.contains("exist"));
     }
 
@@ -194,14 +172,17 @@ mod tests {
             r#"{"id":"abc123","type":"proposition","domain":"philosophy","title":"Valid"}"#,
         )
         .unwrap();
-        fs::write(temp_dir.join("malformed.json"), r#"{INVALID JSON}"#).unwrap();
+        fs::write(temp_dir.join("malformed.json"), r"{INVALID JSON}").unwrap();
 
         let result = load_nodes_from_dir(&temp_dir);
 
         // Should return error mentioning which file failed
         assert!(result.is_err());
         let err = result.unwrap_err();
-        assert!(err.contains("malformed") || err.contains("JSON"));
+        assert_eq!(err.kind(), "json");
+        assert_eq!(err.location().path, Some(temp_dir.join("malformed.json")));
+        assert_eq!(err.location().line, Some(1));
+        assert!(err.to_string().contains("malformed.json:1:"));
 
         fs::remove_dir_all(&temp_dir).unwrap();
     }
@@ -214,7 +195,7 @@ mod tests {
 
         if examples_dir.exists() {
             let result = load_nodes_from_dir(&examples_dir);
-            assert!(result.is_ok(), "Failed to load sample nodes: {:?}", result);
+            assert!(result.is_ok(), "Failed to load sample nodes: {result:?}");
 
             let nodes = result.unwrap();
             assert!(nodes.len() >= 10, "Expected at least 10 sample nodes");
@@ -0,0 +1,367 @@
+//! Lenient directory loading
+//!
+//! Unlike the strict parsers, which stop at the first problem, these loaders
+//! keep every node and edge that parses and return a diagnostic for each one
+//! that does not, so a contributor sees all errors from a single run.
+//!
+//! Directories are walked recursively (`**/*.json` for nodes, `**/*.jsonl`
+//! and `**/*.toon` for edges, matching `scripts/build_index.py`) in sorted
+//! path order. Edge files follow the `edges/<relation>.jsonl` convention:
+//! an edge whose relation differs from its file name is reported as an
+//! `Integrity` diagnostic and not loaded. With
+//! the `parallel` feature on native targets, files are read and parsed on
+//! the rayon thread pool; results keep the same order either way.
+
+use super::json::load_node_from_json;
+use super::toon::parse_toon_document;
+use crate::error::Error;
+use crate::graph::{Edge, GraphStore, Node};
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Everything a lenient load produced
+#[derive(Debug, Clone, Default)]
+pub struct LoadReport {
+    /// Nodes that parsed successfully
+    pub nodes: Vec<Node>,
+    /// Edges that parsed successfully
+    pub edges: Vec<Edge>,
+    /// One located error per file or line that failed
+    pub diagnostics: Vec<Error>,
+    /// File each loaded node came from: `node_paths[i]` is the file of `nodes[i]`
+    pub node_paths: Vec<PathBuf>,
+}
+
+impl LoadReport {
+    /// Returns true if nothing failed to load
+    #[must_use]
+    pub const fn is_clean(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Appends another report's nodes, edges, diagnostics and node paths
+    pub fn merge(&mut self, other: Self) {
+        self.nodes.extend(other.nodes);
+        self.edges.extend(other.edges);
+        self.diagnostics.extend(other.diagnostics);
+        self.node_paths.extend(other.node_paths);
+    }
+
+    /// Returns the file each loaded node came from, by node ID
+    ///
+    /// If several files define the same ID, the last one loaded wins; use
+    /// [`Self::node_paths`] to see every file.
+    #[must_use]
+    pub fn node_files(&self) -> HashMap<String, PathBuf> {
+        self.nodes
+            .iter()
+            .zip(&self.node_paths)
+            .map(|(node, path)| (node.id.clone(), path.clone()))
+            .collect()
+    }
+
+    /// Builds a graph store with adjacency from the loaded nodes and edges
+    #[must_use]
+    pub fn into_graph_store(self) -> GraphStore {
+        let mut store = GraphStore::new();
+        for node in self.nodes {
+            store.add_node(node);
+        }
+        for edge in self.edges {
+            store.add_edge(edge);
+        }
+        store.build_adjacency();
+        store
+    }
+}
+
+/// Directory of node files under a repository root
+pub const NODES_DIR: &str = "nodes";
+
+/// Directory of edge files under a repository root
+pub const EDGES_DIR: &str = "edges";
+
+/// Loads every `*.json` node file under `dir`, recursively
+///
+/// A missing directory or unreadable file becomes an `Io` diagnostic and a
+/// malformed file a `Json` diagnostic with its path, line and column.
+#[must_use]
+pub fn load_nodes_lenient(dir: &Path) -> LoadReport {
+    let mut report = LoadReport::default();
+    let files = collect_files(dir, &["json"], &mut report.diagnostics);
+
+    for (path, result) in files.iter().zip(map_files(&files, |path| {
+        read_file(path)
+            .and_then(|content| load_node_from_json(&content).map_err(|e| e.with_path(path)))
+    })) {
+        match result {
+            Ok(node) => {
+                report.node_paths.push(path.clone());
+                report.nodes.push(node);
+            }
+            Err(err) => report.diagnostics.push(err),
+        }
+    }
+
+    report
+}
+
+/// Loads every `*.jsonl` and `*.toon` edge file under `dir`, recursively
+///
+/// Malformed lines are reported individually; the valid lines of the same
+/// file are still loaded. TOON files may also carry a `nodes` table.
+#[must_use]
+pub fn load_edges_lenient(dir: &Path) -> LoadReport {
+    let mut report = LoadReport::default();
+    let files = collect_files(dir, &["jsonl", "toon"], &mut report.diagnostics);
+
+    for file_report in map_files(&files, load_edge_file) {
+        report.merge(file_report);
+    }
+
+    report
+}
+
+/// Loads nodes from `nodes_dir` and edges from `edges_dir`
+#[must_use]
+pub fn load_lenient(nodes_dir: &Path, edges_dir: &Path) -> LoadReport {
+    let mut report = load_nodes_lenient(nodes_dir);
+    report.merge(load_edges_lenient(edges_dir));
+    report
+}
+
+/// Loads a repository checkout: `nodes/` and `edges/` under `root`
+#[must_use]
+pub fn load_repository_lenient(root: &Path) -> LoadReport {
+    load_lenient(&root.join(NODES_DIR), &root.join(EDGES_DIR))
+}
+
+/// Loads one edge file, checking JSONL relations against the file name
+fn load_edge_file(path: &Path) -> LoadReport {
+    let mut report = LoadReport::default();
+    let content = match read_file(path) {
+        Ok(content) => content,
+        Err(err) => {
+            report.diagnostics.push(err);
+            return report;
+        }
+    };
+
+    if path.extension().is_some_and(|ext| ext == "toon") {
+        match parse_toon_document(&content) {
+            Ok(document) => {
+                report.node_paths = vec![path.to_path_buf(); document.nodes.len()];
+                report.nodes = document.nodes;
+                report.edges = document.edges;
+            }
+            Err(err) => report.diagnostics.push(err.with_path(path)),
+        }
+        return report;
+    }
+
+    let relation = path.file_stem().and_then(OsStr::to_str).unwrap_or_default();
+    for (line_num, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str::<Edge>(line) {
+            Ok(edge) if edge.relation == relation => report.edges.push(edge),
+            Ok(edge) => report.diagnostics.push(
+                Error::integrity(format!(
+                    "Edge {} -> {} has relation '{}' but is stored in '{relation}' file",
+                    edge.from, edge.to, edge.relation
+                ))
+                .with_path(path)
+                .at(line_num + 1, 1),
+            ),
+            Err(e) => report.diagnostics.push(
+                Error::from_json(&e)
+                    .with_path(path)
+                    .at(line_num + 1, e.column()),
+            ),
+        }
+    }
+
+    report
+}
+
+/// Recursively lists files with any of the given extensions, sorted by path
+pub(crate) fn collect_files(
+    dir: &Path,
+    extensions: &[&str],
+    diagnostics: &mut Vec<Error>,
+) -> Vec<PathBuf> {
+    if !dir.exists() {
+        diagnostics.push(Error::io("Directory not found").with_path(dir));
+        return Vec::new();
+    }
+    if !dir.is_dir() {
+        diagnostics.push(Error::io("Path is not a directory").with_path(dir));
+        return Vec::new();
+    }
+
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let entries = match fs::read_dir(&current) {
+            Ok(entries) => entries,
+            Err(e) => {
+                diagnostics
+                    .push(Error::io(format!("Failed to read directory: {e}")).with_path(&current));
+                continue;
+            }
+        };
+
+        for entry in entries {
+            let path = match entry {
+                Ok(entry) => entry.path(),
+                Err(e) => {
+                    diagnostics.push(
+                        Error::io(format!("Failed to read directory entry: {e}"))
+                            .with_path(&current),
+                    );
+                    continue;
+                }
+            };
+
+            if path.is_dir() {
+                pending.push(path);
+            } else if path
+                .extension()
+                .and_then(OsStr::to_str)
+                .is_some_and(|ext| extensions.contains(&ext))
+            {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
+
+pub(crate) fn read_file(path: &Path) -> Result<String, Error> {
+    fs::read_to_string(path)
+        .map_err(|e| Error::io(format!("Failed to read file: {e}")).with_path(path))
+}
+
+/// Applies `load` to every file, in parallel when enabled, preserving order
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+fn map_files<T: Send>(files: &[PathBuf], load: impl Fn(&Path) -> T + Sync) -> Vec<T> {
+    use rayon::prelude::*;
+
+    files.par_iter().map(|path| load(path)).collect()
+}
+
+/// Applies `load` to every file, in parallel when enabled, preserving order
+#[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+fn map_files<T>(files: &[PathBuf], load: impl Fn(&Path) -> T) -> Vec<T> {
+    files.iter().map(|path| load(path)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node_json(id: &str) -> String {
+        format!(r#"{{"id":"{id}","type":"proposition","domain":"philosophy","title":"Node {id}"}}"#)
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_load_nodes_lenient_collects_all_errors() {
+        let dir = temp_dir("truthmines_lenient_nodes");
+        fs::create_dir_all(dir.join("philosophy/epistemology")).unwrap();
+        fs::write(dir.join("aaa001.json"), node_json("aaa001")).unwrap();
+        fs::write(dir.join("philosophy/bbb002.json"), node_json("bbb002")).unwrap();
+        fs::write(
+            dir.join("philosophy/epistemology/ccc003.json"),
+            node_json("ccc003"),
+        )
+        .unwrap();
+        fs::write(dir.join("broken.json"), "{\n  \"id\": ?\n}").unwrap();
+        fs::write(dir.join("philosophy/partial.json"), r#"{"id":"ddd004"}"#).unwrap();
+        fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let report = load_nodes_lenient(&dir);
+
+        let mut ids: Vec<&str> = report.nodes.iter().map(|node| node.id.as_str()).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, ["aaa001", "bbb002", "ccc003"]);
+        assert_eq!(report.diagnostics.len(), 2);
+        assert!(!report.is_clean());
+
+        assert_eq!(
+            report.node_files().get("bbb002"),
+            Some(&dir.join("philosophy/bbb002.json"))
+        );
+
+        let broken = &report.diagnostics[0];
+        assert_eq!(broken.location().path, Some(dir.join("broken.json")));
+        assert_eq!(broken.location().line, Some(2));
+        assert_eq!(
+            report.diagnostics[1].location().path,
+            Some(dir.join("philosophy/partial.json"))
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_edges_lenient_reports_bad_lines() {
+        let dir = temp_dir("truthmines_lenient_edges");
+        fs::write(
+            dir.join("supports.jsonl"),
+            r#"{"f":"a","t":"b","relation":"supports","domain":"x"}
+{"f":"a"}
+{"f":"b","t":"c","relation":"supports","domain":"x"}"#,
+        )
+        .unwrap();
+        fs::write(dir.join("attacks.jsonl"), "not json\n").unwrap();
+
+        let report = load_edges_lenient(&dir);
+
+        assert_eq!(report.edges.len(), 2);
+        assert_eq!(report.diagnostics.len(), 2);
+        assert_eq!(
+            report.diagnostics[0].location().path,
+            Some(dir.join("attacks.jsonl"))
+        );
+        let bad_line = &report.diagnostics[1];
+        assert_eq!(bad_line.location().path, Some(dir.join("supports.jsonl")));
+        assert_eq!(bad_line.location().line, Some(2));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_lenient_missing_directory() {
+        let missing = Path::new("/nonexistent/truthmines/nodes");
+
+        let report = load_lenient(missing, missing);
+
+        assert!(report.nodes.is_empty());
+        assert_eq!(report.diagnostics.len(), 2);
+        assert_eq!(report.diagnostics[0].kind(), "io");
+    }
+
+    #[test]
+    fn test_load_lenient_project_data() {
+        let project_root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+
+        let report = load_lenient(&project_root.join("nodes"), &project_root.join("edges"));
+
+        assert!(report.is_clean(), "{:?}", report.diagnostics);
+        assert!(!report.nodes.is_empty());
+        assert!(!report.edges.is_empty());
+    }
+}
@@ -1,4 +1,6 @@
 //! Data parsers
 
 pub mod json;
+pub mod loader;
 pub mod toon;
+pub mod writer;
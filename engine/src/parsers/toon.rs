@@ -1,6 +1,259 @@
-//! TOON format edge parser
+//! TOON format parser and writer
+//!
+//! A TOON document is a sequence of tables. The reserved `nodes` table holds
+//! node rows; every other table holds edges of the relation it is named after:
+//!
+//! ```text
+//! nodes[2]{id,type,domain,title}:
+//! abc123,proposition,philosophy,Knowledge requires safety
+//! def456,theorem,mathematics,Fundamental Theorem
+//!
+//! supports[1]{f,t,w,domain,conditions}:
+//! abc123,def456,0.9,philosophy,{"requires":["safety"]}
+//! ```
+//!
+//! The declared `[count]` must match the number of rows. Edge columns other
+//! than `f`, `t`, `w` and `domain` become keys of `Edge.metadata`; a column
+//! named `metadata` carries the whole metadata value.
+//!
+//! Cells come in three forms:
+//! - bare: trimmed text; in metadata columns, numbers, `true`/`false` and
+//!   `null` are read as JSON scalars
+//! - quoted: `"..."` with `\"`, `\\`, `\n`, `\r` and `\t` escapes; may span
+//!   lines and is always a string
+//! - JSON: a bare cell starting with `{` or `[` is read as one JSON value,
+//!   commas inside it included
 
-use crate::graph::Edge;
+use crate::error::{Error, Location, Result};
+use crate::graph::{Edge, GraphStore, Node};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Name of the table holding node rows
+pub const NODES_TABLE: &str = "nodes";
+
+/// Required node columns, in the order they are written
+const NODE_FIELDS: [&str; 4] = ["id", "type", "domain", "title"];
+
+/// Optional long node columns, in the order they are written
+const OPTIONAL_NODE_FIELDS: [&str; 8] = [
+    "status", "content", "formal", "tags", "sources", "created", "updated", "metadata",
+];
+
+/// Edge columns that map to `Edge` fields rather than metadata keys
+const EDGE_FIELDS: [&str; 5] = ["f", "t", "w", "domain", "metadata"];
+
+/// Separator for list-valued node cells (`tags`, `sources`)
+///
+/// Lists with an item that contains it, is empty or has surrounding
+/// whitespace are written as a JSON array cell instead.
+const LIST_SEPARATOR: char = '|';
+
+/// Nodes and edges read from (or written to) one TOON document
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToonDocument {
+    /// Rows of the `nodes` table
+    pub nodes: Vec<Node>,
+    /// Rows of all relation tables
+    pub edges: Vec<Edge>,
+}
+
+impl ToonDocument {
+    /// Copies the nodes and edges of a graph
+    #[must_use]
+    pub fn from_graph(graph: &GraphStore) -> Self {
+        Self {
+            nodes: graph.nodes().to_vec(),
+            edges: graph.edges().to_vec(),
+        }
+    }
+
+    /// Builds a graph store with adjacency from the document
+    #[must_use]
+    pub fn into_graph_store(self) -> GraphStore {
+        let mut store = GraphStore::new();
+        for node in self.nodes {
+            store.add_node(node);
+        }
+        for edge in self.edges {
+            store.add_edge(edge);
+        }
+        store.build_adjacency();
+        store
+    }
+}
+
+/// Kind of table currently being read
+enum TableKind {
+    Nodes,
+    Edges(String),
+}
+
+/// Table currently being read
+struct Table {
+    kind: TableKind,
+    name: String,
+    declared: Option<usize>,
+    fields: Vec<String>,
+    rows: usize,
+    /// Line of the table header
+    line: usize,
+}
+
+impl Table {
+    /// Checks the declared row count against the rows read
+    fn finish(&self) -> Result<()> {
+        match self.declared {
+            Some(declared) if declared != self.rows => Err(Error::toon(format!(
+                "Table '{}' declares {declared} rows but has {}",
+                self.name, self.rows
+            ))
+            .at(self.line, 1)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// One parsed cell
+#[derive(Debug, Clone, PartialEq)]
+enum Cell {
+    Bare(String),
+    Quoted(String),
+    Json(Value),
+}
+
+impl Cell {
+    /// Returns the cell as text (JSON cells are re-serialized)
+    fn into_text(self) -> String {
+        match self {
+            Self::Bare(text) | Self::Quoted(text) => text,
+            Self::Json(value) => value.to_string(),
+        }
+    }
+
+    /// Returns the cell as a JSON value; `None` for an empty bare cell
+    fn into_value(self) -> Option<Value> {
+        match self {
+            Self::Bare(text) if text.is_empty() => None,
+            Self::Bare(text) => Some(match serde_json::from_str::<Value>(&text) {
+                Ok(value @ (Value::Number(_) | Value::Bool(_) | Value::Null)) => value,
+                _ => Value::String(text),
+            }),
+            Self::Quoted(text) => Some(Value::String(text)),
+            Self::Json(value) => Some(value),
+        }
+    }
+}
+
+/// Parses a TOON document containing node and/or edge tables
+///
+/// # Arguments
+///
+/// * `toon_content` - TOON formatted string
+///
+/// # Returns
+///
+/// Result containing the document or error message
+///
+/// # Errors
+///
+/// Returns error if a header is malformed, a row has the wrong number of
+/// fields, a table's declared row count is wrong, or a node table lacks a
+/// required column
+pub fn parse_toon_document(toon_content: &str) -> Result<ToonDocument> {
+    let mut document = ToonDocument::default();
+    let mut table: Option<Table> = None;
+    let mut lines = toon_content.lines().enumerate();
+
+    while let Some((line_num, raw_line)) = lines.next() {
+        let line = raw_line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if is_header(line) {
+            if let Some(previous) = &table {
+                previous.finish()?;
+            }
+
+            let (name, declared, fields) =
+                parse_header(line).map_err(|err| err.at(line_num + 1, 1))?;
+            let kind = if name == NODES_TABLE {
+                for required in NODE_FIELDS {
+                    if !fields.iter().any(|field| field == required) {
+                        return Err(Error::toon(format!(
+                            "Node table missing required field '{required}': {line}"
+                        ))
+                        .at(line_num + 1, 1));
+                    }
+                }
+                TableKind::Nodes
+            } else {
+                TableKind::Edges(name.clone())
+            };
+            table = Some(Table {
+                kind,
+                name,
+                declared,
+                fields,
+                rows: 0,
+                line: line_num + 1,
+            });
+            continue;
+        }
+
+        // Parse data row, pulling in continuation lines while a quoted
+        // field is still open
+        let mut row = raw_line.to_string();
+        let cells = loop {
+            match split_row(&row) {
+                Ok(Some(cells)) => break cells,
+                Ok(None) => {}
+                Err((offset, message)) => {
+                    let Location { line, column, .. } = Location::from_offset(&row, offset);
+                    return Err(
+                        Error::toon(message).at(line_num + line.unwrap_or(1), column.unwrap_or(1))
+                    );
+                }
+            }
+            let Some((_, next)) = lines.next() else {
+                return Err(Error::toon("Unterminated quoted field").at(line_num + 1, 1));
+            };
+            row.push('\n');
+            row.push_str(next);
+        };
+
+        let Some(table) = &mut table else {
+            continue;
+        };
+
+        if cells.len() != table.fields.len() {
+            return Err(Error::toon(format!(
+                "Field count mismatch: expected {} fields, got {}",
+                table.fields.len(),
+                cells.len()
+            ))
+            .at(line_num + 1, 1));
+        }
+
+        table.rows += 1;
+        match &table.kind {
+            TableKind::Nodes => document.nodes.push(node_from_row(&table.fields, cells)),
+            TableKind::Edges(relation) => {
+                document
+                    .edges
+                    .push(edge_from_row(relation, &table.fields, cells));
+            }
+        }
+    }
+
+    if let Some(table) = &table {
+        table.finish()?;
+    }
+
+    Ok(document)
+}
 
 /// Parses edges from TOON format string
 ///
@@ -11,6 +264,9 @@ use crate::graph::Edge;
 /// ghi789,jkl012,0.85,philosophy
 /// ```
 ///
+/// A `nodes` table, if present, is validated but skipped; use
+/// [`parse_toon_document`] to read it.
+///
 /// # Arguments
 ///
 /// * `toon_content` - TOON formatted string
@@ -22,85 +278,494 @@ use crate::graph::Edge;
 /// # Errors
 ///
 /// Returns error if TOON format is malformed or has invalid data
-pub fn parse_toon(toon_content: &str) -> Result<Vec<Edge>, String> {
-    let mut edges = Vec::new();
-    let mut current_relation = None;
-    let mut current_fields = Vec::new();
+pub fn parse_toon(toon_content: &str) -> Result<Vec<Edge>> {
+    parse_toon_document(toon_content).map(|document| document.edges)
+}
 
-    for line in toon_content.lines() {
-        let line = line.trim();
+/// Parses a header line: `name[count]{fields}:`
+///
+/// The `[count]` part is optional.
+fn parse_header(line: &str) -> Result<(String, Option<usize>, Vec<String>)> {
+    let parts: Vec<&str> = line.split('{').collect();
+    if parts.len() != 2 {
+        return Err(Error::toon(format!("Invalid TOON header format: {line}")));
+    }
 
-        if line.is_empty() {
-            continue;
+    // Extract table name (before '[')
+    let (name, count) = match parts[0].split_once('[') {
+        Some((name, rest)) => {
+            let count = rest
+                .strip_suffix(']')
+                .and_then(|count| count.trim().parse::<usize>().ok())
+                .ok_or_else(|| {
+                    Error::toon(format!("Invalid TOON header: bad row count in {line}"))
+                })?;
+            (name.trim(), Some(count))
         }
+        None => (parts[0].trim(), None),
+    };
+    if name.is_empty() {
+        return Err(Error::toon(format!(
+            "Invalid TOON header: missing relation name in {line}"
+        )));
+    }
+
+    // Extract fields (between { and })
+    let fields = parts[1]
+        .trim_end_matches(':')
+        .trim_end_matches('}')
+        .split(',')
+        .map(|field| field.trim().to_string())
+        .collect();
+
+    Ok((name.to_string(), count, fields))
+}
+
+/// Returns true if a line is a table header rather than a data row
+///
+/// Headers end with `:` and have no comma or quote before the field list;
+/// every table has at least two columns, so data rows always contain an
+/// unquoted comma there.
+fn is_header(line: &str) -> bool {
+    let name_part = line.split('{').next().unwrap_or(line);
+    line.ends_with(':') && !name_part.contains(',') && !name_part.contains('"')
+}
+
+/// Splits a data row into cells
+///
+/// # Returns
+///
+/// `Ok(None)` if a quoted or JSON cell is still open at the end of `row`,
+/// or the byte offset and message of the first malformed cell
+fn split_row(row: &str) -> std::result::Result<Option<Vec<Cell>>, (usize, String)> {
+    let mut cells = Vec::new();
+    let mut chars = row.char_indices().peekable();
+
+    loop {
+        while chars.next_if(|(_, c)| *c == ' ' || *c == '\t').is_some() {}
+
+        let closed = match chars.peek() {
+            Some(&(_, '"')) => {
+                chars.next();
+                let mut cell = String::new();
+                loop {
+                    match chars.next() {
+                        None => return Ok(None),
+                        Some((_, '"')) => break,
+                        Some((escape, '\\')) => match chars.next() {
+                            None => return Ok(None),
+                            Some((_, '"')) => cell.push('"'),
+                            Some((_, '\\')) => cell.push('\\'),
+                            Some((_, 'n')) => cell.push('\n'),
+                            Some((_, 'r')) => cell.push('\r'),
+                            Some((_, 't')) => cell.push('\t'),
+                            Some((_, other)) => {
+                                return Err((escape, format!("Invalid escape '\\{other}'")))
+                            }
+                        },
+                        Some((_, c)) => cell.push(c),
+                    }
+                }
+                cells.push(Cell::Quoted(cell));
+                true
+            }
+            Some(&(start, '{' | '[')) => {
+                let Some(end) = json_cell_end(&mut chars) else {
+                    return Ok(None);
+                };
+                let value = serde_json::from_str(&row[start..end])
+                    .map_err(|e| (start, format!("Invalid JSON cell: {e}")))?;
+                cells.push(Cell::Json(value));
+                true
+            }
+            _ => {
+                let mut cell = String::new();
+                let mut last = true;
+                for (_, c) in chars.by_ref() {
+                    if c == ',' {
+                        last = false;
+                        break;
+                    }
+                    cell.push(c);
+                }
+                cells.push(Cell::Bare(cell.trim().to_string()));
+                if last {
+                    return Ok(Some(cells));
+                }
+                false
+            }
+        };
 
-        // Check if this is a header line (ends with :)
-        if line.ends_with(':') {
-            // Parse header: relation[count]{fields}:
-            let parts: Vec<&str> = line.split('{').collect();
-            if parts.len() != 2 {
-                return Err(format!("Invalid TOON header format: {line}"));
+        // After a quoted or JSON cell only a separator may follow
+        if closed {
+            while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+            match chars.next() {
+                None => return Ok(Some(cells)),
+                Some((_, ',')) => {}
+                Some((offset, other)) => {
+                    return Err((offset, format!("Unexpected '{other}' after closing quote")))
+                }
             }
+        }
+    }
+}
 
-            // Extract relation name (before '[')
-            let relation_part = parts[0];
-            let relation = relation_part.split('[').next().unwrap_or("");
-            if relation.is_empty() {
-                return Err(format!("Invalid TOON header: missing relation name in {line}"));
+/// Consumes a balanced JSON object/array and returns its end byte offset
+///
+/// Returns `None` if the input ends first.
+fn json_cell_end(chars: &mut impl Iterator<Item = (usize, char)>) -> Option<usize> {
+    let mut depth = 0_usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (idx, c) in chars {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
             }
-            current_relation = Some(relation.to_string());
-
-            // Extract fields (between { and })
-            let fields_part = parts[1]
-                .trim_end_matches(':')
-                .trim_end_matches('}')
-                .trim();
-            current_fields = fields_part.split(',').map(str::trim).collect();
-
-        } else if let Some(relation) = &current_relation {
-            // Parse data row
-            let values: Vec<&str> = line.split(',').map(str::trim).collect();
-
-            if values.len() != current_fields.len() {
-                return Err(format!(
-                    "Field count mismatch: expected {} fields, got {} in line: {line}",
-                    current_fields.len(),
-                    values.len()
-                ));
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(idx + c.len_utf8());
+                }
             }
+            _ => {}
+        }
+    }
 
-            // Map fields to edge
-            let mut from = "";
-            let mut to = "";
-            let mut domain = "";
-            let mut weight: Option<f32> = None;
-
-            for (i, &field) in current_fields.iter().enumerate() {
-                match field {
-                    "f" => from = values[i],
-                    "t" => to = values[i],
-                    "domain" => domain = values[i],
-                    "w" => {
-                        if !values[i].is_empty() {
-                            weight = values[i].parse::<f32>().ok();
-                        }
-                    }
-                    _ => {} // Ignore unknown fields
+    None
+}
+
+fn edge_from_row(relation: &str, fields: &[String], cells: Vec<Cell>) -> Edge {
+    let mut edge = Edge {
+        from: String::new(),
+        to: String::new(),
+        relation: relation.to_string(),
+        domain: String::new(),
+        weight: None,
+        metadata: None,
+    };
+    let mut metadata = Map::new();
+
+    for (field, cell) in fields.iter().zip(cells) {
+        match field.as_str() {
+            "f" => edge.from = cell.into_text(),
+            "t" => edge.to = cell.into_text(),
+            "domain" => edge.domain = cell.into_text(),
+            "w" => edge.weight = cell.into_text().parse::<f32>().ok(),
+            "metadata" => match cell.into_value() {
+                Some(Value::Object(entries)) => metadata.extend(entries),
+                other => edge.metadata = other,
+            },
+            key => {
+                if let Some(value) = cell.into_value() {
+                    metadata.insert(key.to_string(), value);
                 }
             }
+        }
+    }
 
-            edges.push(Edge {
-                from: from.to_string(),
-                to: to.to_string(),
-                relation: relation.clone(),
-                domain: domain.to_string(),
-                weight,
-            metadata: None,
+    if !metadata.is_empty() {
+        edge.metadata = Some(Value::Object(metadata));
+    }
+
+    edge
+}
+
+fn node_from_row(fields: &[String], cells: Vec<Cell>) -> Node {
+    let mut node = Node {
+        id: String::new(),
+        r#type: String::new(),
+        domain: String::new(),
+        title: String::new(),
+        content: None,
+        formal: None,
+        tags: vec![],
+        metadata: Value::Null,
+        sources: vec![],
+        created: None,
+        updated: None,
+        status: None,
+    };
+
+    let optional = |cell: Cell| Some(cell.into_text()).filter(|text| !text.is_empty());
+    let list = |cell: Cell| -> Vec<String> {
+        match cell {
+            Cell::Json(Value::Array(items)) => items
+                .into_iter()
+                .map(|item| match item {
+                    Value::String(text) => text,
+                    other => other.to_string(),
+                })
+                .collect(),
+            cell => cell
+                .into_text()
+                .split(LIST_SEPARATOR)
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    };
+
+    for (field, cell) in fields.iter().zip(cells) {
+        match field.as_str() {
+            "id" => node.id = cell.into_text(),
+            "type" => node.r#type = cell.into_text(),
+            "domain" => node.domain = cell.into_text(),
+            "title" => node.title = cell.into_text(),
+            "status" => node.status = optional(cell),
+            "content" => node.content = optional(cell),
+            "formal" => node.formal = optional(cell),
+            "tags" => node.tags = list(cell),
+            "sources" => node.sources = list(cell),
+            "created" => node.created = optional(cell),
+            "updated" => node.updated = optional(cell),
+            "metadata" => node.metadata = cell.into_value().unwrap_or(Value::Null),
+            _ => {} // Ignore unknown fields
+        }
+    }
+
+    node
+}
+
+/// Serializes edges to TOON format
+///
+/// Edges are grouped by relation (sorted by name, edge order preserved within
+/// a group). Each group gets the smallest field set that loses no data:
+/// `f` and `t` always, `w` only if some edge in the group has a weight, and
+/// `domain` only if some edge has a non-empty domain. Metadata keys become
+/// extra columns (sorted); if some edge's metadata is not an object or uses
+/// a reserved column name, a single `metadata` column is written instead.
+/// The output round-trips through [`parse_toon`].
+///
+/// # Arguments
+///
+/// * `edges` - Edges to serialize
+///
+/// # Returns
+///
+/// TOON formatted string (empty for no edges)
+#[must_use]
+pub fn write_toon(edges: &[Edge]) -> String {
+    let mut groups: BTreeMap<&str, Vec<&Edge>> = BTreeMap::new();
+    for edge in edges {
+        groups.entry(edge.relation.as_str()).or_default().push(edge);
+    }
+
+    let tables: Vec<String> = groups
+        .into_iter()
+        .map(|(relation, group)| {
+            let has_weight = group.iter().any(|edge| edge.weight.is_some());
+            let has_domain = group.iter().any(|edge| !edge.domain.is_empty());
+
+            let whole_metadata = group.iter().any(|edge| match &edge.metadata {
+                None => false,
+                Some(Value::Object(entries)) => entries
+                    .keys()
+                    .any(|key| EDGE_FIELDS.contains(&key.as_str())),
+                Some(_) => true,
             });
+            let metadata_keys: BTreeSet<&str> = if whole_metadata {
+                BTreeSet::new()
+            } else {
+                group
+                    .iter()
+                    .filter_map(|edge| edge.metadata.as_ref()?.as_object())
+                    .flat_map(|entries| entries.keys().map(String::as_str))
+                    .collect()
+            };
+
+            let mut fields = vec!["f", "t"];
+            if has_weight {
+                fields.push("w");
+            }
+            if has_domain {
+                fields.push("domain");
+            }
+            if whole_metadata {
+                fields.push("metadata");
+            }
+            fields.extend(&metadata_keys);
+
+            let rows = group.into_iter().map(|edge| {
+                let mut cells = vec![encode_text(&edge.from), encode_text(&edge.to)];
+                if has_weight {
+                    cells.push(edge.weight.map(|w| w.to_string()).unwrap_or_default());
+                }
+                if has_domain {
+                    cells.push(encode_text(&edge.domain));
+                }
+                if whole_metadata {
+                    cells.push(edge.metadata.as_ref().map(encode_value).unwrap_or_default());
+                }
+                for key in &metadata_keys {
+                    cells.push(
+                        edge.metadata
+                            .as_ref()
+                            .and_then(|metadata| metadata.get(*key))
+                            .map(encode_value)
+                            .unwrap_or_default(),
+                    );
+                }
+                cells
+            });
+            write_table(relation, &fields, rows)
+        })
+        .collect();
+
+    tables.join("\n")
+}
+
+/// Serializes nodes and edges to one TOON document
+///
+/// The `nodes` table comes first, followed by the relation tables of
+/// [`write_toon`]. Optional node columns (`status`, `content`, `formal`,
+/// `tags`, `sources`, `created`, `updated`, `metadata`) are included only if some
+/// node has a value; list cells are joined with `|` (or written as a JSON
+/// array if an item would not survive the split) and metadata is written as
+/// a JSON cell.
+///
+/// # Arguments
+///
+/// * `document` - Nodes and edges to serialize
+///
+/// # Returns
+///
+/// TOON formatted string that [`parse_toon_document`] reads back
+#[must_use]
+pub fn write_toon_document(document: &ToonDocument) -> String {
+    let edges = write_toon(&document.edges);
+    if document.nodes.is_empty() {
+        return edges;
+    }
+
+    let mut fields = NODE_FIELDS.to_vec();
+    for field in OPTIONAL_NODE_FIELDS {
+        if document
+            .nodes
+            .iter()
+            .any(|node| !node_cell(node, field).is_empty())
+        {
+            fields.push(field);
+        }
+    }
+
+    let rows = document
+        .nodes
+        .iter()
+        .map(|node| fields.iter().map(|field| node_cell(node, field)).collect());
+    let table = write_table(NODES_TABLE, &fields, rows);
+
+    if edges.is_empty() {
+        table
+    } else {
+        format!("{table}\n{edges}")
+    }
+}
+
+/// Returns the encoded cell of a node column (empty if absent)
+fn node_cell(node: &Node, field: &str) -> String {
+    let optional = |value: &Option<String>| value.as_deref().map(encode_text).unwrap_or_default();
+    match field {
+        "id" => encode_text(&node.id),
+        "type" => encode_text(&node.r#type),
+        "domain" => encode_text(&node.domain),
+        "title" => encode_text(&node.title),
+        "status" => optional(&node.status),
+        "content" => optional(&node.content),
+        "formal" => optional(&node.formal),
+        "tags" => encode_list(&node.tags),
+        "sources" => encode_list(&node.sources),
+        "created" => optional(&node.created),
+        "updated" => optional(&node.updated),
+        "metadata" if !node.metadata.is_null() => encode_value(&node.metadata),
+        _ => String::new(),
+    }
+}
+
+/// Encodes a list cell, joined with [`LIST_SEPARATOR`] when every item reads
+/// back unchanged from the split and as a JSON array cell otherwise
+fn encode_list(items: &[String]) -> String {
+    let splits_cleanly = items
+        .iter()
+        .all(|item| !item.is_empty() && !item.contains(LIST_SEPARATOR) && item.trim() == item);
+    if splits_cleanly {
+        encode_text(&items.join(&LIST_SEPARATOR.to_string()))
+    } else {
+        encode_value(&Value::from(items))
+    }
+}
+
+/// Writes one table: header line followed by one line per row of encoded cells
+fn write_table(
+    name: &str,
+    fields: &[&str],
+    rows: impl ExactSizeIterator<Item = Vec<String>>,
+) -> String {
+    let mut table = format!("{name}[{}]{{{}}}:\n", rows.len(), fields.join(","));
+    for cells in rows {
+        table.push_str(&cells.join(","));
+        table.push('\n');
+    }
+    table
+}
+
+/// Encodes a JSON value as a metadata cell
+///
+/// Strings are quoted whenever the bare form would read back as something
+/// else (empty, a number, `true`, a JSON cell, ...).
+fn encode_value(value: &Value) -> String {
+    match value {
+        Value::String(text) => {
+            if Cell::Bare(text.clone()).into_value().as_ref() == Some(value) {
+                encode_text(text)
+            } else {
+                quote(text)
+            }
         }
+        other => other.to_string(),
+    }
+}
+
+/// Encodes a text cell, quoting it if it would not survive [`split_row`] bare
+///
+/// Newlines are written as `\n` escapes so every row stays on one line.
+fn encode_text(value: &str) -> String {
+    let needs_quotes = value.contains([',', '"', '\n', '\r', '{'])
+        || value.starts_with(|c: char| c == '[' || c.is_whitespace())
+        || value.ends_with(char::is_whitespace);
+    if needs_quotes {
+        quote(value)
+    } else {
+        value.to_string()
     }
+}
 
-    Ok(edges)
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
 }
 
 /// Parses edges from JSONL format
@@ -118,22 +783,34 @@ pub fn parse_toon(toon_content: &str) -> Result<Vec<Edge>, String> {
 /// # Errors
 ///
 /// Returns error if JSON is malformed
-pub fn parse_jsonl(jsonl_content: &str) -> Result<Vec<Edge>, String> {
+pub fn parse_jsonl(jsonl_content: &str) -> Result<Vec<Edge>> {
+    let (edges, errors) = parse_jsonl_lenient(jsonl_content);
+
+    errors.into_iter().next().map_or(Ok(edges), Err)
+}
+
+/// Parses edges from JSONL format, skipping malformed lines
+///
+/// # Returns
+///
+/// Every edge that parsed, plus one located `Json` error per bad line
+#[must_use]
+pub fn parse_jsonl_lenient(jsonl_content: &str) -> (Vec<Edge>, Vec<Error>) {
     let mut edges = Vec::new();
+    let mut errors = Vec::new();
 
     for (line_num, line) in jsonl_content.lines().enumerate() {
-        let line = line.trim();
-        if line.is_empty() {
+        if line.trim().is_empty() {
             continue;
         }
 
-        let edge: Edge = serde_json::from_str(line)
-            .map_err(|e| format!("Line {}: JSON parsing error: {e}", line_num + 1))?;
-
-        edges.push(edge);
+        match serde_json::from_str(line) {
+            Ok(edge) => edges.push(edge),
+            Err(e) => errors.push(Error::from_json(&e).at(line_num + 1, e.column())),
+        }
     }
 
-    Ok(edges)
+    (edges, errors)
 }
 
 #[cfg(test)]
@@ -142,7 +819,8 @@ mod tests {
 
     #[test]
     fn test_parse_jsonl_single_edge() {
-        let jsonl = r#"{"f":"abc123","t":"def456","relation":"supports","domain":"philosophy","w":0.9}"#;
+        let jsonl =
+            r#"{"f":"abc123","t":"def456","relation":"supports","domain":"philosophy","w":0.9}"#;
         let result = parse_jsonl(jsonl);
 
         assert!(result.is_ok());
@@ -191,11 +869,38 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_jsonl_lenient_keeps_valid_lines() {
+        let jsonl = r#"{"f":"a","t":"b","relation":"supports","domain":"x"}
+{"f":"c"}
+not json
+{"f":"d","t":"e","relation":"supports","domain":"x"}"#;
+
+        let (edges, errors) = parse_jsonl_lenient(jsonl);
+
+        assert_eq!(edges.len(), 2);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].location().line, Some(2));
+        assert_eq!(errors[1].location().line, Some(3));
+    }
+
+    #[test]
+    fn test_parse_jsonl_error_location() {
+        let jsonl =
+            "{\"f\":\"a\",\"t\":\"b\",\"relation\":\"supports\",\"domain\":\"x\"}\n\n{\"f\":\"c\"}";
+
+        let err = parse_jsonl(jsonl).unwrap_err();
+
+        assert_eq!(err.kind(), "json");
+        assert_eq!(err.location().line, Some(3));
+        assert!(err.message().contains("missing field"));
+    }
+
     #[test]
     fn test_parse_toon_single_relation() {
-        let toon = r#"supports[2]{f,t,w,domain}:
+        let toon = r"supports[2]{f,t,w,domain}:
 abc123,def456,0.9,philosophy
-ghi789,jkl012,0.85,philosophy"#;
+ghi789,jkl012,0.85,philosophy";
 
         let result = parse_toon(toon);
 
@@ -211,11 +916,11 @@ ghi789,jkl012,0.85,philosophy"#;
 
     #[test]
     fn test_parse_toon_multiple_relations() {
-        let toon = r#"supports[1]{f,t,w,domain}:
+        let toon = r"supports[1]{f,t,w,domain}:
 abc123,def456,0.9,philosophy
 
 proves[1]{f,t,w,domain}:
-ghi789,jkl012,1.0,mathematics"#;
+ghi789,jkl012,1.0,mathematics";
 
         let result = parse_toon(toon);
 
@@ -228,8 +933,8 @@ ghi789,jkl012,1.0,mathematics"#;
 
     #[test]
     fn test_parse_toon_without_weight() {
-        let toon = r#"supports[1]{f,t,domain}:
-abc123,def456,philosophy"#;
+        let toon = r"supports[1]{f,t,domain}:
+abc123,def456,philosophy";
 
         let result = parse_toon(toon);
 
@@ -242,42 +947,56 @@ abc123,def456,philosophy"#;
 
     #[test]
     fn test_parse_toon_malformed_header() {
-        let toon = r#"INVALID HEADER FORMAT:
-abc123,def456,philosophy"#;
+        let toon = r"INVALID HEADER FORMAT:
+abc123,def456,philosophy";
 
         let result = parse_toon(toon);
 
         assert!(result.is_err());
         let err = result.unwrap_err();
-        assert!(err.contains("header") || err.contains("format"));
+        assert_eq!(err.kind(), "toon");
+        assert_eq!(err.location().line, Some(1));
+        assert!(err.message().contains("header"));
     }
 
     #[test]
     fn test_parse_toon_wrong_field_count() {
-        let toon = r#"supports[1]{f,t,w,domain}:
-abc123,def456,0.9"#; // Missing domain field
+        let toon = r"supports[1]{f,t,w,domain}:
+abc123,def456,0.9"; // Missing domain field
 
         let result = parse_toon(toon);
 
         assert!(result.is_err());
         let err = result.unwrap_err();
-        assert!(err.contains("field") || err.contains("expected"));
+        assert!(err.message().contains("expected 4 fields"));
+        assert_eq!(
+            err.to_string(),
+            "2:1: toon error: Field count mismatch: expected 4 fields, got 3"
+        );
     }
 
     #[test]
     fn test_parse_toon_from_project_file() {
         // Integration test: parse actual TOON file from project
         use std::fs;
-        let project_root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let project_root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap();
         let toon_path = project_root.join("dist/edges.toon");
 
         if toon_path.exists() {
             let toon_content = fs::read_to_string(toon_path).unwrap();
             let result = parse_toon(&toon_content);
 
-            assert!(result.is_ok(), "Failed to parse project TOON file: {:?}", result);
+            assert!(
+                result.is_ok(),
+                "Failed to parse project TOON file: {result:?}"
+            );
             let edges = result.unwrap();
-            assert!(edges.len() >= 20, "Expected at least 20 edges from sample graph");
+            assert!(
+                edges.len() >= 20,
+                "Expected at least 20 edges from sample graph"
+            );
 
             // Verify all edges have valid data
             for edge in &edges {
@@ -289,6 +1008,403 @@ abc123,def456,0.9"#; // Missing domain field
         }
     }
 
+    fn create_test_edge(from: &str, to: &str, relation: &str, weight: Option<f32>) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            relation: relation.to_string(),
+            domain: "philosophy".to_string(),
+            weight,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_write_toon_groups_by_relation() {
+        let edges = vec![
+            create_test_edge("a", "b", "supports", Some(0.9)),
+            create_test_edge("c", "d", "attacks", None),
+            create_test_edge("e", "f", "supports", None),
+        ];
+
+        let toon = write_toon(&edges);
+
+        assert_eq!(
+            toon,
+            "attacks[1]{f,t,domain}:\nc,d,philosophy\n\nsupports[2]{f,t,w,domain}:\na,b,0.9,philosophy\ne,f,,philosophy\n"
+        );
+    }
+
+    #[test]
+    fn test_write_toon_minimal_fields() {
+        let mut edge = create_test_edge("a", "b", "cites", None);
+        edge.domain = String::new();
+
+        assert_eq!(write_toon(&[edge]), "cites[1]{f,t}:\na,b\n");
+        assert_eq!(write_toon(&[]), "");
+    }
+
+    #[test]
+    fn test_write_toon_roundtrip() {
+        let edges = vec![
+            create_test_edge("abc123", "def456", "proves", Some(1.0)),
+            create_test_edge("abc123", "ghi789", "supports", Some(0.123_456_7)),
+            create_test_edge("ghi789", "def456", "supports", None),
+        ];
+
+        let parsed = parse_toon(&write_toon(&edges)).unwrap();
+
+        assert_eq!(parsed.len(), edges.len());
+        for edge in &edges {
+            let found = parsed
+                .iter()
+                .find(|p| p.from == edge.from && p.to == edge.to)
+                .unwrap();
+            assert_eq!(found.relation, edge.relation);
+            assert_eq!(found.domain, edge.domain);
+            assert_eq!(found.weight, edge.weight);
+        }
+    }
+
+    #[test]
+    fn test_parse_toon_document_mixed() {
+        let toon = r"nodes[2]{id,type,domain,title}:
+abc123,proposition,philosophy,Knowledge requires safety
+def456,theorem,mathematics,Fundamental Theorem
+
+supports[1]{f,t,w,domain}:
+abc123,def456,0.9,philosophy";
+
+        let document = parse_toon_document(toon).unwrap();
+
+        assert_eq!(document.nodes.len(), 2);
+        assert_eq!(document.nodes[0].title, "Knowledge requires safety");
+        assert_eq!(document.nodes[1].r#type, "theorem");
+        assert_eq!(document.edges.len(), 1);
+
+        // Edge-only view skips the node table
+        assert_eq!(parse_toon(toon).unwrap().len(), 1);
+
+        let store = document.into_graph_store();
+        assert_eq!(store.node_count(), 2);
+        assert_eq!(store.neighbors("abc123", 1).len(), 1);
+    }
+
+    #[test]
+    fn test_parse_toon_document_requires_node_fields() {
+        let toon = r"nodes[1]{id,type,title}:
+abc123,proposition,Missing domain";
+
+        let err = parse_toon_document(toon).unwrap_err();
+
+        assert!(err.message().contains("domain"));
+    }
+
+    #[test]
+    fn test_write_toon_document_roundtrip() {
+        let node = |id: &str| Node {
+            id: id.to_string(),
+            r#type: "proposition".to_string(),
+            domain: "philosophy".to_string(),
+            title: format!("Node {id}"),
+            content: None,
+            formal: None,
+            tags: vec![],
+            metadata: serde_json::Value::Null,
+            sources: vec![],
+            created: None,
+            updated: None,
+            status: None,
+        };
+        let mut first = node("abc123");
+        first.formal = Some("K(p) -> p".to_string());
+        first.tags = vec!["epistemology".to_string(), "safety".to_string()];
+        let document = ToonDocument {
+            nodes: vec![first, node("def456")],
+            edges: vec![create_test_edge("abc123", "def456", "supports", Some(0.9))],
+        };
+
+        let toon = write_toon_document(&document);
+
+        assert!(toon.starts_with("nodes[2]{id,type,domain,title,formal,tags}:\n"));
+        assert!(toon
+            .contains("abc123,proposition,philosophy,Node abc123,K(p) -> p,epistemology|safety\n"));
+        assert_eq!(parse_toon_document(&toon).unwrap(), document);
+    }
+
+    #[test]
+    fn test_write_toon_document_list_with_separator_roundtrip() {
+        let node = Node {
+            id: "abc123".to_string(),
+            r#type: "proposition".to_string(),
+            domain: "philosophy".to_string(),
+            title: "Safety".to_string(),
+            content: None,
+            formal: None,
+            tags: vec!["epistemology".to_string()],
+            metadata: serde_json::Value::Null,
+            sources: vec!["Smith | Jones 2020".to_string(), " Williams ".to_string()],
+            created: None,
+            updated: None,
+            status: None,
+        };
+        let document = ToonDocument {
+            nodes: vec![node],
+            edges: vec![],
+        };
+
+        let toon = write_toon_document(&document);
+
+        assert!(toon.contains(r#",epistemology,["Smith | Jones 2020"," Williams "]"#));
+        assert_eq!(parse_toon_document(&toon).unwrap(), document);
+    }
+
+    #[test]
+    fn test_toon_document_from_graph() {
+        let document = ToonDocument {
+            nodes: vec![],
+            edges: vec![create_test_edge("a", "b", "supports", None)],
+        };
+        let store = document.clone().into_graph_store();
+
+        assert_eq!(ToonDocument::from_graph(&store), document);
+        assert_eq!(write_toon_document(&document), write_toon(&document.edges));
+    }
+
+    #[test]
+    fn test_parse_toon_quoted_cells() {
+        let toon = r#"nodes[2]{id,type,domain,title}:
+abc123,proposition,philosophy,"Knowledge, safety, and luck"
+def456,concept,philosophy, "Say \"hi\"\nthen leave" 
+
+supports[1]{f,t,domain}:
+abc123,def456,bridge:phil→math"#;
+
+        let document = parse_toon_document(toon).unwrap();
+
+        assert_eq!(document.nodes[0].title, "Knowledge, safety, and luck");
+        assert_eq!(document.nodes[1].title, "Say \"hi\"\nthen leave");
+        assert_eq!(document.edges[0].domain, "bridge:phil→math");
+    }
+
+    #[test]
+    fn test_parse_toon_quoted_cell_spans_lines() {
+        let toon = "nodes[1]{id,type,domain,title,content}:\nabc123,proposition,philosophy,Title,\"first line\nsecond, line:\"\n";
+
+        let document = parse_toon_document(toon).unwrap();
+
+        assert_eq!(document.nodes.len(), 1);
+        assert_eq!(
+            document.nodes[0].content.as_deref(),
+            Some("first line\nsecond, line:")
+        );
+    }
+
+    #[test]
+    fn test_parse_toon_row_ending_in_colon_is_not_header() {
+        let toon = r#"supports[1]{f,t,domain}:
+abc123,def456,"note:""#;
+
+        let edges = parse_toon(toon).unwrap();
+
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].domain, "note:");
+    }
+
+    #[test]
+    fn test_parse_toon_quoting_errors() {
+        let unterminated = "supports[1]{f,t,domain}:\nabc123,def456,\"open";
+        let err = parse_toon(unterminated).unwrap_err();
+        assert!(err.message().contains("Unterminated"));
+        assert_eq!(err.location().line, Some(2));
+
+        let bad_escape = r#"supports[1]{f,t,domain}:
+abc123,def456,"\q""#;
+        let err = parse_toon(bad_escape).unwrap_err();
+        assert!(err.message().contains("escape"));
+        assert_eq!(err.location().column, Some(16));
+
+        let trailing = r#"supports[1]{f,t,domain}:
+abc123,def456,"x"y"#;
+        let err = parse_toon(trailing).unwrap_err();
+        assert!(err.message().contains("closing quote"));
+        assert_eq!(err.location().column, Some(18));
+    }
+
+    #[test]
+    fn test_write_toon_adversarial_roundtrip() {
+        let titles = [
+            "Knowledge, safety, and luck",
+            "bridge:phil→math",
+            "Ends with colon:",
+            "Quote \" and backslash \\",
+            "Line one\nline two\r\n\ttabbed",
+            "  padded  ",
+            "{braced,}",
+            "",
+        ];
+        let nodes: Vec<Node> = titles
+            .iter()
+            .enumerate()
+            .map(|(idx, title)| Node {
+                id: format!("n{idx}"),
+                r#type: "proposition".to_string(),
+                domain: "bridge:phil→math".to_string(),
+                title: (*title).to_string(),
+                content: Some(format!("{title}, again")),
+                formal: None,
+                tags: vec![],
+                metadata: serde_json::Value::Null,
+                sources: vec![],
+                created: None,
+                updated: None,
+                status: None,
+            })
+            .collect();
+        let mut edge = create_test_edge("n0", "n1", "supports", Some(0.5));
+        edge.domain = "bridge:phil→math, \"quoted\"".to_string();
+        let document = ToonDocument {
+            nodes,
+            edges: vec![edge],
+        };
+
+        let toon = write_toon_document(&document);
+
+        // Every row stays on its own line
+        assert_eq!(
+            toon.lines().filter(|l| !l.is_empty()).count(),
+            2 + titles.len() + 1
+        );
+        assert_eq!(parse_toon_document(&toon).unwrap(), document);
+    }
+
+    #[test]
+    fn test_parse_toon_extra_columns_to_metadata() {
+        let toon = r#"supports[2]{f,t,domain,conditions,strength,note}:
+abc123,def456,philosophy,{"requires": ["safety", "sensitivity"]},3,"42"
+ghi789,def456,philosophy,,true,plain text"#;
+
+        let edges = parse_toon(toon).unwrap();
+
+        assert_eq!(
+            edges[0].metadata,
+            Some(serde_json::json!({
+                "conditions": {"requires": ["safety", "sensitivity"]},
+                "strength": 3,
+                "note": "42"
+            }))
+        );
+        assert_eq!(
+            edges[1].metadata,
+            Some(serde_json::json!({"strength": true, "note": "plain text"}))
+        );
+    }
+
+    #[test]
+    fn test_parse_toon_metadata_column() {
+        let toon = r#"supports[1]{f,t,metadata}:
+abc123,def456,{"w": "shadowed", "source": "x"}"#;
+
+        let edges = parse_toon(toon).unwrap();
+
+        assert_eq!(edges[0].weight, None);
+        assert_eq!(
+            edges[0].metadata,
+            Some(serde_json::json!({"w": "shadowed", "source": "x"}))
+        );
+    }
+
+    #[test]
+    fn test_parse_toon_invalid_json_cell() {
+        let toon = "supports[1]{f,t,extra}:\nabc123,def456,{not json}";
+
+        let err = parse_toon(toon).unwrap_err();
+        assert!(err.message().contains("JSON"));
+        assert_eq!(err.location().line, Some(2));
+        assert_eq!(err.location().column, Some(15));
+    }
+
+    #[test]
+    fn test_parse_toon_validates_declared_count() {
+        let too_few = r"supports[3]{f,t}:
+a,b
+c,d
+
+proves[1]{f,t}:
+e,f";
+        let err = parse_toon(too_few).unwrap_err();
+        assert!(err.message().contains("supports") && err.message().contains("declares 3"));
+        assert_eq!(err.location().line, Some(1));
+
+        let too_many = "proves[1]{f,t}:\na,b\nc,d";
+        assert!(parse_toon(too_many)
+            .unwrap_err()
+            .message()
+            .contains("declares 1"));
+
+        let bad_count = "proves[x]{f,t}:\na,b";
+        assert!(parse_toon(bad_count)
+            .unwrap_err()
+            .message()
+            .contains("count"));
+
+        // Count may be omitted
+        assert_eq!(parse_toon("proves{f,t}:\na,b").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_write_toon_metadata_roundtrip() {
+        let mut first = create_test_edge("a", "b", "supports", Some(0.5));
+        first.metadata = Some(serde_json::json!({
+            "conditions": ["safety", "no defeaters"],
+            "count": 2,
+            "label": "true",
+            "empty": "",
+            "nested": {"k": [1, {"x": null}]}
+        }));
+        let second = create_test_edge("c", "d", "supports", None);
+        let mut reserved = create_test_edge("e", "f", "attacks", None);
+        reserved.metadata = Some(serde_json::json!({"w": 1, "domain": "x"}));
+        let mut scalar = create_test_edge("g", "h", "cites", None);
+        scalar.metadata = Some(serde_json::json!("just a string"));
+        let edges = vec![first, second, reserved, scalar];
+
+        let toon = write_toon(&edges);
+
+        assert!(toon.contains("supports[2]{f,t,w,domain,conditions,count,empty,label,nested}:"));
+        assert!(toon.contains("attacks[1]{f,t,domain,metadata}:"));
+
+        let mut parsed = parse_toon(&toon).unwrap();
+        parsed.sort_by(|a, b| a.from.cmp(&b.from));
+        assert_eq!(parsed, edges);
+    }
+
+    #[test]
+    fn test_write_toon_document_node_metadata() {
+        let document = ToonDocument {
+            nodes: vec![Node {
+                id: "abc123".to_string(),
+                r#type: "proposition".to_string(),
+                domain: "philosophy".to_string(),
+                title: "[Draft] Safety".to_string(),
+                content: None,
+                formal: None,
+                tags: vec![],
+                metadata: serde_json::json!({"importance": 7}),
+                sources: vec![],
+                created: None,
+                updated: None,
+                status: None,
+            }],
+            edges: vec![],
+        };
+
+        let toon = write_toon_document(&document);
+
+        assert!(toon.contains(r#"abc123,proposition,philosophy,"[Draft] Safety",{"importance":7}"#));
+        assert_eq!(parse_toon_document(&toon).unwrap(), document);
+    }
+
     #[test]
     fn test_parse_toon_empty_input() {
         let result = parse_toon("");
@@ -0,0 +1,400 @@
+//! Writing refactorings back to repository files
+//!
+//! [`apply_refactor`] takes the [`RefactorReport`] of an in-memory change
+//! (rename, merge, split) and edits only the files it touches: node files
+//! are rewritten from the refactored graph, keeping keys the `Node` struct
+//! does not carry, and edge lines are patched in place in their JSONL file.
+//! Every touched file is left in canonical form (see [`crate::format`]).
+
+use super::loader::{collect_files, read_file, EDGES_DIR, NODES_DIR};
+use crate::error::{Error, Result};
+use crate::format::{format_edges_jsonl_as, format_node_json_as};
+use crate::graph::refactor::RefactorReport;
+use crate::graph::{Edge, GraphStore, Node};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Node keys that are written from the `Node` struct; others are kept as found
+pub(crate) const NODE_FIELDS: &[&str] = &[
+    "id", "type", "domain", "title", "status", "content", "formal", "tags", "metadata", "sources",
+    "created", "updated",
+];
+
+/// Edge keys that are written from the `Edge` struct; others are kept as found
+pub(crate) const EDGE_FIELDS: &[&str] = &["f", "t", "relation", "domain", "w", "metadata"];
+
+/// Files touched by [`apply_refactor`]
+#[derive(Debug, Default)]
+pub struct WriteReport {
+    /// Files created or rewritten
+    pub written: Vec<PathBuf>,
+    /// Files deleted
+    pub removed: Vec<PathBuf>,
+    /// Changes that could not be applied; the rest were still written
+    pub diagnostics: Vec<Error>,
+}
+
+/// Writes a refactoring back to the repository under `root`
+///
+/// `node_files` maps node IDs to the files they were loaded from (see
+/// [`super::loader::LoadReport::node_files`]); new nodes get
+/// `nodes/<id>.json`, and a renamed node's file is renamed too if it was
+/// named after the old ID. Edges are only patched in JSONL files; an edge
+/// change that matches no JSONL line (e.g. one loaded from TOON) is
+/// reported as a diagnostic.
+#[allow(clippy::implicit_hasher)]
+#[must_use]
+pub fn apply_refactor(
+    root: &Path,
+    graph: &GraphStore,
+    report: &RefactorReport,
+    node_files: &HashMap<String, PathBuf>,
+) -> WriteReport {
+    let mut written = WriteReport::default();
+    let nodes_dir = root.join(NODES_DIR);
+
+    let file_of = |id: &str| {
+        node_files
+            .get(id)
+            .cloned()
+            .ok_or_else(|| Error::integrity(format!("No file found for node '{id}'")))
+    };
+
+    for (old_id, new_id) in &report.renamed_nodes {
+        let result = file_of(old_id).and_then(|path| {
+            let target = if path.file_stem().is_some_and(|stem| stem == old_id.as_str()) {
+                path.with_file_name(format!("{new_id}.json"))
+            } else {
+                path.clone()
+            };
+            write_node(graph, new_id, Some(&path), &target)?;
+            if target != path {
+                std::fs::remove_file(&path).map_err(|e| Error::from(e).with_path(&path))?;
+                written.removed.push(path);
+            }
+            Ok(target)
+        });
+        record(&mut written, result);
+    }
+
+    for id in &report.updated_nodes {
+        let result = file_of(id).and_then(|path| {
+            write_node(graph, id, Some(&path), &path)?;
+            Ok(path)
+        });
+        record(&mut written, result);
+    }
+
+    for id in &report.added_nodes {
+        let path = nodes_dir.join(format!("{id}.json"));
+        let result = if path.exists() {
+            Err(
+                Error::integrity(format!("File already exists for new node '{id}'"))
+                    .with_path(&path),
+            )
+        } else {
+            write_node(graph, id, None, &path).map(|()| path)
+        };
+        record(&mut written, result);
+    }
+
+    for id in &report.removed_nodes {
+        match file_of(id).and_then(|path| {
+            std::fs::remove_file(&path).map_err(|e| Error::from(e).with_path(&path))?;
+            Ok(path)
+        }) {
+            Ok(path) => written.removed.push(path),
+            Err(err) => written.diagnostics.push(err),
+        }
+    }
+
+    patch_edges(root, report, &mut written);
+    written
+}
+
+fn record(written: &mut WriteReport, result: Result<PathBuf>) {
+    match result {
+        Ok(path) => written.written.push(path),
+        Err(err) => written.diagnostics.push(err),
+    }
+}
+
+/// Writes node `id` of `graph` to `target`, keeping unknown keys of `source`
+fn write_node(graph: &GraphStore, id: &str, source: Option<&Path>, target: &Path) -> Result<()> {
+    let node = graph
+        .get_node(id)
+        .ok_or_else(|| Error::integrity(format!("Unknown node '{id}'")))?;
+
+    let written = match source {
+        Some(path) => read_file(path)?,
+        None => String::new(),
+    };
+    let mut raw = match source {
+        Some(path) => match serde_json::from_str(&written) {
+            Ok(Value::Object(raw)) => raw,
+            Ok(_) => Map::new(),
+            Err(e) => return Err(Error::from_json(&e).with_path(path)),
+        },
+        None => Map::new(),
+    };
+    overlay_node(&mut raw, node)?;
+
+    let formatted = format_node_json_as(&Value::Object(raw).to_string(), &written)?;
+    std::fs::write(target, formatted).map_err(|e| Error::from(e).with_path(target))
+}
+
+/// Replaces the struct-backed keys of `raw` with the fields of `node`
+pub(crate) fn overlay_node(raw: &mut Map<String, Value>, node: &Node) -> Result<()> {
+    let Value::Object(mut fields) = serde_json::to_value(node)? else {
+        return Err(Error::json("node did not serialize to an object"));
+    };
+
+    for &key in NODE_FIELDS {
+        match fields.remove(key) {
+            Some(Value::Null) | None => {
+                raw.remove(key);
+            }
+            Some(Value::Array(items)) if items.is_empty() => {
+                raw.remove(key);
+            }
+            Some(value) => {
+                raw.insert(key.to_string(), value);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Applies the edge changes and removals of `report` to the JSONL files
+fn patch_edges(root: &Path, report: &RefactorReport, written: &mut WriteReport) {
+    let mut changes: Vec<Option<(&Edge, &Edge)>> = report
+        .changed_edges
+        .iter()
+        .map(|change| Some((&change.before, &change.after)))
+        .collect();
+    let mut removals: Vec<Option<&Edge>> = report.removed_edges.iter().map(Some).collect();
+    if changes.is_empty() && removals.is_empty() {
+        return;
+    }
+
+    let files = collect_files(&root.join(EDGES_DIR), &["jsonl"], &mut written.diagnostics);
+    for path in files {
+        let source = match read_file(&path) {
+            Ok(source) => source,
+            Err(err) => {
+                written.diagnostics.push(err);
+                continue;
+            }
+        };
+
+        let mut lines = Vec::new();
+        let mut modified = false;
+        for line in source.lines() {
+            let parsed = serde_json::from_str::<Value>(line).ok().and_then(|value| {
+                let edge = serde_json::from_value::<Edge>(value.clone()).ok()?;
+                Some((value, edge))
+            });
+            let Some((Value::Object(mut raw), edge)) = parsed else {
+                lines.push(line.to_string());
+                continue;
+            };
+
+            if let Some(slot) = changes
+                .iter_mut()
+                .find(|slot| slot.is_some_and(|(before, _)| *before == edge))
+            {
+                let (_, after) = slot.take().unwrap_or_else(|| unreachable!());
+                overlay_edge(&mut raw, after);
+                lines.push(Value::Object(raw).to_string());
+                modified = true;
+            } else if let Some(slot) = removals
+                .iter_mut()
+                .find(|slot| slot.is_some_and(|removed| *removed == edge))
+            {
+                *slot = None;
+                modified = true;
+            } else {
+                lines.push(line.to_string());
+            }
+        }
+
+        if modified {
+            let mut content = lines.join("\n");
+            content.push('\n');
+            let result = format_edges_jsonl_as(&content, &source)
+                .and_then(|formatted| std::fs::write(&path, formatted).map_err(Error::from))
+                .map_err(|e| e.with_path(&path));
+            match result {
+                Ok(()) => written.written.push(path),
+                Err(err) => written.diagnostics.push(err),
+            }
+        }
+    }
+
+    let unmatched = changes
+        .into_iter()
+        .flatten()
+        .map(|(before, _)| before)
+        .chain(removals.into_iter().flatten());
+    for edge in unmatched {
+        written.diagnostics.push(Error::integrity(format!(
+            "Edge {} -[{}]-> {} was not found in any JSONL file and was not updated",
+            edge.from, edge.relation, edge.to
+        )));
+    }
+}
+
+/// Replaces the struct-backed keys of an edge line with the fields of `edge`
+pub(crate) fn overlay_edge(raw: &mut Map<String, Value>, edge: &Edge) {
+    raw.insert("f".to_string(), Value::from(edge.from.as_str()));
+    raw.insert("t".to_string(), Value::from(edge.to.as_str()));
+    raw.insert("relation".to_string(), Value::from(edge.relation.as_str()));
+    raw.insert("domain".to_string(), Value::from(edge.domain.as_str()));
+    match edge.weight {
+        // Through the shortest f32 text, so 0.9 is not written as 0.8999999761581421
+        Some(weight) => {
+            let weight = weight
+                .to_string()
+                .parse::<f64>()
+                .map_or(Value::Null, Value::from);
+            raw.insert("w".to_string(), weight);
+        }
+        None => {
+            raw.remove("w");
+        }
+    }
+    match &edge.metadata {
+        Some(metadata) => {
+            raw.insert("metadata".to_string(), metadata.clone());
+        }
+        None => {
+            raw.remove("metadata");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::refactor::WeightPolicy;
+    use crate::parsers::loader::load_repository_lenient;
+
+    fn write_repository(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("truth_mines_writer_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join(NODES_DIR)).unwrap();
+        std::fs::create_dir_all(root.join(EDGES_DIR)).unwrap();
+        for (id, extra) in [
+            (
+                "aaa111",
+                r#","namespace":"local","tags":["x"],"metadata":{"scale":1e15}"#,
+            ),
+            ("bbb222", r#","tags":["y"],"sources":["src"]"#),
+            ("ccc333", ""),
+        ] {
+            std::fs::write(
+                root.join(NODES_DIR).join(format!("{id}.json")),
+                format!(r#"{{"id":"{id}","type":"concept","domain":"philosophy","title":"{id}"{extra}}}"#),
+            )
+            .unwrap();
+        }
+        std::fs::write(
+            root.join(EDGES_DIR).join("supports.jsonl"),
+            concat!(
+                "{\"f\":\"aaa111\",\"t\":\"ccc333\",\"relation\":\"supports\",\"w\":0.9,\"domain\":\"philosophy\",\"metadata\":{\"scale\":1e26},\"note\":\"kept\"}\n",
+                "{\"f\":\"bbb222\",\"t\":\"ccc333\",\"relation\":\"supports\",\"w\":0.5,\"domain\":\"philosophy\"}\n",
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            root.join(EDGES_DIR).join("attacks.jsonl"),
+            "{\"f\":\"ccc333\",\"t\":\"aaa111\",\"relation\":\"attacks\",\"domain\":\"philosophy\"}\n",
+        )
+        .unwrap();
+        root
+    }
+
+    #[test]
+    fn test_apply_rename() {
+        let root = write_repository("rename");
+        let loaded = load_repository_lenient(&root);
+        let node_files = loaded.node_files();
+        let mut graph = loaded.into_graph_store();
+
+        let report = graph.rename_node("aaa111", "zzz999").unwrap();
+        let written = apply_refactor(&root, &graph, &report, &node_files);
+        assert!(written.diagnostics.is_empty(), "{:?}", written.diagnostics);
+        assert_eq!(
+            written.removed,
+            vec![root.join(NODES_DIR).join("aaa111.json")]
+        );
+
+        let renamed = std::fs::read_to_string(root.join(NODES_DIR).join("zzz999.json")).unwrap();
+        assert!(renamed.contains("\"id\": \"zzz999\""));
+        assert!(
+            renamed.contains("\"namespace\": \"local\""),
+            "unknown keys kept"
+        );
+        assert!(renamed.contains("\"scale\": 1e15"), "number text kept");
+
+        let supports =
+            std::fs::read_to_string(root.join(EDGES_DIR).join("supports.jsonl")).unwrap();
+        assert!(supports.contains(
+            "{\"f\":\"zzz999\",\"t\":\"ccc333\",\"relation\":\"supports\",\"w\":0.9,\"domain\":\"philosophy\",\"metadata\":{\"scale\":1e26},\"note\":\"kept\"}"
+        ));
+        let attacks = std::fs::read_to_string(root.join(EDGES_DIR).join("attacks.jsonl")).unwrap();
+        assert!(attacks.contains("\"t\":\"zzz999\""));
+
+        let reloaded = load_repository_lenient(&root);
+        assert!(reloaded.is_clean());
+        assert_eq!(reloaded.edges.len(), 3);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_apply_merge() {
+        let root = write_repository("merge");
+        let loaded = load_repository_lenient(&root);
+        let node_files = loaded.node_files();
+        let mut graph = loaded.into_graph_store();
+
+        let report = graph
+            .merge_nodes("aaa111", "bbb222", WeightPolicy::Mean)
+            .unwrap();
+        let written = apply_refactor(&root, &graph, &report, &node_files);
+        assert!(written.diagnostics.is_empty(), "{:?}", written.diagnostics);
+        assert!(!root.join(NODES_DIR).join("bbb222.json").exists());
+
+        let kept = std::fs::read_to_string(root.join(NODES_DIR).join("aaa111.json")).unwrap();
+        assert!(kept.contains("\"tags\": [\"x\", \"y\"]"));
+        assert!(kept.contains("\"sources\": [\"src\"]"));
+
+        let supports =
+            std::fs::read_to_string(root.join(EDGES_DIR).join("supports.jsonl")).unwrap();
+        assert_eq!(
+            supports,
+            "{\"f\":\"aaa111\",\"t\":\"ccc333\",\"relation\":\"supports\",\"w\":0.7,\"domain\":\"philosophy\",\"metadata\":{\"scale\":1e26},\"note\":\"kept\"}\n"
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_unmatched_edge_is_reported() {
+        let root = write_repository("unmatched");
+        let loaded = load_repository_lenient(&root);
+        let node_files = loaded.node_files();
+        let mut graph = loaded.into_graph_store();
+        std::fs::remove_file(root.join(EDGES_DIR).join("attacks.jsonl")).unwrap();
+
+        let report = graph.rename_node("aaa111", "zzz999").unwrap();
+        let written = apply_refactor(&root, &graph, &report, &node_files);
+        assert_eq!(written.diagnostics.len(), 1);
+        assert!(written.diagnostics[0].message().contains("-[attacks]->"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,12 +1,20 @@
 //! Style mapping from semantic properties to GPU visual properties
 
+use crate::error::{Error, Result};
 use crate::gpu::{GpuEdge, GpuNode};
 use crate::graph::{Edge, Node};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Name of the built-in base theme that `extends` can always resolve
+pub const DEFAULT_THEME: &str = "default";
+
 /// Style configuration loaded from TOML
-#[derive(Debug, Clone, Deserialize, Default)]
+///
+/// A style may declare `extends = "<theme>"` at the top level, in which case
+/// its tables are deep-merged over the named base theme: keys present in the
+/// overlay win, everything else is inherited.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct StyleConfig {
     #[serde(default)]
     colors: Colors,
@@ -14,7 +22,7 @@ pub struct StyleConfig {
     layout: LayoutConfig,
 }
 
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 struct Colors {
     #[serde(default)]
     domains: HashMap<String, Vec<u8>>,
@@ -22,7 +30,7 @@ struct Colors {
     relations: HashMap<String, Vec<u8>>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct LayoutConfig {
     #[serde(default = "default_depth_spacing")]
     depth_spacing: f32,
@@ -43,15 +51,73 @@ impl Default for LayoutConfig {
 impl StyleConfig {
     /// Loads style config from TOML string
     ///
+    /// `extends` may only name the built-in [`DEFAULT_THEME`]; use
+    /// [`StyleConfig::from_toml_with_themes`] to inherit from other themes.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if TOML is malformed or extends an unknown theme
+    pub fn from_toml(toml_str: &str) -> Result<Self> {
+        Self::from_toml_with_themes(toml_str, &HashMap::new())
+    }
+
+    /// Loads style config from TOML string, resolving `extends` against `themes`
+    ///
+    /// A theme registered under [`DEFAULT_THEME`] shadows the built-in default.
+    ///
+    /// # Arguments
+    ///
+    /// * `toml_str` - TOML style configuration string
+    /// * `themes` - Named base themes that `extends` may refer to
+    ///
     /// # Errors
     ///
-    /// Returns error if TOML is malformed
-    pub fn from_toml(toml_str: &str) -> Result<Self, String> {
+    /// Returns error if TOML is malformed or extends an unknown theme
+    #[allow(clippy::implicit_hasher)]
+    pub fn from_toml_with_themes(toml_str: &str, themes: &HashMap<String, Self>) -> Result<Self> {
         if toml_str.trim().is_empty() {
             return Ok(Self::default());
         }
 
-        toml::from_str(toml_str).map_err(|e| format!("TOML parsing error: {e}"))
+        let mut overlay: toml::Table =
+            toml::from_str(toml_str).map_err(|e| Error::from_toml(&e, toml_str))?;
+
+        let base = match overlay.remove("extends") {
+            None => Self::default(),
+            Some(toml::Value::String(name)) => match themes.get(&name) {
+                Some(theme) => theme.clone(),
+                None if name == DEFAULT_THEME => Self::default(),
+                None => return Err(Error::toml(format!("Unknown base theme: {name}"))),
+            },
+            Some(other) => {
+                return Err(Error::toml(format!(
+                    "`extends` must be a string, got {other}"
+                )))
+            }
+        };
+
+        base.merge_table(overlay)
+    }
+
+    /// Returns a new style with `overlay_toml` deep-merged over this one
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the overlay TOML is malformed
+    pub fn extend(&self, overlay_toml: &str) -> Result<Self> {
+        let overlay: toml::Table =
+            toml::from_str(overlay_toml).map_err(|e| Error::from_toml(&e, overlay_toml))?;
+        self.merge_table(overlay)
+    }
+
+    fn merge_table(&self, overlay: toml::Table) -> Result<Self> {
+        let mut merged = toml::Table::try_from(self)
+            .map_err(|e| Error::toml(format!("Serialization failed: {e}")))?;
+        deep_merge(&mut merged, overlay);
+
+        toml::Value::Table(merged)
+            .try_into()
+            .map_err(|e: toml::de::Error| Error::toml(e.message()))
     }
 
     /// Maps a `Node` to `GpuNode` using style configuration
@@ -105,7 +171,7 @@ impl StyleConfig {
 
     // Helper methods
 
-    fn get_domain_color(&self, domain: &str) -> [f32; 4] {
+    pub(crate) fn get_domain_color(&self, domain: &str) -> [f32; 4] {
         if let Some(rgb) = self.colors.domains.get(domain) {
             if rgb.len() >= 3 {
                 return [
@@ -121,7 +187,7 @@ impl StyleConfig {
         [0.5, 0.5, 0.5, 1.0]
     }
 
-    fn get_relation_color(&self, relation: &str) -> [f32; 4] {
+    pub(crate) fn get_relation_color(&self, relation: &str) -> [f32; 4] {
         if let Some(rgb) = self.colors.relations.get(relation) {
             if rgb.len() >= 3 {
                 return [
@@ -174,6 +240,23 @@ impl StyleConfig {
     }
 }
 
+/// Recursively merges `overlay` into `base`
+///
+/// Nested tables are merged key by key; any other value (including arrays,
+/// such as RGB triples) replaces the base value wholesale.
+fn deep_merge(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                deep_merge(base_table, overlay_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -191,6 +274,7 @@ mod tests {
             sources: vec![],
             created: None,
             updated: None,
+            status: None,
         }
     }
 
@@ -207,14 +291,14 @@ mod tests {
 
     #[test]
     fn test_style_config_from_toml() {
-        let toml_str = r#"
+        let toml_str = r"
 [colors.domains]
 philosophy = [147, 51, 234]
 mathematics = [37, 99, 235]
 
 [layout]
 depth_spacing = 5.0
-"#;
+";
 
         let result = StyleConfig::from_toml(toml_str);
         assert!(result.is_ok());
@@ -231,10 +315,10 @@ depth_spacing = 5.0
 
     #[test]
     fn test_map_node_philosophy_to_purple() {
-        let toml_str = r#"
+        let toml_str = r"
 [colors.domains]
 philosophy = [147, 51, 234]
-"#;
+";
         let config = StyleConfig::from_toml(toml_str).unwrap();
         let node = create_test_node("abc123", "philosophy", "proposition");
 
@@ -253,10 +337,10 @@ philosophy = [147, 51, 234]
 
     #[test]
     fn test_map_node_math_to_blue() {
-        let toml_str = r#"
+        let toml_str = r"
 [colors.domains]
 mathematics = [37, 99, 235]
-"#;
+";
         let config = StyleConfig::from_toml(toml_str).unwrap();
         let node = create_test_node("abc123", "mathematics", "theorem");
 
@@ -359,11 +443,11 @@ mathematics = [37, 99, 235]
 
     #[test]
     fn test_map_edge_relation_to_color() {
-        let toml_str = r#"
+        let toml_str = r"
 [colors.relations]
 supports = [34, 197, 94]
 attacks = [239, 68, 68]
-"#;
+";
         let config = StyleConfig::from_toml(toml_str).unwrap();
 
         let support_edge = create_test_edge("a", "b", "supports");
@@ -376,6 +460,108 @@ attacks = [239, 68, 68]
         assert_ne!(gpu_support.color, gpu_attack.color);
     }
 
+    #[test]
+    fn test_extends_default_uses_builtin_defaults() {
+        let toml_str = r#"
+extends = "default"
+
+[colors.domains]
+philosophy = [10, 20, 30]
+"#;
+        let config = StyleConfig::from_toml(toml_str).unwrap();
+
+        assert_eq!(config.depth_spacing(), 5.0);
+        assert!((config.get_domain_color("philosophy")[0] - 10.0 / 255.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_extends_registered_theme_deep_merges() {
+        let base = StyleConfig::from_toml(
+            r"
+[colors.domains]
+philosophy = [147, 51, 234]
+mathematics = [37, 99, 235]
+
+[colors.relations]
+supports = [34, 197, 94]
+
+[layout]
+depth_spacing = 8.0
+",
+        )
+        .unwrap();
+        let themes = HashMap::from([("light".to_string(), base.clone())]);
+
+        let dark = StyleConfig::from_toml_with_themes(
+            r#"
+extends = "light"
+
+[colors.domains]
+philosophy = [0, 0, 0]
+"#,
+            &themes,
+        )
+        .unwrap();
+
+        // Overridden key
+        assert_eq!(dark.get_domain_color("philosophy"), [0.0, 0.0, 0.0, 1.0]);
+        // Sibling keys and other tables are inherited
+        assert_eq!(
+            dark.get_domain_color("mathematics"),
+            base.get_domain_color("mathematics")
+        );
+        assert_eq!(
+            dark.get_relation_color("supports"),
+            base.get_relation_color("supports")
+        );
+        assert_eq!(dark.depth_spacing(), 8.0);
+    }
+
+    #[test]
+    fn test_extends_unknown_theme_errors() {
+        let result = StyleConfig::from_toml(r#"extends = "missing""#);
+
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), "toml");
+        assert!(err.message().contains("missing"));
+    }
+
+    #[test]
+    fn test_malformed_toml_error_location() {
+        let err = StyleConfig::from_toml("[nodes]\nsize = ").unwrap_err();
+
+        assert_eq!(err.kind(), "toml");
+        assert_eq!(err.location().line, Some(2));
+    }
+
+    #[test]
+    fn test_extends_non_string_errors() {
+        let result = StyleConfig::from_toml("extends = 3");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_extend_overlay_keeps_base() {
+        let base = StyleConfig::from_toml(
+            r"
+[colors.relations]
+supports = [34, 197, 94]
+attacks = [239, 68, 68]
+",
+        )
+        .unwrap();
+
+        let print = base
+            .extend("[colors.relations]\nattacks = [0, 0, 0]")
+            .unwrap();
+
+        assert_eq!(print.get_relation_color("attacks"), [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(
+            print.get_relation_color("supports"),
+            base.get_relation_color("supports")
+        );
+    }
+
     #[test]
     fn test_load_project_style_config() {
         // Integration test: load actual project style config
@@ -2,4 +2,4 @@
 
 pub mod mapper;
 
-pub use mapper::StyleConfig;
+pub use mapper::{StyleConfig, DEFAULT_THEME};
@@ -0,0 +1,842 @@
+//! Schema validation of nodes and edges
+//!
+//! A typed equivalent of `schemas/node.schema.json` and
+//! `schemas/edge.schema.json`. Validation works on raw JSON values so that
+//! rules the `Node`/`Edge` structs cannot represent, such as
+//! `additionalProperties: false` or the node `namespace` field, are still
+//! enforced. Every rule is checked and each failure is reported as a
+//! [`Violation`] naming the offending field, rather than stopping at the
+//! first.
+//!
+//! [`validate_repository`] runs these checks over a whole checkout, plus the
+//! graph-level integrity checks (duplicate IDs, edges to missing nodes).
+
+use crate::error::Error;
+use crate::graph::{Edge, Node};
+use crate::parsers::loader::{
+    collect_files, load_repository_lenient, read_file, LoadReport, EDGES_DIR,
+};
+use crate::parsers::toon::parse_toon_document;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+/// Allowed node `type` values
+pub const NODE_TYPES: &[&str] = &[
+    "proposition",
+    "theorem",
+    "theory",
+    "axiom",
+    "definition",
+    "observation",
+    "experiment",
+    "concept",
+    "lemma",
+    "corollary",
+    "conjecture",
+    "principle",
+    "law",
+];
+
+/// Allowed node `domain` values
+pub const DOMAINS: &[&str] = &["philosophy", "mathematics", "physics"];
+
+/// Allowed node `status` values
+pub const STATUSES: &[&str] = &["draft", "stable", "refuted", "archived"];
+
+/// Allowed `metadata.modality` values
+pub const MODALITIES: &[&str] = &["necessary", "contingent", "possible", "impossible"];
+
+/// Allowed `metadata.foundational_system` values
+pub const FOUNDATIONAL_SYSTEMS: &[&str] = &[
+    "ZFC",
+    "HoTT",
+    "CategoryTheory",
+    "ClassicalLogic",
+    "IntuitionisticLogic",
+    "Other",
+];
+
+/// Allowed `metadata.regime` values
+pub const REGIMES: &[&str] = &["classical", "quantum", "relativistic", "statistical"];
+
+/// Allowed edge `relation` values
+pub const RELATIONS: &[&str] = &[
+    "supports",
+    "attacks",
+    "entails",
+    "defines",
+    "cites",
+    "presupposes",
+    "refutes",
+    "explicates",
+    "proves",
+    "generalizes",
+    "equivalent",
+    "lemma_for",
+    "corollary_of",
+    "predicts",
+    "explains",
+    "limiting_case_of",
+    "tests",
+    "approximates",
+    "reduces_to",
+    "unifies",
+    "formalizes",
+    "models",
+    "philosophical_foundation",
+    "empirical_grounding",
+    "applies_to",
+];
+
+/// Node schema properties, in schema order
+pub(crate) const NODE_PROPERTIES: &[&str] = &[
+    "id",
+    "type",
+    "domain",
+    "title",
+    "status",
+    "namespace",
+    "content",
+    "formal",
+    "tags",
+    "metadata",
+    "sources",
+    "created",
+    "updated",
+];
+
+/// Node `metadata` schema properties, in schema order
+pub(crate) const NODE_METADATA_PROPERTIES: &[&str] = &[
+    "modality",
+    "certainty",
+    "importance",
+    "difficulty",
+    "axiom_system",
+    "foundational_system",
+    "contested",
+    "proof_id",
+    "regime",
+    "domain_of_validity",
+];
+
+/// Node `metadata.domain_of_validity` schema properties, in schema order
+pub(crate) const DOMAIN_OF_VALIDITY_PROPERTIES: &[&str] = &["min_scale", "max_scale"];
+
+/// Edge schema properties, in schema order
+pub(crate) const EDGE_PROPERTIES: &[&str] = &["f", "t", "relation", "w", "domain", "metadata"];
+
+const MAX_TITLE_CHARS: usize = 200;
+
+/// A single schema rule broken by a node or edge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Path to the offending field, e.g. `metadata.certainty` or `tags[2]`
+    pub field: String,
+    /// What is wrong with it
+    pub message: String,
+}
+
+impl Violation {
+    fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl From<Violation> for Error {
+    fn from(violation: Violation) -> Self {
+        Self::schema(violation.to_string())
+    }
+}
+
+/// Validates a loaded node
+///
+/// Fields the struct does not carry (`namespace`, unknown keys)
+/// cannot be checked here; use [`validate_node_value`] on the raw JSON.
+#[must_use]
+pub fn validate_node(node: &Node) -> Vec<Violation> {
+    serde_json::to_value(node).map_or_else(
+        |e| vec![Violation::new("$", e.to_string())],
+        |value| validate_node_value(&value),
+    )
+}
+
+/// Validates a loaded edge
+#[must_use]
+pub fn validate_edge(edge: &Edge) -> Vec<Violation> {
+    serde_json::to_value(edge).map_or_else(
+        |e| vec![Violation::new("$", e.to_string())],
+        |value| validate_edge_value(&value),
+    )
+}
+
+/// Validates raw node JSON against the node schema
+#[must_use]
+pub fn validate_node_value(value: &Value) -> Vec<Violation> {
+    let mut check = Checker::default();
+    let Some(object) = value.as_object() else {
+        check.fail("$", "expected an object");
+        return check.violations;
+    };
+
+    check.known_properties(object, NODE_PROPERTIES);
+
+    if let Some(id) = check.string(object, "", "id", true) {
+        check.id("id", id);
+    }
+    if let Some(r#type) = check.string(object, "", "type", true) {
+        check.one_of("type", r#type, NODE_TYPES);
+    }
+    if let Some(domain) = check.string(object, "", "domain", true) {
+        check.one_of("domain", domain, DOMAINS);
+    }
+    if let Some(title) = check.string(object, "", "title", true) {
+        let length = title.chars().count();
+        if length == 0 || length > MAX_TITLE_CHARS {
+            check.fail(
+                "title",
+                format!("length {length} is outside 1..={MAX_TITLE_CHARS}"),
+            );
+        }
+    }
+    if let Some(status) = check.string(object, "", "status", false) {
+        check.one_of("status", status, STATUSES);
+    }
+    if let Some(namespace) = check.string(object, "", "namespace", false) {
+        if namespace.is_empty() || !namespace.chars().all(|c| is_tag_char(c) || c == '-') {
+            check.fail("namespace", "must match ^[a-z0-9_-]+$");
+        }
+    }
+    check.string(object, "", "content", false);
+    check.string(object, "", "formal", false);
+    for name in ["created", "updated"] {
+        if let Some(timestamp) = check.string(object, "", name, false) {
+            if !is_date_time(timestamp) {
+                check.fail(name, format!("'{timestamp}' is not an RFC 3339 date-time"));
+            }
+        }
+    }
+
+    if let Some(tags) = check.array(object, "", "tags") {
+        let mut seen = HashSet::new();
+        for (idx, tag) in tags.iter().enumerate() {
+            let path = format!("tags[{idx}]");
+            match tag.as_str() {
+                None => check.fail(&path, "expected a string"),
+                Some(tag) if tag.is_empty() || !tag.chars().all(is_tag_char) => {
+                    check.fail(&path, format!("'{tag}' must match ^[a-z0-9_]+$"));
+                }
+                Some(tag) if !seen.insert(tag) => {
+                    check.fail(&path, format!("duplicate tag '{tag}'"));
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    if let Some(sources) = check.array(object, "", "sources") {
+        for (idx, source) in sources.iter().enumerate() {
+            if !source.is_string() {
+                check.fail(&format!("sources[{idx}]"), "expected a string");
+            }
+        }
+    }
+
+    // `Node` serialises absent metadata as null
+    match object.get("metadata") {
+        None | Some(Value::Null) => {}
+        Some(Value::Object(metadata)) => check.node_metadata(metadata),
+        Some(_) => check.fail("metadata", "expected an object"),
+    }
+
+    check.violations
+}
+
+/// Validates raw edge JSON against the edge schema
+#[must_use]
+pub fn validate_edge_value(value: &Value) -> Vec<Violation> {
+    let mut check = Checker::default();
+    let Some(object) = value.as_object() else {
+        check.fail("$", "expected an object");
+        return check.violations;
+    };
+
+    check.known_properties(object, EDGE_PROPERTIES);
+
+    for name in ["f", "t"] {
+        if let Some(id) = check.string(object, "", name, true) {
+            check.id(name, id);
+        }
+    }
+    if let Some(relation) = check.string(object, "", "relation", true) {
+        check.one_of("relation", relation, RELATIONS);
+    }
+    check.string(object, "", "domain", true);
+    check.number_in(object, "", "w", 0.0, 1.0);
+    if let Some(metadata) = object.get("metadata") {
+        if !metadata.is_object() {
+            check.fail("metadata", "expected an object");
+        }
+    }
+
+    check.violations
+}
+
+/// Loads the repository at `root` and checks it
+///
+/// Node files are validated as raw JSON so unknown properties are caught.
+/// Edge files are re-read so violations point at the offending line.
+///
+/// # Returns
+///
+/// The lenient load report, with schema and integrity errors appended to
+/// its diagnostics
+#[must_use]
+pub fn validate_repository(root: &Path) -> LoadReport {
+    let mut report = load_repository_lenient(root);
+    let mut diagnostics = Vec::new();
+
+    let mut id_counts: HashMap<&str, usize> = HashMap::new();
+    for node in &report.nodes {
+        *id_counts.entry(node.id.as_str()).or_default() += 1;
+    }
+
+    for (node, path) in report.nodes.iter().zip(&report.node_paths) {
+        let raw = Some(path)
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .and_then(|path| read_file(path).ok())
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok());
+        let violations = raw.map_or_else(|| validate_node(node), |raw| validate_node_value(&raw));
+        for violation in violations {
+            diagnostics.push(located(violation.into(), Some(path), None));
+        }
+
+        // Reported once per defining file, so every copy can be found
+        if id_counts[node.id.as_str()] > 1 {
+            diagnostics.push(located(
+                Error::integrity(format!("Duplicate node id '{}'", node.id)),
+                Some(path),
+                None,
+            ));
+        }
+    }
+
+    let mut unused = Vec::new();
+    let edges_dir = root.join(EDGES_DIR);
+    for path in collect_files(&edges_dir, &["jsonl", "toon"], &mut unused) {
+        let Ok(content) = read_file(&path) else {
+            continue; // already reported by the loader
+        };
+
+        let edges: Vec<(Option<usize>, Value)> =
+            if path.extension().is_some_and(|ext| ext == "toon") {
+                parse_toon_document(&content)
+                    .map(|document| document.edges)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|edge| serde_json::to_value(edge).ok())
+                    .map(|value| (None, value))
+                    .collect()
+            } else {
+                content
+                    .lines()
+                    .enumerate()
+                    .filter_map(|(line_num, line)| {
+                        serde_json::from_str(line)
+                            .ok()
+                            .map(|value| (Some(line_num + 1), value))
+                    })
+                    .collect()
+            };
+
+        for (line, value) in edges {
+            for violation in validate_edge_value(&value) {
+                diagnostics.push(located(violation.into(), Some(&path), line));
+            }
+            for end in ["f", "t"] {
+                if let Some(id) = value[end].as_str() {
+                    if !id_counts.contains_key(id) {
+                        diagnostics.push(located(
+                            Error::integrity(format!("{end}: unknown node '{id}'")),
+                            Some(&path),
+                            line,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    report.diagnostics.extend(diagnostics);
+    report
+}
+
+fn located(err: Error, path: Option<&Path>, line: Option<usize>) -> Error {
+    let err = match path {
+        Some(path) => err.with_path(path),
+        None => err,
+    };
+    match line {
+        Some(line) => err.at(line, 1),
+        None => err,
+    }
+}
+
+/// Collects violations while walking one value
+#[derive(Default)]
+struct Checker {
+    violations: Vec<Violation>,
+}
+
+impl Checker {
+    fn fail(&mut self, field: &str, message: impl Into<String>) {
+        self.violations.push(Violation::new(field, message));
+    }
+
+    fn known_properties(&mut self, object: &Map<String, Value>, known: &[&str]) {
+        for key in object.keys() {
+            if !known.contains(&key.as_str()) {
+                self.fail(key, "unknown property");
+            }
+        }
+    }
+
+    /// Returns the string at `name`, recording a violation if it is missing
+    /// (when required) or not a string
+    fn string<'a>(
+        &mut self,
+        object: &'a Map<String, Value>,
+        prefix: &str,
+        name: &str,
+        required: bool,
+    ) -> Option<&'a str> {
+        match object.get(name) {
+            None if required => {
+                self.fail(&field(prefix, name), "required property is missing");
+                None
+            }
+            None => None,
+            Some(Value::String(text)) => Some(text),
+            Some(_) => {
+                self.fail(&field(prefix, name), "expected a string");
+                None
+            }
+        }
+    }
+
+    fn array<'a>(
+        &mut self,
+        object: &'a Map<String, Value>,
+        prefix: &str,
+        name: &str,
+    ) -> Option<&'a [Value]> {
+        match object.get(name) {
+            None => None,
+            Some(Value::Array(items)) => Some(items),
+            Some(_) => {
+                self.fail(&field(prefix, name), "expected an array");
+                None
+            }
+        }
+    }
+
+    fn number_in(
+        &mut self,
+        object: &Map<String, Value>,
+        prefix: &str,
+        name: &str,
+        min: f64,
+        max: f64,
+    ) {
+        match object.get(name) {
+            None => {}
+            Some(Value::Number(number)) => {
+                let number = number.as_f64().unwrap_or(f64::NAN);
+                if !(min..=max).contains(&number) {
+                    self.fail(
+                        &field(prefix, name),
+                        format!("{number} is outside [{min}, {max}]"),
+                    );
+                }
+            }
+            Some(_) => self.fail(&field(prefix, name), "expected a number"),
+        }
+    }
+
+    fn one_of(&mut self, field: &str, value: &str, allowed: &[&str]) {
+        if !allowed.contains(&value) {
+            self.fail(
+                field,
+                format!("'{value}' is not one of: {}", allowed.join(", ")),
+            );
+        }
+    }
+
+    fn id(&mut self, field: &str, id: &str) {
+        if id.len() != 6
+            || !id
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+        {
+            self.fail(field, format!("'{id}' must match ^[a-z0-9]{{6}}$"));
+        }
+    }
+
+    fn node_metadata(&mut self, metadata: &Map<String, Value>) {
+        const PREFIX: &str = "metadata";
+
+        if let Some(modality) = self.string(metadata, PREFIX, "modality", false) {
+            self.one_of("metadata.modality", modality, MODALITIES);
+        }
+        self.number_in(metadata, PREFIX, "certainty", 0.0, 1.0);
+        self.number_in(metadata, PREFIX, "importance", 0.0, 10.0);
+        self.number_in(metadata, PREFIX, "difficulty", 0.0, 10.0);
+        self.string(metadata, PREFIX, "axiom_system", false);
+        if let Some(system) = self.string(metadata, PREFIX, "foundational_system", false) {
+            self.one_of("metadata.foundational_system", system, FOUNDATIONAL_SYSTEMS);
+        }
+        if metadata
+            .get("contested")
+            .is_some_and(|contested| !contested.is_boolean())
+        {
+            self.fail("metadata.contested", "expected a boolean");
+        }
+        self.string(metadata, PREFIX, "proof_id", false);
+        if let Some(regime) = self.string(metadata, PREFIX, "regime", false) {
+            self.one_of("metadata.regime", regime, REGIMES);
+        }
+        match metadata.get("domain_of_validity") {
+            None => {}
+            Some(Value::Object(validity)) => {
+                for &name in DOMAIN_OF_VALIDITY_PROPERTIES {
+                    if validity.get(name).is_some_and(|scale| !scale.is_number()) {
+                        self.fail(
+                            &format!("metadata.domain_of_validity.{name}"),
+                            "expected a number",
+                        );
+                    }
+                }
+            }
+            Some(_) => self.fail("metadata.domain_of_validity", "expected an object"),
+        }
+    }
+}
+
+fn field(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{prefix}.{name}")
+    }
+}
+
+const fn is_tag_char(c: char) -> bool {
+    c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'
+}
+
+/// Checks the RFC 3339 shape `YYYY-MM-DDTHH:MM:SS[.frac](Z|±HH:MM)`
+fn is_date_time(text: &str) -> bool {
+    fn digits(part: &str, count: usize) -> bool {
+        part.len() == count && part.bytes().all(|b| b.is_ascii_digit())
+    }
+
+    let Some((date, time)) = text.split_once(['T', 't']) else {
+        return false;
+    };
+    let date_ok = matches!(
+        date.split('-').collect::<Vec<_>>()[..],
+        [year, month, day] if digits(year, 4) && digits(month, 2) && digits(day, 2)
+    );
+
+    let (clock, offset) = if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+        (clock, None)
+    } else if let Some(idx) = time.rfind(['+', '-']) {
+        (&time[..idx], Some(&time[idx + 1..]))
+    } else {
+        return false;
+    };
+    let clock = match clock.split_once('.') {
+        None => clock,
+        Some((whole, fraction)) if !fraction.is_empty() && digits(fraction, fraction.len()) => {
+            whole
+        }
+        Some(_) => return false,
+    };
+    let clock_ok = matches!(
+        clock.split(':').collect::<Vec<_>>()[..],
+        [hour, minute, second] if digits(hour, 2) && digits(minute, 2) && digits(second, 2)
+    );
+    let offset_ok = offset.is_none_or(|offset| {
+        matches!(
+            offset.split(':').collect::<Vec<_>>()[..],
+            [hour, minute] if digits(hour, 2) && digits(minute, 2)
+        )
+    });
+
+    date_ok && clock_ok && offset_ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::path::Path;
+
+    fn valid_node() -> Value {
+        json!({
+            "id": "k7x9m2",
+            "type": "proposition",
+            "domain": "philosophy",
+            "title": "Knowledge requires safety",
+            "status": "stable",
+            "tags": ["epistemology", "safety_condition"],
+            "metadata": {"certainty": 0.75, "importance": 8, "modality": "contingent"},
+            "sources": ["pritchard2005"],
+            "created": "2025-01-15T10:00:00Z",
+            "updated": "2025-01-15T12:00:00.5+01:00"
+        })
+    }
+
+    fn fields(violations: &[Violation]) -> Vec<&str> {
+        violations.iter().map(|v| v.field.as_str()).collect()
+    }
+
+    #[test]
+    fn test_valid_node_passes() {
+        assert_eq!(validate_node_value(&valid_node()), []);
+    }
+
+    #[test]
+    fn test_node_violations_reported_per_field() {
+        let mut node = valid_node();
+        node["id"] = json!("K7X9M2");
+        node["type"] = json!("hunch");
+        node["tags"] = json!(["ok", "Bad Tag", "ok"]);
+        node["metadata"]["certainty"] = json!(1.5);
+        node["metadata"]["importance"] = json!(11);
+        node["extra"] = json!(true);
+
+        let violations = validate_node_value(&node);
+
+        assert_eq!(
+            fields(&violations),
+            [
+                "extra",
+                "id",
+                "type",
+                "tags[1]",
+                "tags[2]",
+                "metadata.certainty",
+                "metadata.importance"
+            ]
+        );
+        assert!(violations[2].message.contains("'hunch' is not one of"));
+    }
+
+    #[test]
+    fn test_node_missing_and_mistyped_fields() {
+        let node = json!({"id": "abc123", "domain": 3, "title": "", "created": "yesterday"});
+
+        let violations = validate_node_value(&node);
+
+        assert_eq!(fields(&violations), ["type", "domain", "title", "created"]);
+        assert_eq!(
+            violations[0].to_string(),
+            "type: required property is missing"
+        );
+    }
+
+    #[test]
+    fn test_validate_typed_node_and_edge() {
+        let node: Node = serde_json::from_value(valid_node()).unwrap();
+        assert_eq!(validate_node(&node), []);
+
+        let edge = Edge {
+            from: "abc123".to_string(),
+            to: "short".to_string(),
+            relation: "supports".to_string(),
+            domain: "philosophy".to_string(),
+            weight: Some(1.5),
+            metadata: None,
+        };
+        let violations = validate_edge(&edge);
+        assert_eq!(fields(&violations), ["t", "w"]);
+
+        let err: Error = violations[0].clone().into();
+        assert_eq!(err.kind(), "schema");
+    }
+
+    #[test]
+    fn test_edge_value_rules() {
+        let edge = json!({"f": "abc123", "t": "def456", "relation": "likes", "weight": 1});
+
+        let violations = validate_edge_value(&edge);
+
+        assert_eq!(fields(&violations), ["weight", "relation", "domain"]);
+    }
+
+    #[test]
+    fn test_date_time_shapes() {
+        assert!(is_date_time("2025-01-15T10:00:00Z"));
+        assert!(is_date_time("2025-01-15T10:00:00.123-05:00"));
+        assert!(!is_date_time("2025-01-15"));
+        assert!(!is_date_time("2025-01-15T10:00Z"));
+        assert!(!is_date_time("2025-01-15T10:00:00"));
+    }
+
+    #[test]
+    fn test_enums_match_schema_files() {
+        let schemas = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("schemas");
+        let load = |name: &str| -> Value {
+            serde_json::from_str(&std::fs::read_to_string(schemas.join(name)).unwrap()).unwrap()
+        };
+        let strings = |value: &Value| -> Vec<String> {
+            value
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|item| item.as_str().unwrap().to_string())
+                .collect()
+        };
+
+        let node = load("node.schema.json");
+        let properties = &node["properties"];
+        assert_eq!(strings(&properties["type"]["enum"]), NODE_TYPES);
+        assert_eq!(strings(&properties["domain"]["enum"]), DOMAINS);
+        assert_eq!(strings(&properties["status"]["enum"]), STATUSES);
+        let metadata = &properties["metadata"]["properties"];
+        assert_eq!(strings(&metadata["modality"]["enum"]), MODALITIES);
+        assert_eq!(
+            strings(&metadata["foundational_system"]["enum"]),
+            FOUNDATIONAL_SYSTEMS
+        );
+        assert_eq!(strings(&metadata["regime"]["enum"]), REGIMES);
+        let mut keys: Vec<&str> = metadata
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        let mut expected = NODE_METADATA_PROPERTIES.to_vec();
+        keys.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(keys, expected);
+        let mut validity: Vec<&str> = metadata["domain_of_validity"]["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        let mut expected = DOMAIN_OF_VALIDITY_PROPERTIES.to_vec();
+        validity.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(validity, expected);
+        let mut keys: Vec<&str> = properties
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        let mut expected = NODE_PROPERTIES.to_vec();
+        keys.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(keys, expected);
+
+        let edge = load("edge.schema.json");
+        assert_eq!(strings(&edge["properties"]["relation"]["enum"]), RELATIONS);
+        let mut keys: Vec<&str> = edge["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        let mut expected = EDGE_PROPERTIES.to_vec();
+        keys.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(keys, expected);
+    }
+
+    #[test]
+    fn test_validate_repository_reports_locations() {
+        let root = std::env::temp_dir().join("truthmines_test_validate_repo");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("nodes")).unwrap();
+        std::fs::create_dir_all(root.join("edges")).unwrap();
+        let mut node = valid_node();
+        node["extra"] = json!(1);
+        std::fs::write(root.join("nodes/k7x9m2.json"), node.to_string()).unwrap();
+        std::fs::write(
+            root.join("edges/supports.jsonl"),
+            "{\"f\":\"k7x9m2\",\"t\":\"zzz999\",\"relation\":\"supports\",\"domain\":\"x\"}\n\
+             {\"f\":\"k7x9m2\",\"t\":\"k7x9m2\",\"relation\":\"supports\",\"domain\":\"x\",\"w\":2}\n",
+        )
+        .unwrap();
+
+        let report = validate_repository(&root);
+        let messages: Vec<String> = report.diagnostics.iter().map(ToString::to_string).collect();
+
+        assert_eq!(report.diagnostics.len(), 3, "{messages:?}");
+        assert_eq!(report.diagnostics[0].kind(), "schema");
+        assert!(messages[0].ends_with("k7x9m2.json: schema error: extra: unknown property"));
+        assert_eq!(report.diagnostics[1].kind(), "integrity");
+        assert_eq!(report.diagnostics[1].location().line, Some(1));
+        assert!(messages[1].contains("t: unknown node 'zzz999'"));
+        assert_eq!(report.diagnostics[2].location().line, Some(2));
+        assert!(messages[2].contains("w: 2 is outside [0, 1]"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_validate_repository_reports_every_duplicate_file() {
+        let root = std::env::temp_dir().join("truthmines_test_validate_duplicates");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("nodes")).unwrap();
+        std::fs::create_dir_all(root.join("edges")).unwrap();
+        let mut first = valid_node();
+        first["extra"] = json!(1);
+        std::fs::write(root.join("nodes/a.json"), first.to_string()).unwrap();
+        std::fs::write(root.join("nodes/b.json"), valid_node().to_string()).unwrap();
+
+        let report = validate_repository(&root);
+        let messages: Vec<String> = report.diagnostics.iter().map(ToString::to_string).collect();
+
+        assert_eq!(report.diagnostics.len(), 3, "{messages:?}");
+        assert!(messages[0].ends_with("a.json: schema error: extra: unknown property"));
+        assert!(messages[1].contains("a.json"));
+        assert!(messages[1].ends_with("Duplicate node id 'k7x9m2'"));
+        assert!(messages[2].contains("b.json"));
+        assert!(messages[2].ends_with("Duplicate node id 'k7x9m2'"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_project_data_is_valid() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let report = crate::parsers::loader::load_lenient(&root.join("nodes"), &root.join("edges"));
+
+        for node in &report.nodes {
+            assert_eq!(validate_node(node), [], "node {}", node.id);
+        }
+        for edge in &report.edges {
+            assert_eq!(validate_edge(edge), [], "edge {} -> {}", edge.from, edge.to);
+        }
+
+        let checked = validate_repository(root);
+        assert!(checked.is_clean(), "{:?}", checked.diagnostics);
+    }
+}
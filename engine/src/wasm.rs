@@ -1,10 +1,25 @@
 //! WebAssembly bindings for JavaScript
 
+use crate::analysis::build_cluster_hierarchy;
+use crate::error::Error;
+use crate::gpu::buffers::{recolor_edges, recolor_nodes};
+use crate::gpu::cache::GpuCache;
+use crate::gpu::dirty::BufferPatch;
+use crate::gpu::format::{edge_layout, encode_edge_buffer, encode_node_buffer, node_layout};
+use crate::gpu::geometry::{generate_edge_geometry, BundlingConfig, EdgeGeometryConfig};
+use crate::gpu::interaction::Interaction;
+use crate::gpu::lod::build_lod_levels;
+use crate::gpu::picking::{
+    decode_pick_color, edge_record_indices, generate_edge_pick_buffer, generate_node_pick_buffer,
+    PickTarget,
+};
+use crate::gpu::{GpuNode, RenderFlags};
+use crate::graph::ids::{IdOptions, IdStrategy};
 use crate::graph::{GraphStore, Node};
-use crate::gpu::buffers::{generate_edge_buffer, generate_node_buffer};
 use crate::layout::{depth::compute_depths, truth_mine::compute_truth_mine_layout};
-use crate::parsers::{json::load_node_from_json, toon::parse_toon};
-use crate::style::StyleConfig;
+use crate::parsers::json::load_node_from_json;
+use crate::parsers::toon::{parse_toon, parse_toon_document, write_toon_document, ToonDocument};
+use crate::style::{StyleConfig, DEFAULT_THEME};
 use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
 
@@ -15,12 +30,20 @@ pub struct GraphEngine {
     style: StyleConfig,
     layout_3d: HashMap<String, [f32; 3]>,
     depths: HashMap<String, u32>,
+    themes: HashMap<String, StyleConfig>,
+    /// Cached GPU records; `None` until requested or after the graph changes
+    gpu_cache: Option<GpuCache>,
+    /// Selection, hover, focus path and search, re-applied on every rebuild
+    interaction: Interaction,
 }
 
 #[wasm_bindgen]
 impl GraphEngine {
     /// Creates a new `GraphEngine`
     ///
+    /// The style is also registered as the `"default"` theme, so later themes
+    /// can declare `extends = "default"` to inherit from it.
+    ///
     /// # Arguments
     ///
     /// * `style_toml` - TOML style configuration string
@@ -29,15 +52,17 @@ impl GraphEngine {
     ///
     /// Returns error if style TOML is malformed
     #[wasm_bindgen(constructor)]
-    pub fn new(style_toml: &str) -> Result<GraphEngine, JsValue> {
-        let style = StyleConfig::from_toml(style_toml)
-            .map_err(|e| JsValue::from_str(&format!("Style config error: {e}")))?;
+    pub fn new(style_toml: &str) -> Result<Self, JsValue> {
+        let style = StyleConfig::from_toml(style_toml)?;
 
-        Ok(GraphEngine {
+        Ok(Self {
             graph: GraphStore::new(),
+            themes: HashMap::from([(DEFAULT_THEME.to_string(), style.clone())]),
             style,
             layout_3d: HashMap::new(),
             depths: HashMap::new(),
+            gpu_cache: None,
+            interaction: Interaction::default(),
         })
     }
 
@@ -57,11 +82,11 @@ impl GraphEngine {
                 self.graph.add_node(node);
             }
         } else {
-            let node = load_node_from_json(nodes_json)
-                .map_err(|e| JsValue::from_str(&e))?;
+            let node = load_node_from_json(nodes_json)?;
             self.graph.add_node(node);
         }
 
+        self.gpu_cache = None;
         Ok(())
     }
 
@@ -75,16 +100,47 @@ impl GraphEngine {
     ///
     /// Returns error if TOON is malformed
     pub fn load_edges_toon(&mut self, edges_toon: &str) -> Result<(), JsValue> {
-        let edges = parse_toon(edges_toon)
-            .map_err(|e| JsValue::from_str(&format!("TOON parse error: {e}")))?;
+        let edges = parse_toon(edges_toon)?;
 
         for edge in edges {
             self.graph.add_edge(edge);
         }
 
+        self.gpu_cache = None;
         Ok(())
     }
 
+    /// Loads nodes and edges from a TOON document
+    ///
+    /// # Arguments
+    ///
+    /// * `toon` - TOON document with an optional `nodes` table and relation tables
+    ///
+    /// # Errors
+    ///
+    /// Returns error if TOON is malformed
+    #[wasm_bindgen(js_name = loadToon)]
+    pub fn load_toon(&mut self, toon: &str) -> Result<(), JsValue> {
+        let document = parse_toon_document(toon)?;
+
+        for node in document.nodes {
+            self.graph.add_node(node);
+        }
+        for edge in document.edges {
+            self.graph.add_edge(edge);
+        }
+
+        self.gpu_cache = None;
+        Ok(())
+    }
+
+    /// Exports the loaded nodes and edges as a TOON document
+    #[must_use]
+    #[wasm_bindgen(js_name = exportToon)]
+    pub fn export_toon(&self) -> String {
+        write_toon_document(&ToonDocument::from_graph(&self.graph))
+    }
+
     /// Computes the truth mine 3D layout
     pub fn compute_layout_truth_mine(&mut self) {
         self.graph.build_adjacency();
@@ -94,22 +150,308 @@ impl GraphEngine {
             &self.depths,
             self.style.depth_spacing(),
         );
+        self.gpu_cache = None;
+    }
+
+    /// Registers a named theme
+    ///
+    /// The theme may `extends` any previously registered theme.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Theme name (e.g. `"dark"`, `"print"`)
+    /// * `style_toml` - TOML style configuration string
+    ///
+    /// # Errors
+    ///
+    /// Returns error if style TOML is malformed or extends an unknown theme
+    #[wasm_bindgen(js_name = registerTheme)]
+    pub fn register_theme(&mut self, name: &str, style_toml: &str) -> Result<(), JsValue> {
+        let theme = StyleConfig::from_toml_with_themes(style_toml, &self.themes)?;
+        self.themes.insert(name.to_string(), theme);
+        Ok(())
+    }
+
+    /// Switches to a registered theme without reloading the graph
+    ///
+    /// Only the colour channels of cached GPU buffers are regenerated. Layout
+    /// settings of the new theme take effect on the next layout computation.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no theme with that name is registered
+    #[wasm_bindgen(js_name = setTheme)]
+    pub fn set_theme(&mut self, name: &str) -> Result<(), JsValue> {
+        let theme = self
+            .themes
+            .get(name)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown theme: {name}")))?;
+        self.style = theme.clone();
+
+        if let Some(cache) = &mut self.gpu_cache {
+            recolor_nodes(cache.nodes.modify_all(), &self.graph, &self.style);
+            recolor_edges(cache.edges.modify_all(), &self.graph, &self.style);
+        }
+
+        Ok(())
     }
 
     /// Gets GPU buffers as JavaScript objects
     ///
+    /// Each buffer starts with a versioned header (see `gpu::format`) that the
+    /// renderer should check against `getBufferLayout()` before reading records.
+    ///
     /// # Returns
     ///
     /// Object with `nodes` and `edges` properties (both `Uint8Array`)
     #[wasm_bindgen(js_name = getGpuBuffers)]
-    pub fn get_gpu_buffers(&self) -> js_sys::Object {
-        let node_buffer = generate_node_buffer(&self.graph, &self.layout_3d, &self.depths, &self.style);
-        let edge_buffer = generate_edge_buffer(&self.graph, &self.style);
+    pub fn get_gpu_buffers(&mut self) -> js_sys::Object {
+        let cache = self.gpu_cache();
+        // A full upload supersedes any pending patches
+        cache.mark_uploaded();
+
+        let result = js_sys::Object::new();
+
+        let node_array = js_sys::Uint8Array::from(&encode_node_buffer(cache.nodes.records())[..]);
+        let edge_array = js_sys::Uint8Array::from(&encode_edge_buffer(cache.edges.records())[..]);
+
+        js_sys::Reflect::set(&result, &"nodes".into(), &node_array).ok();
+        js_sys::Reflect::set(&result, &"edges".into(), &edge_array).ok();
+
+        result
+    }
+
+    /// Gets only the GPU buffer ranges changed since the last upload
+    ///
+    /// Byte offsets are relative to the first record (after the header).
+    /// After a reload, relayout or theme change the buffers are rebuilt:
+    /// `full` is then true, the record counts may have changed and the
+    /// patches cover every record, so the renderer must reallocate (or call
+    /// `getGpuBuffers()` instead).
+    ///
+    /// # Returns
+    ///
+    /// Object with a `full` flag and `nodes` and `edges` arrays of
+    /// `{ offset, bytes }` patches
+    #[wasm_bindgen(js_name = getGpuBufferUpdates)]
+    pub fn get_gpu_buffer_updates(&mut self) -> js_sys::Object {
+        let cache = self.gpu_cache();
+        let result = js_sys::Object::new();
+
+        js_sys::Reflect::set(
+            &result,
+            &"full".into(),
+            &JsValue::from(cache.take_rebuilt()),
+        )
+        .ok();
+
+        js_sys::Reflect::set(
+            &result,
+            &"nodes".into(),
+            &patches_to_js(cache.nodes.take_patches()),
+        )
+        .ok();
+        js_sys::Reflect::set(
+            &result,
+            &"edges".into(),
+            &patches_to_js(cache.edges.take_patches()),
+        )
+        .ok();
+
+        result
+    }
+
+    /// Sets the render flags of a node
+    ///
+    /// Structural bits (see `getRenderFlags`) are computed from the graph and
+    /// kept; only the other bits are taken from `flags`.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the node does not exist
+    #[wasm_bindgen(js_name = setNodeFlags)]
+    pub fn set_node_flags(&mut self, id: &str, flags: u32) -> bool {
+        if self.graph.index_of(id).is_none() {
+            return false;
+        }
+        self.interaction.node_flags.insert(id.to_string(), flags);
+        self.update_gpu_node(id, |node| {
+            node.flags = RenderFlags::from_bits(node.flags)
+                .with_overrides(flags)
+                .bits();
+        })
+    }
+
+    /// Sets the scalar channel (salience, importance, ...) of a node
+    ///
+    /// # Returns
+    ///
+    /// `false` if the node does not exist
+    #[wasm_bindgen(js_name = setNodeScalar)]
+    pub fn set_node_scalar(&mut self, id: &str, scalar: f32) -> bool {
+        if self.graph.index_of(id).is_none() {
+            return false;
+        }
+        self.interaction.node_scalars.insert(id.to_string(), scalar);
+        self.update_gpu_node(id, |node| node.scalar = scalar)
+    }
+
+    /// Marks a node as selected, clearing the previous selection
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Node ID, or `undefined` to clear the selection
+    ///
+    /// # Returns
+    ///
+    /// `false` if `id` is given but the node does not exist
+    #[allow(clippy::needless_pass_by_value)] // wasm_bindgen cannot take `Option<&str>`
+    #[wasm_bindgen(js_name = selectNode)]
+    pub fn select_node(&mut self, id: Option<String>) -> bool {
+        if id
+            .as_ref()
+            .is_some_and(|id| self.graph.index_of(id).is_none())
+        {
+            return false;
+        }
+        self.interaction.selected = id;
+        self.apply_interaction(Interaction::apply_selection);
+        true
+    }
+
+    /// Marks a node as hovered, clearing the previous hover
+    ///
+    /// # Returns
+    ///
+    /// `false` if `id` is given but the node does not exist
+    #[allow(clippy::needless_pass_by_value)] // wasm_bindgen cannot take `Option<&str>`
+    #[wasm_bindgen(js_name = hoverNode)]
+    pub fn hover_node(&mut self, id: Option<String>) -> bool {
+        if id
+            .as_ref()
+            .is_some_and(|id| self.graph.index_of(id).is_none())
+        {
+            return false;
+        }
+        self.interaction.hovered = id;
+        self.apply_interaction(Interaction::apply_hover);
+        true
+    }
+
+    /// Flags the nodes and edges of the shortest path between two nodes
+    ///
+    /// Any previous focus path is cleared first.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - Start node ID
+    /// * `to` - End node ID
+    /// * `max_depth` - Maximum path length to search
+    ///
+    /// # Returns
+    ///
+    /// Number of nodes on the path (0 if none was found)
+    #[wasm_bindgen(js_name = setFocusPath)]
+    pub fn set_focus_path(&mut self, from: &str, to: &str, max_depth: u32) -> usize {
+        self.interaction.focus_path = self
+            .graph
+            .find_paths(from, to, max_depth)
+            .into_iter()
+            .min_by_key(Vec::len)
+            .map(|nodes| nodes.iter().map(|node| node.id.clone()).collect())
+            .unwrap_or_default();
+
+        self.apply_interaction(Interaction::apply_focus_path)
+    }
+
+    /// Flags nodes whose ID, title or tags contain `query` (case-insensitive)
+    ///
+    /// An empty query clears the highlight.
+    ///
+    /// # Returns
+    ///
+    /// Number of matching nodes
+    #[wasm_bindgen(js_name = highlightSearch)]
+    pub fn highlight_search(&mut self, query: &str) -> usize {
+        self.interaction.search = query.to_string();
+        self.apply_interaction(Interaction::apply_search)
+    }
+
+    /// Returns the render flag bit values as JSON (e.g. `{"SELECTED": 1, ...}`)
+    #[must_use]
+    #[wasm_bindgen(js_name = getRenderFlags)]
+    pub fn get_render_flags() -> String {
+        let flags = [
+            ("SELECTED", RenderFlags::SELECTED),
+            ("HOVERED", RenderFlags::HOVERED),
+            ("FOCUS_PATH", RenderFlags::FOCUS_PATH),
+            ("FOUNDATION", RenderFlags::FOUNDATION),
+            ("IN_CYCLE", RenderFlags::IN_CYCLE),
+            ("BRIDGE", RenderFlags::BRIDGE),
+            ("CROSS_DOMAIN", RenderFlags::CROSS_DOMAIN),
+            ("DANGLING_SOURCE", RenderFlags::DANGLING_SOURCE),
+            ("SEARCH_MATCH", RenderFlags::SEARCH_MATCH),
+        ];
+        let map: serde_json::Map<String, serde_json::Value> = flags
+            .iter()
+            .map(|(name, flag)| ((*name).to_string(), flag.bits().into()))
+            .collect();
+
+        serde_json::Value::Object(map).to_string()
+    }
+
+    /// Generates curved/bundled edge polylines
+    ///
+    /// # Arguments
+    ///
+    /// * `segments` - Line segments per curved edge
+    /// * `curvature` - Lateral offset between parallel edges (fraction of length)
+    /// * `bundle_bridges` - Apply force-directed bundling to bridge edges
+    ///
+    /// # Returns
+    ///
+    /// Object with `vertices` (`Uint8Array` of `GpuEdgeVertex`) and `offsets`
+    /// (`Uint32Array`, vertices of edge `j` are `offsets[j]..offsets[j + 1]`)
+    #[must_use]
+    #[wasm_bindgen(js_name = getEdgeGeometry)]
+    pub fn get_edge_geometry(
+        &self,
+        segments: u32,
+        curvature: f32,
+        bundle_bridges: bool,
+    ) -> js_sys::Object {
+        let config = EdgeGeometryConfig {
+            segments,
+            curvature,
+            bundling: bundle_bridges.then(BundlingConfig::default),
+        };
+        let geometry = generate_edge_geometry(&self.graph, &self.layout_3d, &config);
 
         let result = js_sys::Object::new();
 
-        let node_array = js_sys::Uint8Array::from(&node_buffer[..]);
-        let edge_array = js_sys::Uint8Array::from(&edge_buffer[..]);
+        let vertex_array =
+            js_sys::Uint8Array::from(bytemuck::cast_slice::<_, u8>(&geometry.vertices));
+        let offset_array = js_sys::Uint32Array::from(&geometry.offsets[..]);
+
+        js_sys::Reflect::set(&result, &"vertices".into(), &vertex_array).ok();
+        js_sys::Reflect::set(&result, &"offsets".into(), &offset_array).ok();
+
+        result
+    }
+
+    /// Gets pick-colour buffers for the offscreen hit-test pass
+    ///
+    /// # Returns
+    ///
+    /// Object with `nodes` and `edges` properties (both `Uint8Array`, RGBA8 per
+    /// record, in the same order as the GPU buffers)
+    #[must_use]
+    #[wasm_bindgen(js_name = getPickBuffers)]
+    pub fn get_pick_buffers(&self) -> js_sys::Object {
+        let result = js_sys::Object::new();
+
+        let node_array = js_sys::Uint8Array::from(&generate_node_pick_buffer(&self.graph)[..]);
+        let edge_array = js_sys::Uint8Array::from(&generate_edge_pick_buffer(&self.graph)[..]);
 
         js_sys::Reflect::set(&result, &"nodes".into(), &node_array).ok();
         js_sys::Reflect::set(&result, &"edges".into(), &edge_array).ok();
@@ -117,19 +459,245 @@ impl GraphEngine {
         result
     }
 
+    /// Gets aggregated cluster buffers for level-of-detail rendering
+    ///
+    /// Levels run coarsest first: domain, domain × depth band, and
+    /// domain × depth band × community.
+    ///
+    /// # Arguments
+    ///
+    /// * `band_size` - Number of depth levels per band
+    ///
+    /// # Returns
+    ///
+    /// Array of objects with `labels` (cluster names), `nodes` and `edges`
+    /// (headered `Uint8Array`s in the same format as `getGpuBuffers`; edge
+    /// endpoints are cluster indices)
+    #[wasm_bindgen(js_name = getLodBuffers)]
+    pub fn get_lod_buffers(&mut self, band_size: u32) -> js_sys::Array {
+        let hierarchy = build_cluster_hierarchy(&self.graph, &self.depths, band_size);
+        let cache = self.gpu_cache();
+        let levels = build_lod_levels(&hierarchy, cache.nodes.records(), cache.edges.records());
+
+        hierarchy
+            .iter()
+            .zip(levels)
+            .map(|(clustering, level)| {
+                let entry = js_sys::Object::new();
+                let labels: js_sys::Array = clustering
+                    .labels
+                    .iter()
+                    .map(|label| JsValue::from_str(label))
+                    .collect();
+                let node_array = js_sys::Uint8Array::from(&encode_node_buffer(&level.nodes)[..]);
+                let edge_array = js_sys::Uint8Array::from(&encode_edge_buffer(&level.edges)[..]);
+
+                js_sys::Reflect::set(&entry, &"labels".into(), &labels).ok();
+                js_sys::Reflect::set(&entry, &"nodes".into(), &node_array).ok();
+                js_sys::Reflect::set(&entry, &"edges".into(), &edge_array).ok();
+                entry
+            })
+            .collect()
+    }
+
+    /// Returns node IDs in GPU buffer order
+    #[must_use]
+    #[wasm_bindgen(js_name = nodeIds)]
+    pub fn node_ids(&self) -> js_sys::Array {
+        self.graph
+            .nodes()
+            .iter()
+            .map(|node| JsValue::from_str(&node.id))
+            .collect()
+    }
+
+    /// Returns the node at a GPU buffer index as JSON
+    #[must_use]
+    #[wasm_bindgen(js_name = nodeAtIndex)]
+    pub fn node_at_index(&self, index: usize) -> Option<String> {
+        self.graph
+            .node_at_index(index)
+            .and_then(|node| serde_json::to_string(node).ok())
+    }
+
+    /// Returns the GPU buffer index of a node
+    #[must_use]
+    #[wasm_bindgen(js_name = indexOf)]
+    pub fn index_of(&self, id: &str) -> Option<usize> {
+        self.graph.index_of(id)
+    }
+
+    /// Generates a node ID that no loaded node uses
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - Text to derive the ID from (e.g. the title); random if omitted
+    /// * `domain` - Domain whose conventional prefix the ID starts with
+    ///
+    /// # Errors
+    ///
+    /// Throws if no free ID is left for the domain prefix
+    #[wasm_bindgen(js_name = generateId)]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Math.random() * 2^53 fits in u64
+    pub fn generate_id(
+        &self,
+        content: Option<String>,
+        domain: Option<String>,
+    ) -> Result<String, JsValue> {
+        let strategy = content.map_or_else(
+            || IdStrategy::Random((js_sys::Math::random() * 9_007_199_254_740_992.0) as u64),
+            IdStrategy::Content,
+        );
+        let mut options = IdOptions::new(strategy);
+        if let Some(domain) = domain {
+            options = options.with_domain(&domain);
+        }
+        Ok(self.graph.generate_id(&options)?)
+    }
+
+    /// Returns the edge at a GPU edge buffer index as JSON
+    #[must_use]
+    #[wasm_bindgen(js_name = edgeAtIndex)]
+    pub fn edge_at_index(&self, index: usize) -> Option<String> {
+        edge_record_indices(&self.graph)
+            .get(index)
+            .and_then(|&idx| serde_json::to_string(&self.graph.edges()[idx]).ok())
+    }
+
+    /// Resolves a pixel read back from the pick target
+    ///
+    /// # Returns
+    ///
+    /// JSON `{ "kind": "node" | "edge", "index": n, "item": {...} }`, or
+    /// `undefined` for background pixels
+    #[must_use]
+    #[wasm_bindgen(js_name = resolvePick)]
+    pub fn resolve_pick(&self, r: u8, g: u8, b: u8, a: u8) -> Option<String> {
+        let (kind, index, item) = match decode_pick_color([r, g, b, a])? {
+            PickTarget::Node(index) => ("node", index, self.node_at_index(index)?),
+            PickTarget::Edge(index) => ("edge", index, self.edge_at_index(index)?),
+        };
+
+        Some(format!(
+            r#"{{"kind":"{kind}","index":{index},"item":{item}}}"#
+        ))
+    }
+
+    /// Returns the GPU buffer layout descriptors as JSON
+    ///
+    /// # Returns
+    ///
+    /// JSON object with `nodes` and `edges` layouts: magic, version, stride and
+    /// per-field offset, component count and scalar type
+    #[must_use]
+    #[wasm_bindgen(js_name = getBufferLayout)]
+    pub fn get_buffer_layout() -> String {
+        serde_json::json!({
+            "nodes": node_layout(),
+            "edges": edge_layout(),
+        })
+        .to_string()
+    }
+
     /// Returns node count
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)] // wasm_bindgen cannot export `const fn`
     #[wasm_bindgen(js_name = nodeCount)]
-    pub const fn node_count(&self) -> usize {
+    pub fn node_count(&self) -> usize {
         self.graph.node_count()
     }
 
     /// Returns edge count
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)] // wasm_bindgen cannot export `const fn`
     #[wasm_bindgen(js_name = edgeCount)]
-    pub const fn edge_count(&self) -> usize {
+    pub fn edge_count(&self) -> usize {
         self.graph.edge_count()
     }
 }
 
+impl GraphEngine {
+    /// Returns the GPU cache, building it from the current graph if needed
+    fn gpu_cache(&mut self) -> &mut GpuCache {
+        self.gpu_state().2
+    }
+
+    /// Returns the graph, interaction state and GPU cache
+    ///
+    /// A rebuilt cache gets the interaction state re-applied.
+    fn gpu_state(&mut self) -> (&GraphStore, &mut Interaction, &mut GpuCache) {
+        let Self {
+            graph,
+            style,
+            layout_3d,
+            depths,
+            gpu_cache,
+            interaction,
+            ..
+        } = self;
+        let cache = gpu_cache.get_or_insert_with(|| {
+            let mut cache = GpuCache::build(graph, layout_3d, depths, style);
+            interaction.apply(graph, &mut cache);
+            cache
+        });
+        (graph, interaction, cache)
+    }
+
+    /// Applies part of the interaction state to the GPU cache
+    fn apply_interaction<R>(
+        &mut self,
+        apply: fn(&mut Interaction, &GraphStore, &mut GpuCache) -> R,
+    ) -> R {
+        let (graph, interaction, cache) = self.gpu_state();
+        apply(interaction, graph, cache)
+    }
+
+    fn update_gpu_node(&mut self, id: &str, f: impl FnOnce(&mut GpuNode)) -> bool {
+        let Some(&index) = self.graph.id_to_idx.get(id) else {
+            return false;
+        };
+        self.gpu_cache().nodes.update(index, f)
+    }
+}
+
+/// Converts to a JS `Error` carrying `kind`, `message`, `path`, `line` and
+/// `column` properties alongside the formatted message
+impl From<Error> for JsValue {
+    fn from(err: Error) -> Self {
+        let js_err = js_sys::Error::new(&err.to_string());
+        let location = err.location();
+        let path = location.path.as_ref().map_or(Self::NULL, |path| {
+            Self::from_str(&path.display().to_string())
+        });
+        let line = location.line.map_or(Self::NULL, Self::from);
+        let column = location.column.map_or(Self::NULL, Self::from);
+
+        js_sys::Reflect::set(&js_err, &"kind".into(), &err.kind().into()).ok();
+        js_sys::Reflect::set(&js_err, &"message".into(), &err.message().into()).ok();
+        js_sys::Reflect::set(&js_err, &"path".into(), &path).ok();
+        js_sys::Reflect::set(&js_err, &"line".into(), &line).ok();
+        js_sys::Reflect::set(&js_err, &"column".into(), &column).ok();
+        js_err.into()
+    }
+}
+
+fn patches_to_js(patches: Vec<BufferPatch>) -> js_sys::Array {
+    patches
+        .into_iter()
+        .map(|patch| {
+            let entry = js_sys::Object::new();
+            js_sys::Reflect::set(&entry, &"offset".into(), &JsValue::from(patch.offset)).ok();
+            js_sys::Reflect::set(
+                &entry,
+                &"bytes".into(),
+                &js_sys::Uint8Array::from(&patch.bytes[..]),
+            )
+            .ok();
+            entry
+        })
+        .collect()
+}
+
 // WASM-specific tests
 #[cfg(all(test, target_arch = "wasm32"))]
 mod wasm_tests {
@@ -151,4 +719,17 @@ mod wasm_tests {
 
         assert_eq!(engine.node_count(), 1);
     }
+
+    #[wasm_bindgen_test]
+    fn test_set_node_flags_keeps_foundation_across_rebuild() {
+        let mut engine = GraphEngine::new("").unwrap();
+        let json = r#"[{"id":"abc123","type":"proposition","domain":"philosophy","title":"Test"}]"#;
+        engine.load_nodes_json(json).unwrap();
+
+        assert!(engine.set_node_flags("abc123", 1 << 12));
+        engine.compute_layout_truth_mine();
+
+        let flags = RenderFlags::from_bits(engine.gpu_cache().nodes.records()[0].flags);
+        assert!(flags.contains(RenderFlags::FOUNDATION | RenderFlags::from_bits(1 << 12)));
+    }
 }
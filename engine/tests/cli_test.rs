@@ -0,0 +1,346 @@
+//! Integration tests for the `truth-mines` command-line tool
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn project_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap()
+}
+
+fn truth_mines(root: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_truth-mines"))
+        .arg("--root")
+        .arg(root)
+        .args(args)
+        .output()
+        .expect("Failed to run truth-mines")
+}
+
+fn temp_repository(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("truth_mines_cli_{name}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("nodes")).unwrap();
+    std::fs::create_dir_all(root.join("edges")).unwrap();
+    root
+}
+
+#[test]
+fn test_validate_project_repository() {
+    let output = truth_mines(project_root(), &["validate", "--json"]);
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["valid"], true);
+    assert!(report["nodes"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn test_validate_reports_broken_edges() {
+    let root = temp_repository("validate");
+    std::fs::write(
+        root.join("nodes/a.json"),
+        r#"{"id":"a","type":"axiom","domain":"mathematics","title":"A"}"#,
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("edges/supports.jsonl"),
+        "{\"f\":\"a\",\"t\":\"missing\",\"relation\":\"supports\"}\n",
+    )
+    .unwrap();
+
+    let output = truth_mines(&root, &["validate"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("supports.jsonl:1"), "{stdout}");
+    assert!(stdout.contains("missing"), "{stdout}");
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_stats_json() {
+    let output = truth_mines(project_root(), &["--json", "stats"]);
+    assert!(output.status.success());
+
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(stats["total_nodes"].as_u64().unwrap() > 0);
+    assert!(stats["max_depth"].is_u64());
+}
+
+#[test]
+fn test_unknown_node_fails() {
+    let output = truth_mines(project_root(), &["neighbors", "no-such-node"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown node 'no-such-node'"));
+}
+
+#[test]
+fn test_export_jsonl_round_trips() {
+    let root = temp_repository("export");
+    let output_path = root.join("edges.jsonl");
+    let output = truth_mines(
+        project_root(),
+        &[
+            "export",
+            "--format",
+            "jsonl",
+            "-o",
+            output_path.to_str().unwrap(),
+        ],
+    );
+    assert!(output.status.success());
+
+    let content = std::fs::read_to_string(&output_path).unwrap();
+    let edges = truth_mines_engine::parsers::toon::parse_jsonl(&content).unwrap();
+    assert!(!edges.is_empty());
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_export_graphml_domain_subgraph() {
+    let output = truth_mines(
+        project_root(),
+        &[
+            "export",
+            "--format",
+            "graphml",
+            "--domain",
+            "physics",
+            "--positions",
+        ],
+    );
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("<?xml"));
+    assert!(stdout.contains("attr.name=\"depth\""));
+    assert!(stdout.contains("<data key=\"x\">"));
+    assert!(stdout.contains("<data key=\"n1\">physics</data>"));
+    assert!(!stdout.contains("<data key=\"n1\">mathematics</data>"));
+}
+
+#[test]
+fn test_lint_uses_config_severities() {
+    let root = temp_repository("lint");
+    std::fs::write(
+        root.join("nodes/phil01.json"),
+        r#"{"id":"phil01","type":"concept","domain":"philosophy","title":"P"}"#,
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("nodes/math01.json"),
+        r#"{"id":"math01","type":"theorem","domain":"mathematics","title":"M"}"#,
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("edges/formalizes.jsonl"),
+        "{\"f\":\"phil01\",\"t\":\"math01\",\"relation\":\"formalizes\",\"domain\":\"philosophy\"}\n",
+    )
+    .unwrap();
+
+    let output = truth_mines(&root, &["--json", "lint"]);
+    assert_eq!(output.status.code(), Some(1));
+    let diagnostics: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(diagnostics[0]["rule"], "cross-domain-bridge");
+    assert_eq!(diagnostics[0]["severity"], "error");
+
+    std::fs::write(
+        root.join("lint.toml"),
+        "[rules]\ncross-domain-bridge = \"warning\"\n",
+    )
+    .unwrap();
+    let output = truth_mines(&root, &["lint"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("warning[cross-domain-bridge] phil01"));
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_fmt_check_project_repository() {
+    let output = truth_mines(project_root(), &["fmt", "--check"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
+
+#[test]
+fn test_new_id_is_unused_and_prefixed() {
+    let output = truth_mines(
+        project_root(),
+        &["--json", "new-id", "--domain", "physics", "--count", "5"],
+    );
+    assert!(output.status.success());
+
+    let ids: Vec<String> = serde_json::from_slice(&output.stdout).unwrap();
+    let graph = truth_mines_engine::GraphStore::load_repository(project_root()).unwrap();
+    assert_eq!(ids.len(), 5);
+    for id in &ids {
+        assert!(id.starts_with("py"));
+        assert!(truth_mines_engine::graph::ids::is_valid_id(id));
+        assert!(graph.get_node(id).is_none());
+    }
+}
+
+#[test]
+fn test_merge_rewrites_repository_files() {
+    let root = temp_repository("merge");
+    for (id, tags) in [
+        ("aaa111", "[\"x\"]"),
+        ("bbb222", "[\"y\"]"),
+        ("ccc333", "[]"),
+    ] {
+        std::fs::write(
+            root.join(format!("nodes/{id}.json")),
+            format!(r#"{{"id":"{id}","type":"concept","domain":"philosophy","title":"{id}","tags":{tags}}}"#),
+        )
+        .unwrap();
+    }
+    std::fs::write(
+        root.join("edges/supports.jsonl"),
+        "{\"f\":\"aaa111\",\"t\":\"ccc333\",\"relation\":\"supports\",\"w\":0.4,\"domain\":\"philosophy\"}\n\
+         {\"f\":\"bbb222\",\"t\":\"ccc333\",\"relation\":\"supports\",\"w\":0.8,\"domain\":\"philosophy\"}\n",
+    )
+    .unwrap();
+
+    let output = truth_mines(&root, &["merge", "aaa111", "bbb222", "--dry-run"]);
+    assert!(output.status.success());
+    assert!(root.join("nodes/bbb222.json").exists());
+
+    let output = truth_mines(&root, &["merge", "aaa111", "bbb222", "--json"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        report["changes"]["removed_nodes"],
+        serde_json::json!(["bbb222"])
+    );
+
+    assert!(!root.join("nodes/bbb222.json").exists());
+    let edges = std::fs::read_to_string(root.join("edges/supports.jsonl")).unwrap();
+    assert_eq!(
+        edges,
+        "{\"f\":\"aaa111\",\"t\":\"ccc333\",\"relation\":\"supports\",\"w\":0.8,\"domain\":\"philosophy\"}\n"
+    );
+
+    let output = truth_mines(&root, &["rename", "aaa111", "ddd444"]);
+    assert!(output.status.success());
+    let node = std::fs::read_to_string(root.join("nodes/ddd444.json")).unwrap();
+    assert!(node.contains("\"tags\": [\"x\", \"y\"]"), "{node}");
+    assert!(truth_mines(&root, &["validate"]).status.success());
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_split_assigns_edges_by_neighbor() {
+    let root = temp_repository("split");
+    for id in ["aaa111", "bbb222", "ccc333"] {
+        std::fs::write(
+            root.join(format!("nodes/{id}.json")),
+            format!(r#"{{"id":"{id}","type":"concept","domain":"philosophy","title":"{id}"}}"#),
+        )
+        .unwrap();
+    }
+    std::fs::write(
+        root.join("edges/supports.jsonl"),
+        "{\"f\":\"aaa111\",\"t\":\"bbb222\",\"relation\":\"supports\",\"domain\":\"philosophy\"}\n\
+         {\"f\":\"bbb222\",\"t\":\"ccc333\",\"relation\":\"supports\",\"domain\":\"philosophy\"}\n",
+    )
+    .unwrap();
+
+    let split = [
+        "split",
+        "bbb222",
+        "--part",
+        "ddd444=Left",
+        "--part",
+        "eee555=Right",
+    ];
+    let output = truth_mines(&root, &split);
+    assert_eq!(output.status.code(), Some(1), "unassigned edges");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--assign aaa111=PART"), "{stderr}");
+
+    let output = truth_mines(
+        &root,
+        &[
+            &split[..],
+            &["--assign", "aaa111=ddd444", "--assign", "ccc333=2"],
+        ]
+        .concat(),
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert!(!root.join("nodes/bbb222.json").exists());
+    let right = std::fs::read_to_string(root.join("nodes/eee555.json")).unwrap();
+    assert!(right.contains("\"title\": \"Right\""), "{right}");
+    let edges = std::fs::read_to_string(root.join("edges/supports.jsonl")).unwrap();
+    assert!(
+        edges.contains("\"f\":\"aaa111\",\"t\":\"ddd444\""),
+        "{edges}"
+    );
+    assert!(
+        edges.contains("\"f\":\"eee555\",\"t\":\"ccc333\""),
+        "{edges}"
+    );
+    assert!(truth_mines(&root, &["validate"]).status.success());
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_diff_reports_changes_and_impact() {
+    let before = temp_repository("diff_before");
+    let after = temp_repository("diff_after");
+    for (root, title) in [(&before, "Old"), (&after, "New")] {
+        for id in ["aaa111", "bbb222"] {
+            std::fs::write(
+                root.join(format!("nodes/{id}.json")),
+                format!(
+                    r#"{{"id":"{id}","type":"concept","domain":"philosophy","title":"{title}"}}"#
+                ),
+            )
+            .unwrap();
+        }
+    }
+    std::fs::write(
+        before.join("edges/supports.jsonl"),
+        "{\"f\":\"aaa111\",\"t\":\"bbb222\",\"relation\":\"supports\",\"domain\":\"philosophy\"}\n",
+    )
+    .unwrap();
+
+    let args = ["diff", before.to_str().unwrap(), after.to_str().unwrap()];
+    let output = truth_mines(&after, &args);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("title: \"Old\" -> \"New\""), "{stdout}");
+    assert!(stdout.contains("- aaa111 -[supports]-> bbb222"), "{stdout}");
+    assert!(stdout.contains("foundation +  bbb222"), "{stdout}");
+
+    let output = truth_mines(&after, &[&args[..], &["--json"]].concat());
+    let diff: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(diff["modified_nodes"].as_array().unwrap().len(), 2);
+    assert_eq!(diff["removed_edges"][0]["f"], "aaa111");
+    assert_eq!(diff["depth_changes"][0]["id"], "bbb222");
+
+    let output = truth_mines(&after, &["diff", before.to_str().unwrap()]);
+    assert_eq!(
+        output.stdout,
+        truth_mines(&after, &args).stdout,
+        "after defaults to --root"
+    );
+
+    std::fs::remove_dir_all(&before).unwrap();
+    std::fs::remove_dir_all(&after).unwrap();
+}
@@ -4,7 +4,7 @@ use std::path::Path;
 use truth_mines_engine::{
     graph::GraphStore,
     layout::{depth::compute_depths, truth_mine::compute_truth_mine_layout},
-    parsers::{json::load_nodes_from_dir, toon::parse_toon},
+    parsers::json::load_nodes_from_dir,
     style::StyleConfig,
 };
 
@@ -57,7 +57,7 @@ fn test_full_pipeline_with_sample_graph() {
     assert_eq!(layout.len(), graph.node_count());
 
     // Verify layout positions are valid
-    for (_id, pos) in &layout {
+    for pos in layout.values() {
         assert!(pos[0].is_finite());
         assert!(pos[1].is_finite());
         assert!(pos[2].is_finite());
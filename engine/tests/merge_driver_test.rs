@@ -0,0 +1,93 @@
+//! Integration tests for the `truth-mines-merge` git merge driver
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("truth_mines_merge_{name}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Writes the three versions and runs the driver as git would
+fn merge_driver(dir: &Path, path: &str, base: &str, ours: &str, theirs: &str) -> Output {
+    for (name, content) in [("base", base), ("ours", ours), ("theirs", theirs)] {
+        std::fs::write(dir.join(name), content).unwrap();
+    }
+    Command::new(env!("CARGO_BIN_EXE_truth-mines-merge"))
+        .args([dir.join("base"), dir.join("ours"), dir.join("theirs")])
+        .arg(path)
+        .output()
+        .expect("Failed to run truth-mines-merge")
+}
+
+#[test]
+fn test_clean_node_merge_is_written_over_ours() {
+    let dir = temp_dir("node");
+    let node = |title: &str, content: &str| {
+        format!(
+            r#"{{"id":"aaa111","type":"concept","domain":"philosophy","title":"{title}","content":"{content}"}}"#
+        )
+    };
+
+    let output = merge_driver(
+        &dir,
+        "nodes/aaa111.json",
+        &node("Title", "Content"),
+        &node("Title", "Our content"),
+        &node("Their title", "Content"),
+    );
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let merged = std::fs::read_to_string(dir.join("ours")).unwrap();
+    assert!(merged.contains("\"title\": \"Their title\""), "{merged}");
+    assert!(merged.contains("\"content\": \"Our content\""), "{merged}");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_weight_conflict_fails_and_marks_both_sides() {
+    let dir = temp_dir("edges");
+    let edge = |w: &str| {
+        format!("{{\"f\":\"aaa111\",\"t\":\"bbb222\",\"relation\":\"supports\",\"w\":{w},\"domain\":\"philosophy\"}}\n")
+    };
+
+    let output = merge_driver(
+        &dir,
+        "edges/supports.jsonl",
+        &edge("0.5"),
+        &edge("0.7"),
+        &edge("0.9"),
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("w changed on both sides"), "{stderr}");
+    assert_eq!(
+        std::fs::read_to_string(dir.join("ours")).unwrap(),
+        format!(
+            "<<<<<<< ours\n{}=======\n{}>>>>>>> theirs\n",
+            edge("0.7"),
+            edge("0.9")
+        )
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_unknown_file_type_is_refused() {
+    let dir = temp_dir("toon");
+    let output = merge_driver(&dir, "edges/supports.toon", "", "ours", "theirs");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(std::fs::read_to_string(dir.join("ours")).unwrap(), "ours");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -12,6 +12,8 @@ from collections import defaultdict
 from pathlib import Path
 from typing import Any, Dict, List
 
+from toon import toon_cell
+
 
 def load_edges(edges_dir: Path) -> Dict[str, List[Dict[str, Any]]]:
     """
@@ -81,9 +83,9 @@ def generate_toon(edges_by_relation: Dict[str, List[Dict[str, Any]]]) -> str:
             if has_weights:
                 # Include weight (or empty if missing)
                 weight_str = str(weight) if weight is not None else ""
-                row = f"{from_id},{to_id},{weight_str},{domain}"
+                row = ",".join(toon_cell(v) for v in (from_id, to_id, weight_str, domain))
             else:
-                row = f"{from_id},{to_id},{domain}"
+                row = ",".join(toon_cell(v) for v in (from_id, to_id, domain))
 
             toon_lines.append(row)
 
@@ -12,6 +12,8 @@ from collections import deque
 from pathlib import Path
 from typing import Any, Dict, List, Set
 
+from toon import toon_cell
+
 
 def load_graph(nodes_dir: Path, edges_dir: Path) -> tuple[Dict[str, Any], List[Dict[str, Any]]]:
     """Load all nodes and edges from directories."""
@@ -90,7 +92,9 @@ def generate_toon_pack(
     lines.append(f"nodes[{len(node_ids)}]{{id,type,domain,title}}:")
     for node_id in sorted(node_ids):
         node = nodes_map[node_id]
-        lines.append(f"{node['id']},{node['type']},{node['domain']},{node['title']}")
+        lines.append(
+            ",".join(toon_cell(node[key]) for key in ("id", "type", "domain", "title"))
+        )
     lines.append("")
 
     # Edges grouped by relation
@@ -105,11 +109,13 @@ def generate_toon_pack(
             lines.append(f"{relation}[{len(rel_edges)}]{{f,t,w,domain}}:")
             for edge in rel_edges:
                 w = edge.get("w", "")
-                lines.append(f"{edge['f']},{edge['t']},{w},{edge['domain']}")
+                cells = (edge["f"], edge["t"], w, edge["domain"])
+                lines.append(",".join(toon_cell(v) for v in cells))
         else:
             lines.append(f"{relation}[{len(rel_edges)}]{{f,t,domain}}:")
             for edge in rel_edges:
-                lines.append(f"{edge['f']},{edge['t']},{edge['domain']}")
+                cells = (edge["f"], edge["t"], edge["domain"])
+                lines.append(",".join(toon_cell(v) for v in cells))
         lines.append("")
 
     return "\n".join(lines)
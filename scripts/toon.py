@@ -0,0 +1,27 @@
+"""
+Shared TOON helpers for Truth Mines scripts.
+
+Mirrors the cell quoting rules of the Rust TOON grammar
+(engine/src/parsers/toon.rs) so that every script writes cells the engine
+reads back unchanged.
+"""
+
+from typing import Any
+
+
+def toon_cell(value: Any) -> str:
+    """Quote a TOON cell if it would not read back unchanged when bare."""
+    text = "" if value is None else str(value)
+    needs_quotes = (
+        any(c in text for c in ',"\n\r{') or text.startswith("[") or text != text.strip()
+    )
+    if not needs_quotes:
+        return text
+    escaped = (
+        text.replace("\\", "\\\\")
+        .replace('"', '\\"')
+        .replace("\n", "\\n")
+        .replace("\r", "\\r")
+        .replace("\t", "\\t")
+    )
+    return f'"{escaped}"'
@@ -46,6 +46,9 @@ export interface Manifest {
     total_nodes: number;
     by_domain: Record<string, number>;
     by_type: Record<string, number>;
+    /** Present when built by the Rust `build_index` binary */
+    total_edges?: number;
+    by_relation?: Record<string, number>;
   };
 }
 
@@ -57,6 +60,15 @@ export type GraphSummary = Array<{
   type: string;
   domain: string;
   title: string;
+  /** Precomputed by the Rust `build_index` binary */
+  depth?: number;
+  position?: [number, number, number];
+  metrics?: {
+    load_bearing: number;
+    in_degree: number;
+    out_degree: number;
+    community: string;
+  };
 }>;
 
 /**